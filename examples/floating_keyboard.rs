@@ -0,0 +1,82 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+use bevy_steamworks::*;
+
+/// A text field that opens the floating keyboard when clicked.
+#[derive(Component)]
+struct NameField;
+
+fn setup(mut commands: Commands) {
+    commands.spawn(Camera2dBundle::default());
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    NameField,
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Px(400.0),
+                            height: Val::Px(50.0),
+                            ..default()
+                        },
+                        background_color: Color::srgb(0.2, 0.2, 0.2).into(),
+                        ..default()
+                    },
+                ))
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        "Player name",
+                        TextStyle::default(),
+                    ));
+                });
+        });
+}
+
+fn open_keyboard(
+    text_input: Res<SteamTextInput>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    fields: Query<(&Interaction, &Node, &GlobalTransform), (Changed<Interaction>, With<NameField>)>,
+) {
+    let scale_factor = windows.single().scale_factor();
+    for (interaction, node, transform) in &fields {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        // The node's rect in logical window coordinates.
+        let rect = Rect::from_center_size(transform.translation().truncate(), node.size());
+        if !text_input.show_floating_keyboard(
+            FloatingGamepadTextInputMode::SingleLine,
+            rect,
+            scale_factor,
+        ) {
+            info!("The floating keyboard is unavailable, use a physical keyboard instead");
+        }
+    }
+}
+
+fn keyboard_dismissed(mut dismissed: EventReader<FloatingKeyboardDismissed>) {
+    for _ in dismissed.read() {
+        // Return focus to the game's own UI here.
+        info!("Floating keyboard dismissed");
+    }
+}
+
+fn main() {
+    // Use the demo Steam AppId for SpaceWar
+    App::new()
+        // it is important to add the plugin before `RenderPlugin` that comes with `DefaultPlugins`
+        .add_plugins(SteamworksPlugin::init_app(480).unwrap())
+        .add_plugins(DefaultPlugins)
+        .add_systems(Startup, setup)
+        .add_systems(Update, (open_keyboard, keyboard_dismissed))
+        .run();
+}
@@ -0,0 +1,28 @@
+//! Calls a Steamworks API that neither bevy_steamworks nor steamworks wrap.
+//!
+//! Run with `cargo run --example raw_sdk_call --features raw-sys`.
+
+use bevy::prelude::*;
+use bevy_steamworks::*;
+
+fn print_ipc_calls(client: Res<Client>) {
+    let calls = client.with_raw(|_| {
+        // `with_raw` holds off Steam callbacks until this closure returns, so the
+        // raw call can't interleave with them. What remains is the SDK's own
+        // contract for the function being called.
+        //
+        // SAFETY: The Steam API is initialized for as long as a `Client` exists,
+        // and `GetIPCCallCount` only reads a counter kept by the Steam client.
+        unsafe { sys::SteamAPI_ISteamUtils_GetIPCCallCount(sys::SteamAPI_SteamUtils_v010()) }
+    });
+    info!("{calls} calls have been made to the Steam client since last checked");
+}
+
+fn main() {
+    App::new()
+        // it is important to add the plugin before `RenderPlugin` that comes with `DefaultPlugins`
+        .add_plugins(SteamworksPlugin::init_app(480).unwrap())
+        .add_plugins(DefaultPlugins)
+        .add_systems(Startup, print_ipc_calls)
+        .run();
+}
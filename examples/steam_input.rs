@@ -0,0 +1,66 @@
+use bevy::prelude::*;
+use bevy_steamworks::*;
+
+const MOVE_SPEED: f32 = 300.0;
+const TURN_SPEED: f32 = 3.0;
+
+#[derive(Component)]
+struct Player;
+
+fn setup(
+    mut commands: Commands,
+    mut action_sets: ResMut<SteamInputActionSets>,
+    mut analog: ResMut<SteamAnalogActions>,
+    mut digital: ResMut<SteamDigitalActions>,
+) {
+    // These names must match the game's Steam Input action manifest.
+    action_sets.set_active(ALL_STEAM_CONTROLLERS, "InGameControls");
+    analog.register("Move");
+    analog.register("Camera");
+    // The camera stick drifts more on some controllers.
+    analog.set_deadzone("Camera", 0.2);
+    digital.register("Jump");
+
+    commands.spawn(Camera2dBundle::default());
+    commands.spawn((
+        Player,
+        SpriteBundle {
+            sprite: Sprite {
+                custom_size: Some(Vec2::new(40.0, 60.0)),
+                ..default()
+            },
+            ..default()
+        },
+    ));
+}
+
+fn move_player(
+    time: Res<Time>,
+    analog: Res<SteamAnalogActions>,
+    digital: Res<SteamDigitalActions>,
+    mut players: Query<&mut Transform, With<Player>>,
+) {
+    let movement = analog.value("Move");
+    let camera = analog.value("Camera");
+    for mut transform in &mut players {
+        transform.translation += movement.extend(0.0) * MOVE_SPEED * time.delta_seconds();
+        transform.rotate_z(-camera.x * TURN_SPEED * time.delta_seconds());
+        if digital.just_pressed("Jump") {
+            transform.scale = Vec3::splat(1.2);
+        } else if digital.just_released("Jump") {
+            transform.scale = Vec3::ONE;
+        }
+    }
+}
+
+fn main() {
+    // Use the demo Steam AppId for SpaceWar
+    App::new()
+        // it is important to add the plugin before `RenderPlugin` that comes with `DefaultPlugins`
+        .add_plugins(SteamworksPlugin::init_app(480).unwrap())
+        .add_plugins(DefaultPlugins)
+        .add_plugins(SteamInputPlugin)
+        .add_systems(Startup, setup)
+        .add_systems(Update, move_player)
+        .run();
+}
@@ -0,0 +1,75 @@
+use std::sync::{Arc, Mutex};
+
+use bevy::{prelude::*, render::view::screenshot::ScreenshotManager, window::PrimaryWindow};
+use bevy_steamworks::*;
+
+/// Frames captured by the render world, waiting to be written to Steam.
+#[derive(Resource, Default, Clone)]
+struct Captures(Arc<Mutex<Vec<Image>>>);
+
+fn setup(mut commands: Commands, screenshots: Res<SteamScreenshots>) {
+    // Pressing the Steam screenshot key now sends `ScreenshotRequested` instead of
+    // capturing the raw framebuffer.
+    if let Err(err) = screenshots.hook(true) {
+        warn!("Failed to hook screenshots: {}", err);
+    }
+    commands.spawn(Camera2dBundle::default());
+    commands.spawn(SpriteBundle {
+        sprite: Sprite {
+            color: Color::srgb(0.3, 0.5, 0.9),
+            custom_size: Some(Vec2::splat(200.0)),
+            ..default()
+        },
+        ..default()
+    });
+}
+
+fn capture_on_request(
+    mut requested: EventReader<ScreenshotRequested>,
+    mut screenshot_manager: ResMut<ScreenshotManager>,
+    window: Query<Entity, With<PrimaryWindow>>,
+    captures: Res<Captures>,
+) {
+    if requested.read().next().is_none() {
+        return;
+    }
+    let Ok(window) = window.get_single() else {
+        return;
+    };
+    let captures = captures.clone();
+    let result = screenshot_manager.take_screenshot(window, move |image| {
+        captures.0.lock().unwrap().push(image);
+    });
+    if result.is_err() {
+        warn!("A screenshot is already being taken");
+    }
+}
+
+fn write_captures(captures: Res<Captures>, mut screenshots: ResMut<SteamScreenshots>) {
+    for image in captures.0.lock().unwrap().drain(..) {
+        if let Err(err) = screenshots.write_image(&image) {
+            warn!("Failed to save screenshot: {}", err);
+        }
+    }
+}
+
+fn report_written(mut written: EventReader<ScreenshotWritten>) {
+    for written in written.read() {
+        match written.result {
+            Ok(handle) => info!("Saved screenshot {} to the Steam library", handle),
+            Err(err) => warn!("Failed to save screenshot: {}", err),
+        }
+    }
+}
+
+fn main() {
+    // Use the demo Steam AppId for SpaceWar
+    App::new()
+        // it is important to add the plugin before `RenderPlugin` that comes with `DefaultPlugins`
+        .add_plugins(SteamworksPlugin::init_app(480).unwrap())
+        .add_plugins(DefaultPlugins)
+        .init_resource::<Captures>()
+        .add_systems(Startup, setup)
+        .add_systems(Update, (capture_on_request, write_captures, report_written))
+        .run();
+}
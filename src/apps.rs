@@ -0,0 +1,36 @@
+mod dlc;
+mod info;
+mod language;
+mod launch;
+
+pub use dlc::*;
+pub use info::*;
+pub use language::*;
+pub use launch::*;
+
+use bevy_app::{App, First, Startup};
+use bevy_ecs::schedule::IntoSystemConfigs;
+
+use crate::{shutdown::steam_running, Client, SteamworksSystem};
+
+pub(crate) fn plugin(app: &mut App) {
+    let client = app.world().resource::<Client>();
+    let dlc = SteamDlc::new(client);
+    let info = SteamAppInfo::new(client);
+    let language = SteamLanguage::new(client);
+    let launch = SteamLaunch::new(client);
+    app.insert_resource(dlc)
+        .insert_resource(info)
+        .insert_resource(language)
+        .insert_resource(launch)
+        .add_event::<DlcInstalled>()
+        .add_event::<FamilySharingBorrowed>()
+        .add_event::<LaunchParameters>()
+        .add_systems(
+            First,
+            (dlc::flush_installed_dlc, launch::flush_launch_parameters)
+                .after(SteamworksSystem::RunCallbacks)
+                .run_if(steam_running),
+        )
+        .add_systems(Startup, info::detect_family_sharing);
+}
@@ -0,0 +1,178 @@
+use std::{
+    ffi::{c_void, CStr},
+    os::raw::c_char,
+};
+
+use bevy_ecs::{
+    event::{Event, EventWriter},
+    system::{ResMut, Resource},
+};
+use steamworks::{sys, AppId, Callback};
+
+use crate::{call_result::CallResults, shutdown::RegisteredCallback, Client, ShuttingDown};
+
+/// Sent when a DLC finishes installing.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct DlcInstalled {
+    /// The app ID of the DLC.
+    pub app_id: AppId,
+}
+
+/// `DlcInstalled_t`, which steamworks does not wrap.
+struct DlcInstalledCallback(AppId);
+
+unsafe impl Callback for DlcInstalledCallback {
+    const ID: i32 = 1005;
+    const SIZE: i32 = std::mem::size_of::<sys::DlcInstalled_t>() as i32;
+
+    unsafe fn from_raw(raw: *mut c_void) -> Self {
+        let val = &mut *(raw as *mut sys::DlcInstalled_t);
+        Self(AppId(val.m_nAppID))
+    }
+}
+
+/// A DLC of the app, as listed by [`SteamDlc::list`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DlcInfo {
+    /// The app ID of the DLC.
+    pub app_id: AppId,
+    /// The name of the DLC.
+    pub name: String,
+    /// Whether the DLC is available in the store.
+    pub available: bool,
+    /// Whether the DLC is installed.
+    pub installed: bool,
+    /// Whether the user owns the DLC.
+    pub owned: bool,
+}
+
+/// Lists the app's DLC and installs or uninstalls optional DLC.
+///
+/// The list is read when the plugin is built and refreshed whenever a DLC
+/// finishes installing, which is also announced with a [`DlcInstalled`] event.
+#[derive(Resource)]
+pub struct SteamDlc {
+    client: Client,
+    dlc: Vec<DlcInfo>,
+    installed: CallResults<AppId>,
+    _installed_callback: RegisteredCallback,
+}
+
+impl SteamDlc {
+    pub(super) fn new(client: &Client) -> Self {
+        let installed = CallResults::new();
+        let installed_in = installed.clone();
+        let installed_callback = RegisteredCallback::new(client.register_callback(
+            move |DlcInstalledCallback(app_id)| {
+                installed_in.push(app_id);
+            },
+        ));
+        let mut dlc = Self {
+            client: client.clone(),
+            dlc: Vec::new(),
+            installed,
+            _installed_callback: installed_callback,
+        };
+        dlc.refresh();
+        dlc
+    }
+
+    /// Gets every DLC of the app.
+    pub fn list(&self) -> &[DlcInfo] {
+        &self.dlc
+    }
+
+    /// Reads the list of DLC from Steam again. Keeps the last list once Steam is
+    /// shutting down.
+    pub fn refresh(&mut self) {
+        if self.client.is_shutting_down() {
+            return;
+        }
+        let apps = self.client.apps();
+        // steamworks does not wrap the DLC enumeration, so it is called directly.
+        // SAFETY: The Steam API is initialized for as long as a `Client` exists.
+        unsafe {
+            let steam_apps = sys::SteamAPI_SteamApps_v008();
+            let count = sys::SteamAPI_ISteamApps_GetDLCCount(steam_apps);
+            self.dlc.clear();
+            for index in 0..count {
+                let mut app_id = 0;
+                let mut available = false;
+                let mut name: [c_char; 128] = [0; 128];
+                let found = sys::SteamAPI_ISteamApps_BGetDLCDataByIndex(
+                    steam_apps,
+                    index,
+                    &mut app_id,
+                    &mut available,
+                    name.as_mut_ptr(),
+                    name.len() as _,
+                );
+                if !found {
+                    continue;
+                }
+                let app_id = AppId(app_id);
+                self.dlc.push(DlcInfo {
+                    app_id,
+                    name: CStr::from_ptr(name.as_ptr()).to_string_lossy().into_owned(),
+                    available,
+                    installed: apps.is_dlc_installed(app_id),
+                    owned: apps.is_subscribed_app(app_id),
+                });
+            }
+        }
+    }
+
+    /// Starts installing an optional DLC the user owns.
+    ///
+    /// Completes with a [`DlcInstalled`] event. Progress can be polled with
+    /// [`SteamDlc::download_progress`].
+    pub fn install(&self, app_id: AppId) -> Result<(), ShuttingDown> {
+        self.client.ensure_running()?;
+        // SAFETY: The Steam API is initialized for as long as a `Client` exists.
+        unsafe { sys::SteamAPI_ISteamApps_InstallDLC(sys::SteamAPI_SteamApps_v008(), app_id.0) }
+        Ok(())
+    }
+
+    /// Uninstalls an optional DLC.
+    pub fn uninstall(&mut self, app_id: AppId) -> Result<(), ShuttingDown> {
+        self.client.ensure_running()?;
+        // SAFETY: The Steam API is initialized for as long as a `Client` exists.
+        unsafe { sys::SteamAPI_ISteamApps_UninstallDLC(sys::SteamAPI_SteamApps_v008(), app_id.0) }
+        if let Some(dlc) = self.dlc.iter_mut().find(|dlc| dlc.app_id == app_id) {
+            dlc.installed = false;
+        }
+        Ok(())
+    }
+
+    /// Gets the bytes downloaded so far and the total bytes of a DLC being
+    /// installed, or `None` if it isn't downloading or Steam is shutting down.
+    pub fn download_progress(&self, app_id: AppId) -> Option<(u64, u64)> {
+        if self.client.is_shutting_down() {
+            return None;
+        }
+        let mut downloaded = 0;
+        let mut total = 0;
+        // SAFETY: The Steam API is initialized for as long as a `Client` exists.
+        let downloading = unsafe {
+            sys::SteamAPI_ISteamApps_GetDlcDownloadProgress(
+                sys::SteamAPI_SteamApps_v008(),
+                app_id.0,
+                &mut downloaded,
+                &mut total,
+            )
+        };
+        downloading.then_some((downloaded, total))
+    }
+}
+
+pub(super) fn flush_installed_dlc(
+    mut dlc: ResMut<SteamDlc>,
+    mut installed: EventWriter<DlcInstalled>,
+) {
+    let apps = dlc.installed.drain();
+    if apps.is_empty() {
+        return;
+    }
+    dlc.refresh();
+    installed.send_batch(apps.into_iter().map(|app_id| DlcInstalled { app_id }));
+}
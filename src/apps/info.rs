@@ -0,0 +1,112 @@
+use std::path::PathBuf;
+
+use bevy_ecs::{
+    event::{Event, EventWriter},
+    system::{Res, Resource},
+};
+use steamworks::{sys, SteamId};
+
+use crate::Client;
+
+/// Sent at startup when the app is borrowed through Family Sharing.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct FamilySharingBorrowed {
+    /// The user who lent the app.
+    pub lender: SteamId,
+}
+
+/// Information about the running app and how the user owns it, for diagnostics
+/// screens and crash reports.
+///
+/// Read when the plugin is built. The build ID and beta branch change when an
+/// update is applied while the game is running, so call
+/// [`SteamAppInfo::refresh`] before reporting them.
+#[derive(Resource, Clone)]
+pub struct SteamAppInfo {
+    client: Client,
+    /// The build ID of the installed version of the app.
+    pub build_id: i32,
+    /// The folder the app is installed into.
+    pub install_dir: PathBuf,
+    /// The name of the beta branch the user is on, if any.
+    pub beta_name: Option<String>,
+    /// The user who owns the app. This differs from the current user when the app
+    /// is borrowed through Family Sharing.
+    pub owner: SteamId,
+    /// When the app was first purchased, as a Unix timestamp. 0 if it wasn't purchased.
+    pub purchase_time: u32,
+    /// Whether the user owns or has borrowed the app.
+    pub is_subscribed: bool,
+    /// Whether the app is borrowed through Family Sharing.
+    pub is_subscribed_from_family_sharing: bool,
+    /// Whether the user is in a cybercafe.
+    pub is_cybercafe: bool,
+    /// Whether the user owns the low violence version of the app.
+    pub is_low_violence: bool,
+}
+
+impl SteamAppInfo {
+    pub(super) fn new(client: &Client) -> Self {
+        let mut info = Self {
+            client: client.clone(),
+            build_id: 0,
+            install_dir: PathBuf::new(),
+            beta_name: None,
+            owner: SteamId::from_raw(0),
+            purchase_time: 0,
+            is_subscribed: false,
+            is_subscribed_from_family_sharing: false,
+            is_cybercafe: false,
+            is_low_violence: false,
+        };
+        info.refresh();
+        info
+    }
+
+    /// Checks if the app is borrowed through Family Sharing rather than owned by
+    /// the current user. Returns `false` once Steam is shutting down.
+    pub fn is_borrowed(&self) -> bool {
+        !self.client.is_shutting_down() && self.owner != self.client.user().steam_id()
+    }
+
+    /// Gets the user who lent the app through Family Sharing, or `None` if the
+    /// current user owns it or Steam is shutting down.
+    pub fn lender(&self) -> Option<SteamId> {
+        self.is_borrowed().then_some(self.owner)
+    }
+
+    /// Reads every value from Steam again. Keeps the last values once Steam is
+    /// shutting down.
+    pub fn refresh(&mut self) {
+        if self.client.is_shutting_down() {
+            return;
+        }
+        let apps = self.client.apps();
+        let app_id = self.client.utils().app_id();
+        self.build_id = apps.app_build_id();
+        self.install_dir = PathBuf::from(apps.app_install_dir(app_id));
+        self.beta_name = apps.current_beta_name();
+        self.owner = apps.app_owner();
+        // steamworks does not wrap GetEarliestPurchaseUnixTime, so it is called directly.
+        // SAFETY: The Steam API is initialized for as long as a `Client` exists.
+        self.purchase_time = unsafe {
+            sys::SteamAPI_ISteamApps_GetEarliestPurchaseUnixTime(
+                sys::SteamAPI_SteamApps_v008(),
+                app_id.0,
+            )
+        };
+        self.is_subscribed = apps.is_subscribed();
+        self.is_subscribed_from_family_sharing = apps.is_subscribed_from_family_sharing();
+        self.is_cybercafe = apps.is_cybercafe();
+        self.is_low_violence = apps.is_low_violence();
+    }
+}
+
+pub(super) fn detect_family_sharing(
+    info: Res<SteamAppInfo>,
+    mut borrowed: EventWriter<FamilySharingBorrowed>,
+) {
+    if let Some(lender) = info.lender() {
+        borrowed.send(FamilySharingBorrowed { lender });
+    }
+}
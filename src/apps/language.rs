@@ -0,0 +1,77 @@
+use bevy_ecs::system::Resource;
+
+use crate::Client;
+
+/// The languages Steam is configured to use, read when the plugin is built.
+///
+/// Changing the game's language in Steam only takes effect the next time the
+/// game is launched, so these values don't change while running.
+#[derive(Resource, Debug, Clone)]
+pub struct SteamLanguage {
+    /// The API language code of the language the game should be displayed in,
+    /// such as `english` or `schinese`.
+    pub current_game_language: String,
+    /// The API language code of the language the Steam client is displayed in.
+    pub ui_language: String,
+    /// The API language codes of every language the game supports.
+    pub available_languages: Vec<String>,
+}
+
+impl SteamLanguage {
+    pub(super) fn new(client: &Client) -> Self {
+        let apps = client.apps();
+        Self {
+            current_game_language: apps.current_game_language(),
+            ui_language: client.utils().ui_language(),
+            available_languages: apps.available_game_languages(),
+        }
+    }
+
+    /// Gets the BCP 47 language tag of the game's language, such as `en` or
+    /// `zh-CN`, for use with localization crates.
+    ///
+    /// Returns `None` if Steam reported a language this crate doesn't know.
+    pub fn to_bevy_locale(&self) -> Option<&'static str> {
+        steam_language_to_locale(&self.current_game_language)
+    }
+}
+
+/// Converts a Steam API language code, such as `english` or `schinese`, into a
+/// BCP 47 language tag.
+///
+/// Returns `None` for languages Steam doesn't document.
+pub fn steam_language_to_locale(language: &str) -> Option<&'static str> {
+    Some(match language {
+        "arabic" => "ar",
+        "bulgarian" => "bg",
+        "schinese" => "zh-CN",
+        "tchinese" => "zh-TW",
+        "czech" => "cs",
+        "danish" => "da",
+        "dutch" => "nl",
+        "english" => "en",
+        "finnish" => "fi",
+        "french" => "fr",
+        "german" => "de",
+        "greek" => "el",
+        "hungarian" => "hu",
+        "indonesian" => "id",
+        "italian" => "it",
+        "japanese" => "ja",
+        "koreana" => "ko",
+        "norwegian" => "no",
+        "polish" => "pl",
+        "portuguese" => "pt",
+        "brazilian" => "pt-BR",
+        "romanian" => "ro",
+        "russian" => "ru",
+        "spanish" => "es",
+        "latam" => "es-419",
+        "swedish" => "sv",
+        "thai" => "th",
+        "turkish" => "tr",
+        "ukrainian" => "uk",
+        "vietnamese" => "vi",
+        _ => return None,
+    })
+}
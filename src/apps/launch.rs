@@ -0,0 +1,133 @@
+use std::ffi::c_void;
+
+use bevy_ecs::{
+    event::{Event, EventWriter},
+    system::{ResMut, Resource},
+};
+use steamworks::{sys, Callback};
+
+use crate::{call_result::CallResults, shutdown::RegisteredCallback, Client, ShuttingDown};
+
+/// Sent with the command line the game was launched with, once at startup and
+/// again whenever Steam passes the running game a new one, such as when the user
+/// follows a `steam://run` link.
+#[derive(Event, Debug, Clone)]
+pub struct LaunchParameters {
+    /// The raw command line.
+    pub command_line: String,
+    /// The command line split into arguments. See [`tokenize_command_line`].
+    pub args: Vec<String>,
+}
+
+/// `NewUrlLaunchParameters_t`, which steamworks does not wrap.
+struct NewUrlLaunchParameters;
+
+unsafe impl Callback for NewUrlLaunchParameters {
+    const ID: i32 = 1014;
+    const SIZE: i32 = std::mem::size_of::<sys::NewUrlLaunchParameters_t>() as i32;
+
+    unsafe fn from_raw(_: *mut c_void) -> Self {
+        Self
+    }
+}
+
+/// Reads the command line and query parameters Steam launched the game with.
+#[derive(Resource)]
+pub struct SteamLaunch {
+    client: Client,
+    initial_sent: bool,
+    changed: CallResults<()>,
+    _changed_callback: RegisteredCallback,
+}
+
+impl SteamLaunch {
+    pub(super) fn new(client: &Client) -> Self {
+        let changed = CallResults::new();
+        let changed_in = changed.clone();
+        let changed_callback =
+            RegisteredCallback::new(client.register_callback(move |NewUrlLaunchParameters| {
+                changed_in.push(());
+            }));
+        Self {
+            client: client.clone(),
+            initial_sent: false,
+            changed,
+            _changed_callback: changed_callback,
+        }
+    }
+
+    /// Gets the command line Steam recorded for the game, such as the
+    /// `+connect 1.2.3.4` in `steam://run/<app id>//+connect 1.2.3.4/`.
+    pub fn command_line(&self) -> Result<String, ShuttingDown> {
+        self.client.ensure_running()?;
+        Ok(self.client.apps().launch_command_line())
+    }
+
+    /// Gets the value of a launch query parameter, such as `key` in
+    /// `steam://run/<app id>//?key=value`. Returns `None` once Steam is shutting
+    /// down.
+    pub fn query_param(&self, key: &str) -> Option<String> {
+        if self.client.is_shutting_down() {
+            return None;
+        }
+        let value = self.client.apps().launch_query_param(key);
+        (!value.is_empty()).then_some(value)
+    }
+
+    fn parameters(&self) -> LaunchParameters {
+        let command_line = self.client.apps().launch_command_line();
+        LaunchParameters {
+            args: tokenize_command_line(&command_line),
+            command_line,
+        }
+    }
+}
+
+/// Splits a command line into arguments on whitespace, keeping text inside
+/// double quotes together.
+///
+/// ```rust
+/// # use bevy_steamworks::tokenize_command_line;
+/// let args = tokenize_command_line(r#"+connect_lobby 109775241 +name "Player One""#);
+/// assert_eq!(args, ["+connect_lobby", "109775241", "+name", "Player One"]);
+/// ```
+pub fn tokenize_command_line(command_line: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut quoted = false;
+    for c in command_line.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                in_arg = true;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            c => {
+                current.push(c);
+                in_arg = true;
+            }
+        }
+    }
+    if in_arg {
+        args.push(current);
+    }
+    args
+}
+
+pub(super) fn flush_launch_parameters(
+    mut launch: ResMut<SteamLaunch>,
+    mut parameters: EventWriter<LaunchParameters>,
+) {
+    let changed = !launch.changed.drain().is_empty();
+    if launch.initial_sent && !changed {
+        return;
+    }
+    launch.initial_sent = true;
+    parameters.send(launch.parameters());
+}
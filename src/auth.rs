@@ -0,0 +1,43 @@
+mod handshake;
+mod peer;
+mod sessions;
+mod tickets;
+mod validation;
+
+pub use handshake::*;
+pub use peer::*;
+pub use sessions::*;
+pub use tickets::*;
+pub use validation::*;
+
+use bevy_app::{App, First, Last};
+use bevy_ecs::schedule::IntoSystemConfigs;
+
+use crate::{shutdown::steam_running, Client, SteamworksSystem};
+
+pub(crate) fn plugin(app: &mut App) {
+    let client = app.world().resource::<Client>();
+    let auth = SteamAuth::new(client);
+    let sessions = SteamAuthSessions::new(client);
+    app.insert_resource(auth)
+        .insert_resource(sessions)
+        .init_resource::<ValidatedPeers>()
+        .add_event::<SessionTicketReady>()
+        .add_event::<WebApiTicketReady>()
+        .add_event::<PeerAuthResult>()
+        .add_event::<PeerTicketValidated>()
+        .add_event::<PlayerAuthenticated>()
+        .add_event::<PlayerRejected>()
+        .add_systems(
+            First,
+            (
+                tickets::flush_ticket_responses,
+                sessions::flush_validate_responses,
+                handshake::resolve_joining_players,
+            )
+                .chain()
+                .after(SteamworksSystem::RunCallbacks)
+                .run_if(steam_running),
+        )
+        .add_systems(Last, peer::release_auth_on_exit);
+}
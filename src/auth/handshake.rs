@@ -0,0 +1,147 @@
+use std::time::{Duration, Instant};
+
+use bevy_ecs::{
+    event::{Event, EventReader, EventWriter},
+    system::ResMut,
+};
+use steamworks::{networking_types::NetworkingIdentity, AuthSessionError, SteamId};
+
+use super::{BeginSessionError, PeerAuthResult, SteamAuth, SteamAuthSessions, TicketOutcome};
+use crate::ShuttingDown;
+
+/// Sends a ticket from [`SteamAuth::connect_authenticated`] to the server.
+pub type TicketTransport = Box<dyn FnOnce(Vec<u8>) + Send + Sync>;
+
+/// How long [`SteamAuthSessions::authenticate_joining_player`] waits for Steam to
+/// validate a ticket by default.
+pub const DEFAULT_JOIN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Why a player passed to [`SteamAuthSessions::authenticate_joining_player`] was
+/// rejected.
+#[derive(Debug)]
+pub enum PlayerRejectReason {
+    /// The auth session could not be started from the ticket the player sent.
+    Ticket(AuthSessionError),
+    /// Steam judged the ticket invalid.
+    Validation(TicketOutcome),
+    /// Steam did not validate the ticket within
+    /// [`SteamAuthSessions::join_timeout`].
+    TimedOut,
+}
+
+/// Sent when a player passed to [`SteamAuthSessions::authenticate_joining_player`]
+/// is validated by Steam and can be let in.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct PlayerAuthenticated {
+    /// The player that joined.
+    pub steam_id: SteamId,
+    /// The owner of the game license used by the player.
+    pub owner_steam_id: SteamId,
+}
+
+/// Sent when a player passed to [`SteamAuthSessions::authenticate_joining_player`]
+/// should be turned away. Their auth session has already been ended.
+#[derive(Event, Debug)]
+pub struct PlayerRejected {
+    /// The player that was rejected.
+    pub steam_id: SteamId,
+    /// Why the player was rejected.
+    pub reason: PlayerRejectReason,
+}
+
+impl SteamAuth {
+    /// Requests a session ticket for connecting to `server_identity` and hands it to
+    /// `transport` once Steam has confirmed it, returning an ID for the request.
+    ///
+    /// `transport` is responsible for getting the bytes to the server, which passes
+    /// them to [`SteamAuthSessions::authenticate_joining_player`]. It is dropped
+    /// without being called if the ticket fails to generate, in which case a
+    /// [`SessionTicketReady`](crate::SessionTicketReady) event carries the error.
+    ///
+    /// The ticket must be cancelled with [`SteamAuth::cancel`] when disconnecting,
+    /// which [`PeerAuth::Ticket`](crate::PeerAuth::Ticket) does automatically.
+    pub fn connect_authenticated(
+        &mut self,
+        server_identity: NetworkingIdentity,
+        transport: impl FnOnce(Vec<u8>) + Send + Sync + 'static,
+    ) -> Result<u64, ShuttingDown> {
+        let request_id = self.request_session_ticket(server_identity)?;
+        self.transports.push((request_id, Box::new(transport)));
+        Ok(request_id)
+    }
+}
+
+impl SteamAuthSessions {
+    /// Starts an auth session for a player joining with the ticket they sent from
+    /// [`SteamAuth::connect_authenticated`].
+    ///
+    /// Completes with a [`PlayerAuthenticated`] or [`PlayerRejected`] event. Players
+    /// whose ticket is not validated within [`SteamAuthSessions::join_timeout`] are
+    /// rejected, and rejected players have their session ended.
+    pub fn authenticate_joining_player(
+        &mut self,
+        steam_id: SteamId,
+        ticket: &[u8],
+    ) -> Result<(), ShuttingDown> {
+        match self.begin(steam_id, ticket) {
+            Ok(()) => {
+                self.joining.insert(steam_id, Instant::now());
+            }
+            Err(BeginSessionError::Ticket(err)) => self.rejected.push(PlayerRejected {
+                steam_id,
+                reason: PlayerRejectReason::Ticket(err),
+            }),
+            Err(BeginSessionError::ShuttingDown) => return Err(ShuttingDown),
+        }
+        Ok(())
+    }
+
+    /// Checks if a player passed to [`SteamAuthSessions::authenticate_joining_player`]
+    /// is still waiting on Steam.
+    pub fn is_joining(&self, steam_id: SteamId) -> bool {
+        self.joining.contains_key(&steam_id)
+    }
+
+    fn reject(&mut self, steam_id: SteamId, reason: PlayerRejectReason) -> PlayerRejected {
+        self.end(steam_id);
+        PlayerRejected { steam_id, reason }
+    }
+}
+
+pub(super) fn resolve_joining_players(
+    mut sessions: ResMut<SteamAuthSessions>,
+    mut results: EventReader<PeerAuthResult>,
+    mut authenticated: EventWriter<PlayerAuthenticated>,
+    mut rejected: EventWriter<PlayerRejected>,
+) {
+    rejected.send_batch(std::mem::take(&mut sessions.rejected));
+    for result in results.read() {
+        if sessions.joining.remove(&result.steam_id).is_none() {
+            continue;
+        }
+        let outcome =
+            TicketOutcome::from_parts(result.steam_id, result.owner_steam_id, &result.response);
+        if outcome.is_valid() {
+            authenticated.send(PlayerAuthenticated {
+                steam_id: result.steam_id,
+                owner_steam_id: result.owner_steam_id,
+            });
+        } else {
+            let rejection =
+                sessions.reject(result.steam_id, PlayerRejectReason::Validation(outcome));
+            rejected.send(rejection);
+        }
+    }
+
+    let timeout = sessions.join_timeout;
+    let expired: Vec<_> = sessions
+        .joining
+        .iter()
+        .filter(|(_, started)| started.elapsed() >= timeout)
+        .map(|(steam_id, _)| *steam_id)
+        .collect();
+    for steam_id in expired {
+        let rejection = sessions.reject(steam_id, PlayerRejectReason::TimedOut);
+        rejected.send(rejection);
+    }
+}
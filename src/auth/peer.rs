@@ -0,0 +1,61 @@
+use bevy_app::AppExit;
+use bevy_ecs::{
+    component::{Component, ComponentHooks, StorageType},
+    event::EventReader,
+    system::ResMut,
+};
+use steamworks::SteamId;
+
+use super::{SteamAuth, SteamAuthSessions};
+
+/// Ties an auth ticket or session to an entity, such as the one representing a
+/// connection to a peer.
+///
+/// When the component is removed or its entity is despawned, the ticket is
+/// cancelled through [`SteamAuth::cancel`] or the session is ended through
+/// [`SteamAuthSessions::end`]. Anything still outstanding when [`AppExit`] is sent
+/// is cancelled or ended as well. Both are idempotent, so it is safe for a ticket
+/// or session to have already been cleaned up by hand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeerAuth {
+    /// A ticket we issued for the peer, identified by the request ID returned from
+    /// [`SteamAuth::request_session_ticket`].
+    Ticket(u64),
+    /// An auth session we opened for the peer with [`SteamAuthSessions::begin`].
+    Session(SteamId),
+}
+
+impl Component for PeerAuth {
+    const STORAGE_TYPE: StorageType = StorageType::Table;
+
+    fn register_component_hooks(hooks: &mut ComponentHooks) {
+        hooks.on_remove(|mut world, entity, _| {
+            let Some(&auth) = world.get::<PeerAuth>(entity) else {
+                return;
+            };
+            match auth {
+                PeerAuth::Ticket(request_id) => {
+                    if let Some(mut steam_auth) = world.get_resource_mut::<SteamAuth>() {
+                        steam_auth.cancel(request_id);
+                    }
+                }
+                PeerAuth::Session(steam_id) => {
+                    if let Some(mut sessions) = world.get_resource_mut::<SteamAuthSessions>() {
+                        sessions.end(steam_id);
+                    }
+                }
+            }
+        });
+    }
+}
+
+pub(super) fn release_auth_on_exit(
+    mut auth: ResMut<SteamAuth>,
+    mut sessions: ResMut<SteamAuthSessions>,
+    mut exit: EventReader<AppExit>,
+) {
+    if exit.read().next().is_some() {
+        auth.cancel_all();
+        sessions.end_all();
+    }
+}
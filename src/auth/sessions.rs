@@ -0,0 +1,193 @@
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+use bevy_ecs::{
+    event::{Event, EventReader, EventWriter},
+    system::{ResMut, Resource},
+};
+use bevy_utils::HashMap;
+use steamworks::{AuthSessionError, AuthSessionValidateError, SteamId};
+
+use super::{
+    PeerTicketValidated, PlayerRejected, TicketOutcome, ValidatedPeers, DEFAULT_JOIN_TIMEOUT,
+};
+use crate::{Client, ShuttingDown, SteamworksEvent};
+
+/// Sent when Steam validates the ticket of a peer whose session was started with
+/// [`SteamAuthSessions::begin`].
+///
+/// Steam may send more than one result for the same peer, such as when the peer
+/// is VAC banned or cancels their ticket partway through a session.
+#[derive(Event, Debug)]
+pub struct PeerAuthResult {
+    /// The peer that was validated.
+    pub steam_id: SteamId,
+    /// Whether the peer's ticket is valid.
+    pub response: Result<(), AuthSessionValidateError>,
+    /// The owner of the game license used by the peer. Differs from `steam_id`
+    /// when the game is borrowed through Family Sharing.
+    pub owner_steam_id: SteamId,
+}
+
+/// An error from [`SteamAuthSessions::begin`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BeginSessionError {
+    /// Steam could not start a session from the ticket.
+    Ticket(AuthSessionError),
+    /// Steam is shutting down.
+    ShuttingDown,
+}
+
+impl fmt::Display for BeginSessionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ticket(err) => write!(f, "failed to begin the auth session: {err}"),
+            Self::ShuttingDown => write!(f, "{ShuttingDown}"),
+        }
+    }
+}
+
+impl std::error::Error for BeginSessionError {}
+
+impl From<AuthSessionError> for BeginSessionError {
+    fn from(err: AuthSessionError) -> Self {
+        Self::Ticket(err)
+    }
+}
+
+impl From<ShuttingDown> for BeginSessionError {
+    fn from(_: ShuttingDown) -> Self {
+        Self::ShuttingDown
+    }
+}
+
+/// Tracks the auth sessions started for peers that sent us a ticket from
+/// [`SteamAuth::request_session_ticket`](crate::SteamAuth::request_session_ticket).
+///
+/// Every session still open when this resource is dropped is ended.
+#[derive(Resource)]
+pub struct SteamAuthSessions {
+    client: Client,
+    /// Whether each peer with an open session has been positively validated.
+    sessions: HashMap<SteamId, bool>,
+    /// Peers whose sessions were ended since the last frame.
+    ended: Vec<SteamId>,
+    /// When each player passed to [`SteamAuthSessions::authenticate_joining_player`]
+    /// started waiting on Steam.
+    pub(super) joining: HashMap<SteamId, Instant>,
+    /// Joining players rejected before their session could be started.
+    pub(super) rejected: Vec<PlayerRejected>,
+    /// How long a joining player's ticket may take to validate before they are
+    /// rejected. Defaults to [`DEFAULT_JOIN_TIMEOUT`].
+    pub join_timeout: Duration,
+}
+
+impl SteamAuthSessions {
+    pub(super) fn new(client: &Client) -> Self {
+        Self {
+            client: client.clone(),
+            sessions: HashMap::default(),
+            ended: Vec::new(),
+            joining: HashMap::default(),
+            rejected: Vec::new(),
+            join_timeout: DEFAULT_JOIN_TIMEOUT,
+        }
+    }
+
+    /// Starts an auth session for a peer from the ticket they sent.
+    ///
+    /// The ticket is validated in the background, completing with a
+    /// [`PeerAuthResult`] event. Fails with [`AuthSessionError::DuplicateRequest`]
+    /// if a session for the peer is already open, or with
+    /// [`BeginSessionError::ShuttingDown`] once Steam is shutting down.
+    pub fn begin(&mut self, steam_id: SteamId, ticket: &[u8]) -> Result<(), BeginSessionError> {
+        self.client.ensure_running()?;
+        if self.sessions.contains_key(&steam_id) {
+            return Err(AuthSessionError::DuplicateRequest.into());
+        }
+        self.client
+            .user()
+            .begin_authentication_session(steam_id, ticket)?;
+        self.sessions.insert(steam_id, false);
+        Ok(())
+    }
+
+    /// Ends a peer's auth session, such as when they disconnect.
+    ///
+    /// Returns `false` if there is no open session for the peer. Unlike beginning
+    /// a session, this still works once Steam is shutting down.
+    pub fn end(&mut self, steam_id: SteamId) -> bool {
+        if self.sessions.remove(&steam_id).is_none() {
+            return false;
+        }
+        self.joining.remove(&steam_id);
+        self.client.user().end_authentication_session(steam_id);
+        self.ended.push(steam_id);
+        true
+    }
+
+    /// Ends every open auth session, even once Steam is shutting down.
+    pub fn end_all(&mut self) {
+        let user = self.client.user();
+        self.joining.clear();
+        for (steam_id, _) in self.sessions.drain() {
+            user.end_authentication_session(steam_id);
+            self.ended.push(steam_id);
+        }
+    }
+
+    /// Checks if a session is open for the peer.
+    pub fn is_open(&self, steam_id: SteamId) -> bool {
+        self.sessions.contains_key(&steam_id)
+    }
+
+    /// Checks if the peer's ticket has been validated by Steam and has not been
+    /// invalidated since.
+    ///
+    /// Unlike [`ValidatedPeers`], this only covers peers with a session opened
+    /// through [`SteamAuthSessions::begin`].
+    pub fn is_authenticated(&self, steam_id: SteamId) -> bool {
+        self.sessions.get(&steam_id).copied().unwrap_or(false)
+    }
+}
+
+impl Drop for SteamAuthSessions {
+    fn drop(&mut self) {
+        self.end_all();
+    }
+}
+
+pub(super) fn flush_validate_responses(
+    mut sessions: ResMut<SteamAuthSessions>,
+    mut validated_peers: ResMut<ValidatedPeers>,
+    mut events: EventReader<SteamworksEvent>,
+    mut validated: EventWriter<PeerTicketValidated>,
+    mut results: EventWriter<PeerAuthResult>,
+) {
+    for steam_id in std::mem::take(&mut sessions.ended) {
+        validated_peers.remove(steam_id);
+    }
+    for event in events.read() {
+        let SteamworksEvent::ValidateAuthTicketResponse(response) = event else {
+            continue;
+        };
+        let ticket = PeerTicketValidated {
+            steam_id: response.steam_id,
+            owner_steam_id: response.owner_steam_id,
+            outcome: TicketOutcome::from_response(response),
+        };
+        validated_peers.apply(&ticket);
+        validated.send(ticket);
+        let Some(validated) = sessions.sessions.get_mut(&response.steam_id) else {
+            continue;
+        };
+        *validated = response.response.is_ok();
+        results.send(PeerAuthResult {
+            steam_id: response.steam_id,
+            response: response.response.clone(),
+            owner_steam_id: response.owner_steam_id,
+        });
+    }
+}
@@ -0,0 +1,249 @@
+use std::fmt::Write;
+
+use bevy_ecs::{
+    event::{Event, EventReader, EventWriter},
+    system::{ResMut, Resource},
+};
+use steamworks::{
+    networking_types::NetworkingIdentity, AuthSessionError, AuthTicket, SteamError,
+    TicketForWebApiResponse,
+};
+
+use super::TicketTransport;
+use crate::{Client, ShuttingDown, SteamworksEvent};
+
+/// Sent when a ticket requested with [`SteamAuth::request_session_ticket`] is
+/// confirmed by Steam and can be sent to whoever will validate it.
+#[derive(Event, Debug)]
+pub struct SessionTicketReady {
+    /// The ID returned by [`SteamAuth::request_session_ticket`].
+    pub request_id: u64,
+    /// The ticket bytes.
+    pub result: Result<Vec<u8>, AuthSessionError>,
+}
+
+/// Sent when a ticket requested with [`SteamAuth::request_webapi_ticket`] is ready
+/// to be sent to a web backend.
+#[derive(Event, Debug, Clone)]
+pub struct WebApiTicketReady {
+    /// The ID returned by [`SteamAuth::request_webapi_ticket`].
+    pub request_id: u64,
+    /// The ticket bytes.
+    pub result: Result<Vec<u8>, SteamError>,
+}
+
+impl WebApiTicketReady {
+    /// Gets the ticket as an uppercase hex string, the form expected by the
+    /// `ISteamUserAuth/AuthenticateUserTicket` Web API.
+    pub fn ticket_hex(&self) -> Option<String> {
+        let ticket = self.result.as_ref().ok()?;
+        let mut hex = String::with_capacity(ticket.len() * 2);
+        for byte in ticket {
+            let _ = write!(hex, "{byte:02X}");
+        }
+        Some(hex)
+    }
+}
+
+struct SessionTicket {
+    request_id: u64,
+    ticket: AuthTicket,
+    /// The ticket bytes, until Steam confirms the ticket.
+    pending: Option<Vec<u8>>,
+}
+
+/// Requests auth session tickets for proving the user's identity to a game
+/// server, peer, or backend.
+///
+/// Tickets are kept alive until they are cancelled with [`SteamAuth::cancel`],
+/// since Steam invalidates a ticket as soon as it is cancelled.
+#[derive(Resource)]
+pub struct SteamAuth {
+    client: Client,
+    next_request_id: u64,
+    tickets: Vec<SessionTicket>,
+    webapi_requests: Vec<(u64, AuthTicket)>,
+    /// Transports waiting on tickets from [`SteamAuth::connect_authenticated`].
+    pub(super) transports: Vec<(u64, TicketTransport)>,
+}
+
+impl SteamAuth {
+    pub(super) fn new(client: &Client) -> Self {
+        Self {
+            client: client.clone(),
+            next_request_id: 0,
+            tickets: Vec::new(),
+            webapi_requests: Vec::new(),
+            transports: Vec::new(),
+        }
+    }
+
+    /// Requests a session ticket for authenticating with `identity`, returning an
+    /// ID for the request.
+    ///
+    /// Completes with a [`SessionTicketReady`] event carrying the same ID once
+    /// Steam has confirmed the ticket.
+    pub fn request_session_ticket(
+        &mut self,
+        identity: NetworkingIdentity,
+    ) -> Result<u64, ShuttingDown> {
+        self.client.ensure_running()?;
+        let (ticket, bytes) = self.client.user().authentication_session_ticket(identity);
+        let request_id = self.next_request_id();
+        self.tickets.push(SessionTicket {
+            request_id,
+            ticket,
+            pending: Some(bytes),
+        });
+        Ok(request_id)
+    }
+
+    /// Requests a ticket for authenticating with a web backend, returning an ID
+    /// for the request. `identity` names the service the ticket is meant for and
+    /// must match what the backend passes when validating it.
+    ///
+    /// Completes with a [`WebApiTicketReady`] event carrying the same ID. Once
+    /// ready, the ticket is kept alive until cancelled with [`SteamAuth::cancel`].
+    pub fn request_webapi_ticket(&mut self, identity: &str) -> Result<u64, ShuttingDown> {
+        self.client.ensure_running()?;
+        let ticket = self
+            .client
+            .user()
+            .authentication_session_ticket_for_webapi(identity);
+        let request_id = self.next_request_id();
+        self.webapi_requests.push((request_id, ticket));
+        Ok(request_id)
+    }
+
+    fn next_request_id(&mut self) -> u64 {
+        let request_id = self.next_request_id;
+        self.next_request_id += 1;
+        request_id
+    }
+
+    fn on_webapi_ticket(
+        &mut self,
+        response: &TicketForWebApiResponse,
+    ) -> Option<WebApiTicketReady> {
+        // Responses are matched on the ticket handle so that concurrent requests
+        // for different identities can't be crossed.
+        let index = self
+            .webapi_requests
+            .iter()
+            .position(|(_, ticket)| *ticket == response.ticket_handle)?;
+        let (request_id, ticket) = self.webapi_requests.swap_remove(index);
+        let result = match response.result {
+            Ok(()) => {
+                self.tickets.push(SessionTicket {
+                    request_id,
+                    ticket,
+                    pending: None,
+                });
+                Ok(response.ticket.clone())
+            }
+            Err(err) => {
+                self.client.user().cancel_authentication_ticket(ticket);
+                Err(err)
+            }
+        };
+        Some(WebApiTicketReady { request_id, result })
+    }
+
+    /// Cancels a ticket requested with [`SteamAuth::request_session_ticket`] or
+    /// [`SteamAuth::request_webapi_ticket`].
+    ///
+    /// Returns `false` if there is no live ticket with the given ID. Unlike
+    /// requesting a ticket, this still works once Steam is shutting down.
+    pub fn cancel(&mut self, request_id: u64) -> bool {
+        self.transports.retain(|(id, _)| *id != request_id);
+        if let Some(index) = self
+            .webapi_requests
+            .iter()
+            .position(|(id, _)| *id == request_id)
+        {
+            let (_, ticket) = self.webapi_requests.swap_remove(index);
+            self.client.user().cancel_authentication_ticket(ticket);
+            return true;
+        }
+        let Some(index) = self
+            .tickets
+            .iter()
+            .position(|ticket| ticket.request_id == request_id)
+        else {
+            return false;
+        };
+        let ticket = self.tickets.swap_remove(index);
+        self.client
+            .user()
+            .cancel_authentication_ticket(ticket.ticket);
+        true
+    }
+
+    /// Cancels every ticket that is still alive or pending, even once Steam is
+    /// shutting down.
+    pub fn cancel_all(&mut self) {
+        self.transports.clear();
+        let user = self.client.user();
+        for (_, ticket) in self.webapi_requests.drain(..) {
+            user.cancel_authentication_ticket(ticket);
+        }
+        for ticket in self.tickets.drain(..) {
+            user.cancel_authentication_ticket(ticket.ticket);
+        }
+    }
+}
+
+fn to_auth_session_error(err: SteamError) -> AuthSessionError {
+    match err {
+        SteamError::DuplicateRequest => AuthSessionError::DuplicateRequest,
+        SteamError::Expired => AuthSessionError::ExpiredTicket,
+        _ => AuthSessionError::InvalidTicket,
+    }
+}
+
+pub(super) fn flush_ticket_responses(
+    mut auth: ResMut<SteamAuth>,
+    mut events: EventReader<SteamworksEvent>,
+    mut ready: EventWriter<SessionTicketReady>,
+    mut webapi_ready: EventWriter<WebApiTicketReady>,
+) {
+    for event in events.read() {
+        let response = match event {
+            SteamworksEvent::AuthSessionTicketResponse(response) => response,
+            SteamworksEvent::TicketForWebApiResponse(response) => {
+                if let Some(ready) = auth.on_webapi_ticket(response) {
+                    webapi_ready.send(ready);
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        let Some(index) = auth
+            .tickets
+            .iter()
+            .position(|ticket| ticket.ticket == response.ticket && ticket.pending.is_some())
+        else {
+            continue;
+        };
+        let request_id = auth.tickets[index].request_id;
+        let result = match response.result {
+            Ok(()) => Ok(auth.tickets[index].pending.take().unwrap_or_default()),
+            Err(err) => {
+                // A ticket that failed to generate is of no use, so stop tracking it.
+                let ticket = auth.tickets.swap_remove(index);
+                auth.client
+                    .user()
+                    .cancel_authentication_ticket(ticket.ticket);
+                Err(to_auth_session_error(err))
+            }
+        };
+        // The transport is dropped unused if the ticket failed to generate.
+        if let Some(index) = auth.transports.iter().position(|(id, _)| *id == request_id) {
+            let (_, transport) = auth.transports.swap_remove(index);
+            if let Ok(ticket) = &result {
+                transport(ticket.clone());
+            }
+        }
+        ready.send(SessionTicketReady { request_id, result });
+    }
+}
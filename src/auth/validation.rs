@@ -0,0 +1,130 @@
+use bevy_ecs::{event::Event, system::Resource};
+use bevy_utils::HashMap;
+use steamworks::{AuthSessionValidateError, SteamId, ValidateAuthTicketResponse};
+
+/// How Steam judged a peer's auth ticket, as reported by [`PeerTicketValidated`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TicketOutcome {
+    /// The ticket is valid and the peer owns the game.
+    Ok,
+    /// The ticket is valid and the peer is playing a copy borrowed through
+    /// Family Sharing.
+    OkBorrowed,
+    /// The peer is VAC banned for this game.
+    VacBanned,
+    /// The peer is banned by the game's publisher.
+    PublisherBanned,
+    /// The peer cancelled their ticket, such as by leaving.
+    TicketCanceled,
+    /// The Steam session the ticket was issued for has ended, such as by the peer
+    /// disconnecting from Steam or logging in elsewhere.
+    TicketExpired,
+    /// The peer does not own a license for the game, or it has expired.
+    NoLicense,
+    /// The ticket is invalid or was already used.
+    TicketInvalid,
+}
+
+impl TicketOutcome {
+    /// Interprets a ticket validation result.
+    pub fn from_response(response: &ValidateAuthTicketResponse) -> Self {
+        Self::from_parts(
+            response.steam_id,
+            response.owner_steam_id,
+            &response.response,
+        )
+    }
+
+    pub(super) fn from_parts(
+        steam_id: SteamId,
+        owner_steam_id: SteamId,
+        response: &Result<(), AuthSessionValidateError>,
+    ) -> Self {
+        match response {
+            Ok(()) if owner_steam_id != steam_id => Self::OkBorrowed,
+            Ok(()) => Self::Ok,
+            Err(AuthSessionValidateError::VACBanned) => Self::VacBanned,
+            Err(AuthSessionValidateError::PublisherIssuedBan) => Self::PublisherBanned,
+            Err(AuthSessionValidateError::AuthTicketCancelled) => Self::TicketCanceled,
+            Err(
+                AuthSessionValidateError::UserNotConnectedToSteam
+                | AuthSessionValidateError::LoggedInElseWhere,
+            ) => Self::TicketExpired,
+            Err(AuthSessionValidateError::NoLicenseOrExpired) => Self::NoLicense,
+            Err(_) => Self::TicketInvalid,
+        }
+    }
+
+    /// Checks if the peer should be let in.
+    pub fn is_valid(self) -> bool {
+        matches!(self, Self::Ok | Self::OkBorrowed)
+    }
+}
+
+/// Sent for every ticket validation result from Steam, whether or not the session
+/// was started through [`SteamAuthSessions`](crate::SteamAuthSessions).
+#[derive(Event, Debug, Clone, Copy)]
+pub struct PeerTicketValidated {
+    /// The peer that was validated.
+    pub steam_id: SteamId,
+    /// The owner of the game license used by the peer.
+    pub owner_steam_id: SteamId,
+    /// How Steam judged the ticket.
+    pub outcome: TicketOutcome,
+}
+
+/// The peers whose tickets are currently valid, along with who owns the license
+/// each of them is playing on.
+///
+/// A peer is added when Steam validates their ticket and removed when a later
+/// result invalidates it or their session is ended through
+/// [`SteamAuthSessions::end`](crate::SteamAuthSessions::end).
+///
+/// Servers that allow one session per license can check
+/// [`ValidatedPeers::peers_with_owner`] before admitting a peer who borrowed the
+/// game through Family Sharing.
+#[derive(Resource, Debug, Default)]
+pub struct ValidatedPeers(HashMap<SteamId, SteamId>);
+
+impl ValidatedPeers {
+    /// Checks if the peer's ticket is currently valid.
+    pub fn contains(&self, steam_id: SteamId) -> bool {
+        self.0.contains_key(&steam_id)
+    }
+
+    /// Iterates over every peer whose ticket is currently valid.
+    pub fn iter(&self) -> impl Iterator<Item = SteamId> + '_ {
+        self.0.keys().copied()
+    }
+
+    /// Gets the owner of the license a validated peer is playing on.
+    pub fn owner(&self, steam_id: SteamId) -> Option<SteamId> {
+        self.0.get(&steam_id).copied()
+    }
+
+    /// Checks if a validated peer borrowed the game through Family Sharing.
+    pub fn is_borrowed(&self, steam_id: SteamId) -> bool {
+        self.owner(steam_id).is_some_and(|owner| owner != steam_id)
+    }
+
+    /// Iterates over every validated peer playing on a license owned by `owner`,
+    /// including the owner themselves.
+    pub fn peers_with_owner(&self, owner: SteamId) -> impl Iterator<Item = SteamId> + '_ {
+        self.0
+            .iter()
+            .filter(move |(_, peer_owner)| **peer_owner == owner)
+            .map(|(steam_id, _)| *steam_id)
+    }
+
+    pub(super) fn apply(&mut self, validated: &PeerTicketValidated) {
+        if validated.outcome.is_valid() {
+            self.0.insert(validated.steam_id, validated.owner_steam_id);
+        } else {
+            self.0.remove(&validated.steam_id);
+        }
+    }
+
+    pub(super) fn remove(&mut self, steam_id: SteamId) {
+        self.0.remove(&steam_id);
+    }
+}
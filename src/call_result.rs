@@ -0,0 +1,48 @@
+use std::sync::Arc;
+
+use bevy_utils::syncunsafecell::SyncUnsafeCell;
+
+/// A buffer of values produced by closures handed to the Steam SDK.
+///
+/// Call result closures are only ever invoked by `SingleClient::run_callbacks`,
+/// which only runs inside `run_steam_callbacks`, either in [`First`] or with the
+/// world borrowed exclusively on exit. Values pushed here must be drained by
+/// systems ordered after [`SteamworksSystem::RunCallbacks`] so that the two can
+/// never alias.
+///
+/// [`First`]: bevy_app::First
+///
+/// [`SteamworksSystem::RunCallbacks`]: crate::SteamworksSystem::RunCallbacks
+pub(crate) struct CallResults<T>(Arc<SyncUnsafeCell<Vec<T>>>);
+
+impl<T> CallResults<T> {
+    pub(crate) fn new() -> Self {
+        Self(Arc::new(SyncUnsafeCell::new(Vec::new())))
+    }
+
+    /// Pushes a value into the buffer.
+    ///
+    /// Must only be called from within a Steam callback or call result closure.
+    pub(crate) fn push(&self, value: T) {
+        // SAFETY: This is only called from Steam callbacks, which only run during
+        // `run_steam_callbacks`. Draining systems are ordered after it. This cannot alias.
+        unsafe {
+            (*self.0.get()).push(value);
+        }
+    }
+
+    /// Takes all of the values currently in the buffer.
+    ///
+    /// Must only be called from systems ordered after `SteamworksSystem::RunCallbacks`.
+    pub(crate) fn drain(&self) -> Vec<T> {
+        // SAFETY: Callers are ordered after `run_steam_callbacks`, the only place
+        // values are pushed. This cannot alias.
+        unsafe { std::mem::take(&mut *self.0.get()) }
+    }
+}
+
+impl<T> Clone for CallResults<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
@@ -0,0 +1,56 @@
+#[cfg(feature = "bevy_asset")]
+mod asset_source;
+#[cfg(feature = "serde")]
+mod autosave;
+mod conflicts;
+mod quota;
+mod saves;
+mod stream;
+mod writer;
+
+#[cfg(feature = "bevy_asset")]
+pub use asset_source::CLOUD_ASSET_SOURCE;
+#[cfg(feature = "serde")]
+pub use autosave::*;
+pub use conflicts::{CloudSaveConflict, CloudSaveMeta, ConflictResolution};
+pub use quota::{CloudQuotaLow, CloudSyncStateChanged, SteamCloudQuota};
+pub use saves::*;
+pub use stream::*;
+pub use writer::CloudFileWritten;
+
+use bevy_app::{App, First, Last};
+use bevy_ecs::schedule::IntoSystemConfigs;
+
+use crate::{shutdown::steam_running, Client, SteamworksSystem};
+
+pub(crate) fn plugin(app: &mut App) {
+    let client = app.world().resource::<Client>();
+    let quota_stale = quota::QuotaStale::default();
+    let conflicts = conflicts::ConflictTracker::default();
+    let (writes, write_results) =
+        writer::spawn_writer(client, quota_stale.clone(), conflicts.clone());
+    let saves = SteamCloudSaves::new(client, quota_stale.clone(), writes, conflicts);
+    let quota = quota::init_quota(client);
+    app.insert_resource(saves)
+        .insert_resource(quota)
+        .insert_resource(quota::QuotaTracker(quota_stale))
+        .insert_resource(write_results)
+        .add_event::<CloudQuotaLow>()
+        .add_event::<CloudFileWritten>()
+        .add_event::<CloudSyncStateChanged>()
+        .add_event::<CloudSaveConflict>()
+        .add_systems(
+            First,
+            (
+                (quota::track_cloud_sync_state, quota::refresh_quota).chain(),
+                writer::flush_write_results,
+                conflicts::flush_conflicts,
+            )
+                .after(SteamworksSystem::RunCallbacks)
+                .run_if(steam_running),
+        )
+        .add_systems(Last, writer::flush_writes_on_exit);
+
+    #[cfg(feature = "bevy_asset")]
+    asset_source::register(app);
+}
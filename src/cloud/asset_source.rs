@@ -0,0 +1,109 @@
+use std::path::{Path, PathBuf};
+
+use bevy_app::App;
+use bevy_asset::{
+    io::{AssetReader, AssetReaderError, AssetSource, PathStream, Reader, VecReader},
+    AssetApp,
+};
+use bevy_utils::HashSet;
+
+use crate::{CloudSaveError, SteamCloudSaves};
+
+/// The [`AssetSourceId`] Steam Cloud files are registered under.
+///
+/// Paths take the form `steamcloud://<cloud file name>`, where `/` separated
+/// segments of the file name are treated as directories.
+///
+/// [`AssetSourceId`]: bevy_asset::io::AssetSourceId
+pub const CLOUD_ASSET_SOURCE: &str = "steamcloud";
+
+/// An [`AssetReader`] that reads files stored in Steam Cloud via [`RemoteStorage`].
+///
+/// [`RemoteStorage`]: steamworks::RemoteStorage
+struct CloudAssetReader {
+    saves: SteamCloudSaves,
+}
+
+impl CloudAssetReader {
+    /// Reads a whole file at once, rather than in chunks that each wait on a call result.
+    fn read_file(&self, path: &Path, name: &str) -> Result<VecReader, AssetReaderError> {
+        match self.saves.read_raw(name) {
+            Ok(bytes) => Ok(VecReader::new(bytes)),
+            Err(CloudSaveError::Io(err)) => Err(err.into()),
+            Err(_) => Err(AssetReaderError::NotFound(path.to_owned())),
+        }
+    }
+
+    fn file_names(&self) -> impl Iterator<Item = String> {
+        self.saves
+            .client
+            .remote_storage()
+            .files()
+            .into_iter()
+            .map(|info| info.name)
+    }
+}
+
+impl AssetReader for CloudAssetReader {
+    async fn read<'a>(&'a self, path: &'a Path) -> Result<Box<Reader<'a>>, AssetReaderError> {
+        let name = cloud_name(path)?;
+        Ok(Box::new(self.read_file(path, &name)?))
+    }
+
+    async fn read_meta<'a>(&'a self, path: &'a Path) -> Result<Box<Reader<'a>>, AssetReaderError> {
+        let name = cloud_name(path)? + ".meta";
+        Ok(Box::new(self.read_file(path, &name)?))
+    }
+
+    async fn read_directory<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> Result<Box<PathStream>, AssetReaderError> {
+        let prefix = directory_prefix(path)?;
+        let children: HashSet<String> = self
+            .file_names()
+            .filter_map(|name| {
+                let child = name.strip_prefix(&prefix)?;
+                let child = child.split('/').next()?;
+                Some(prefix.clone() + child)
+            })
+            .collect();
+        if children.is_empty() {
+            return Err(AssetReaderError::NotFound(path.to_owned()));
+        }
+        let paths: Vec<PathBuf> = children.into_iter().map(PathBuf::from).collect();
+        Ok(Box::new(futures_lite::stream::iter(paths)))
+    }
+
+    async fn is_directory<'a>(&'a self, path: &'a Path) -> Result<bool, AssetReaderError> {
+        let prefix = directory_prefix(path)?;
+        Ok(prefix.is_empty() || self.file_names().any(|name| name.starts_with(&prefix)))
+    }
+}
+
+/// Converts an asset path into a Steam Cloud file name.
+fn cloud_name(path: &Path) -> Result<String, AssetReaderError> {
+    let segments = path
+        .iter()
+        .map(|segment| segment.to_str())
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| AssetReaderError::NotFound(path.to_owned()))?;
+    Ok(segments.join("/"))
+}
+
+fn directory_prefix(path: &Path) -> Result<String, AssetReaderError> {
+    let name = cloud_name(path)?;
+    Ok(if name.is_empty() { name } else { name + "/" })
+}
+
+pub(super) fn register(app: &mut App) {
+    let saves = app.world().resource::<SteamCloudSaves>().clone();
+    app.register_asset_source(
+        CLOUD_ASSET_SOURCE,
+        AssetSource::build().with_reader(move || {
+            Box::new(CloudAssetReader {
+                saves: saves.clone(),
+            })
+        }),
+    );
+}
@@ -0,0 +1,87 @@
+use std::{
+    marker::PhantomData,
+    time::{Duration, Instant},
+};
+
+use bevy_app::{App, Last, Plugin};
+use bevy_ecs::system::{Local, Res, Resource};
+use bevy_log::warn;
+use serde::{de::DeserializeOwned, Serialize};
+
+use super::SteamCloudSaves;
+
+/// A [`Plugin`] that persists a resource to Steam Cloud whenever it changes.
+///
+/// When built, the resource is loaded from Steam Cloud if a save exists.
+/// Afterwards, changes to the resource are detected via Bevy's change detection
+/// and written back at most once every `interval`.
+///
+/// Must be added after [`SteamworksPlugin`](crate::SteamworksPlugin).
+pub struct CloudAutosavePlugin<R> {
+    name: String,
+    interval: Duration,
+    _marker: PhantomData<fn() -> R>,
+}
+
+impl<R> CloudAutosavePlugin<R> {
+    /// Creates a new plugin that saves the resource to the cloud file `name`
+    /// at most once every `interval`.
+    pub fn new(name: impl Into<String>, interval: Duration) -> Self {
+        Self {
+            name: name.into(),
+            interval,
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[derive(Resource)]
+struct AutosaveSettings<R> {
+    name: String,
+    interval: Duration,
+    _marker: PhantomData<fn() -> R>,
+}
+
+impl<R: Resource + Serialize + DeserializeOwned> Plugin for CloudAutosavePlugin<R> {
+    fn build(&self, app: &mut App) {
+        let saves = app.world().resource::<SteamCloudSaves>().clone();
+        if saves.exists(&self.name) {
+            match saves.load::<R>(&self.name) {
+                Ok(value) => {
+                    app.insert_resource(value);
+                }
+                Err(err) => warn!("Failed to load cloud save {:?}: {}", self.name, err),
+            }
+        }
+        app.insert_resource(AutosaveSettings::<R> {
+            name: self.name.clone(),
+            interval: self.interval,
+            _marker: PhantomData,
+        })
+        .add_systems(Last, autosave::<R>);
+    }
+}
+
+fn autosave<R: Resource + Serialize>(
+    resource: Option<Res<R>>,
+    settings: Res<AutosaveSettings<R>>,
+    saves: Res<SteamCloudSaves>,
+    mut dirty: Local<bool>,
+    mut last_save: Local<Option<Instant>>,
+) {
+    let Some(resource) = resource else {
+        return;
+    };
+    if resource.is_changed() && !resource.is_added() {
+        *dirty = true;
+    }
+    let ready = last_save.map_or(true, |last| last.elapsed() >= settings.interval);
+    if !*dirty || !ready {
+        return;
+    }
+    *dirty = false;
+    *last_save = Some(Instant::now());
+    if let Err(err) = saves.save(&settings.name, &*resource) {
+        warn!("Failed to autosave {:?}: {}", settings.name, err);
+    }
+}
@@ -0,0 +1,244 @@
+use std::{
+    fmt::Write as _,
+    fs, io,
+    path::PathBuf,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use bevy_ecs::{
+    event::{Event, EventWriter},
+    system::Res,
+};
+use bevy_utils::HashMap;
+
+use super::{CloudSaveError, SteamCloudSaves};
+
+const MANIFEST_FILE: &str = "manifest.txt";
+
+/// The timestamp and content hash of a save file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CloudSaveMeta {
+    /// When the file was written, as a Unix timestamp.
+    pub unix_timestamp: i64,
+    /// A 64-bit FNV-1a hash of the save's payload.
+    pub hash: u64,
+}
+
+impl CloudSaveMeta {
+    fn new(unix_timestamp: i64, payload: &[u8]) -> Self {
+        Self {
+            unix_timestamp,
+            hash: fnv1a(payload),
+        }
+    }
+}
+
+/// Sent when a save loaded from Steam Cloud was written by another machine since
+/// this machine last saved it.
+///
+/// Resolve it with [`SteamCloudSaves::resolve_conflict`].
+#[derive(Event, Debug, Clone)]
+pub struct CloudSaveConflict {
+    /// The name of the conflicting file.
+    pub name: String,
+    /// The last save this machine wrote.
+    pub local_meta: CloudSaveMeta,
+    /// The save currently stored in Steam Cloud.
+    pub remote_meta: CloudSaveMeta,
+}
+
+/// How to resolve a [`CloudSaveConflict`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictResolution {
+    /// Overwrite the cloud file with the last save this machine wrote.
+    KeepLocal,
+    /// Accept the cloud file as the latest save.
+    KeepRemote,
+}
+
+struct Manifest {
+    dir: PathBuf,
+    entries: HashMap<String, CloudSaveMeta>,
+}
+
+impl Manifest {
+    fn load(dir: PathBuf) -> io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+        let mut entries = HashMap::default();
+        match fs::read_to_string(dir.join(MANIFEST_FILE)) {
+            Ok(contents) => {
+                for line in contents.lines() {
+                    let mut fields = line.splitn(3, ' ');
+                    let (Some(hash), Some(timestamp), Some(name)) =
+                        (fields.next(), fields.next(), fields.next())
+                    else {
+                        continue;
+                    };
+                    let (Ok(hash), Ok(unix_timestamp)) =
+                        (u64::from_str_radix(hash, 16), timestamp.parse())
+                    else {
+                        continue;
+                    };
+                    entries.insert(
+                        name.to_owned(),
+                        CloudSaveMeta {
+                            unix_timestamp,
+                            hash,
+                        },
+                    );
+                }
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err),
+        }
+        Ok(Self { dir, entries })
+    }
+
+    fn store(&self) -> io::Result<()> {
+        let mut contents = String::new();
+        for (name, meta) in self.entries.iter() {
+            let _ = writeln!(
+                contents,
+                "{:016x} {} {}",
+                meta.hash, meta.unix_timestamp, name
+            );
+        }
+        fs::write(self.dir.join(MANIFEST_FILE), contents)
+    }
+
+    fn local_copy_path(&self, name: &str) -> PathBuf {
+        let mut file_name = String::with_capacity(name.len() * 2 + 4);
+        for byte in name.bytes() {
+            let _ = write!(file_name, "{byte:02x}");
+        }
+        file_name.push_str(".bin");
+        self.dir.join(file_name)
+    }
+}
+
+#[derive(Default)]
+struct TrackerState {
+    manifest: Option<Manifest>,
+    conflicts: Vec<CloudSaveConflict>,
+}
+
+/// Records the saves this machine writes so conflicting cloud saves can be detected.
+#[derive(Clone, Default)]
+pub(super) struct ConflictTracker(Arc<Mutex<TrackerState>>);
+
+impl ConflictTracker {
+    fn enable(&self, dir: PathBuf) -> io::Result<()> {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .manifest = Some(Manifest::load(dir)?);
+        Ok(())
+    }
+
+    pub(super) fn record(&self, name: &str, unix_timestamp: i64, payload: &[u8]) -> io::Result<()> {
+        let mut state = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        let Some(manifest) = state.manifest.as_mut() else {
+            return Ok(());
+        };
+        fs::write(manifest.local_copy_path(name), payload)?;
+        manifest
+            .entries
+            .insert(name.to_owned(), CloudSaveMeta::new(unix_timestamp, payload));
+        manifest.store()
+    }
+
+    pub(super) fn check(&self, name: &str, unix_timestamp: i64, payload: &[u8]) {
+        let mut state = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        let Some(local_meta) = state
+            .manifest
+            .as_ref()
+            .and_then(|manifest| manifest.entries.get(name))
+            .copied()
+        else {
+            return;
+        };
+        let remote_meta = CloudSaveMeta::new(unix_timestamp, payload);
+        if remote_meta.hash != local_meta.hash
+            && remote_meta.unix_timestamp != local_meta.unix_timestamp
+        {
+            state.conflicts.push(CloudSaveConflict {
+                name: name.to_owned(),
+                local_meta,
+                remote_meta,
+            });
+        }
+    }
+
+    fn local_copy(&self, name: &str) -> Option<io::Result<Vec<u8>>> {
+        let state = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        let manifest = state.manifest.as_ref()?;
+        if !manifest.entries.contains_key(name) {
+            return None;
+        }
+        Some(fs::read(manifest.local_copy_path(name)))
+    }
+
+    fn take_conflicts(&self) -> Vec<CloudSaveConflict> {
+        std::mem::take(
+            &mut self
+                .0
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .conflicts,
+        )
+    }
+}
+
+impl SteamCloudSaves {
+    /// Enables conflict detection, storing the manifest and local copies of saves
+    /// in `dir`.
+    ///
+    /// Every save written through this resource is then recorded locally with its
+    /// timestamp and content hash. When [`SteamCloudSaves::read`] or
+    /// [`SteamCloudSaves::load`] finds that the cloud copy has changed since this
+    /// machine last wrote it, a [`CloudSaveConflict`] event is sent.
+    ///
+    /// `dir` should be a machine-local directory that is not synced to Steam Cloud.
+    pub fn enable_conflict_detection(&self, dir: impl Into<PathBuf>) -> io::Result<()> {
+        self.conflicts.enable(dir.into())
+    }
+
+    /// Resolves a [`CloudSaveConflict`] for the file `name`.
+    pub fn resolve_conflict(
+        &self,
+        name: &str,
+        resolution: ConflictResolution,
+    ) -> Result<(), CloudSaveError> {
+        match resolution {
+            ConflictResolution::KeepLocal => {
+                let payload = self
+                    .conflicts
+                    .local_copy(name)
+                    .ok_or(CloudSaveError::NotFound)??;
+                self.write(name, &payload)
+            }
+            ConflictResolution::KeepRemote => {
+                let (payload, unix_timestamp) = self.read_unchecked(name)?;
+                self.conflicts.record(name, unix_timestamp, &payload)?;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A 64-bit FNV-1a hash, chosen for being stable across Rust versions and platforms.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash = 0xcbf2_9ce4_8422_2325_u64;
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    hash
+}
+
+pub(super) fn flush_conflicts(
+    saves: Res<SteamCloudSaves>,
+    mut conflicts: EventWriter<CloudSaveConflict>,
+) {
+    conflicts.send_batch(saves.conflicts.take_conflicts());
+}
@@ -0,0 +1,147 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use bevy_ecs::{
+    event::{Event, EventWriter},
+    system::{Res, ResMut, Resource},
+};
+use steamworks::sys;
+
+use crate::Client;
+
+/// Sent when the available Steam Cloud quota drops below
+/// [`SteamCloudQuota::low_space_threshold`].
+#[derive(Event, Debug, Clone, Copy)]
+pub struct CloudQuotaLow {
+    /// The total number of bytes the user is allowed to store for this app.
+    pub total_bytes: u64,
+    /// The number of bytes still available.
+    pub available_bytes: u64,
+}
+
+/// Sent when Steam Cloud is enabled or disabled for the user's account or this app.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct CloudSyncStateChanged {
+    /// Whether Steam Cloud is enabled for the user's account.
+    pub enabled_for_account: bool,
+    /// Whether Steam Cloud is enabled for this app.
+    pub enabled_for_app: bool,
+}
+
+/// The current user's Steam Cloud quota for this app.
+///
+/// Refreshed at startup and after every write performed through [`SteamCloudSaves`].
+///
+/// [`SteamCloudSaves`]: crate::SteamCloudSaves
+#[derive(Resource, Debug, Clone)]
+pub struct SteamCloudQuota {
+    /// The total number of bytes the user is allowed to store for this app.
+    pub total_bytes: u64,
+    /// The number of bytes still available.
+    pub available_bytes: u64,
+    /// Whether Steam Cloud is enabled for the user's account.
+    pub is_cloud_enabled_for_account: bool,
+    /// Whether Steam Cloud is enabled for this app.
+    pub is_cloud_enabled_for_app: bool,
+    /// When `available_bytes` drops below this, a [`CloudQuotaLow`] event is sent.
+    pub low_space_threshold: u64,
+}
+
+impl SteamCloudQuota {
+    /// Checks if there is enough quota left to store `bytes` more bytes.
+    pub fn has_room_for(&self, bytes: u64) -> bool {
+        self.available_bytes >= bytes
+    }
+
+    fn refresh(&mut self, client: &Client) {
+        let remote_storage = client.remote_storage();
+        self.is_cloud_enabled_for_account = remote_storage.is_cloud_enabled_for_account();
+        self.is_cloud_enabled_for_app = remote_storage.is_cloud_enabled_for_app();
+        // steamworks does not wrap GetQuota, so it is called directly.
+        // SAFETY: The Steam API is initialized for as long as a `Client` exists.
+        unsafe {
+            let remote_storage = sys::SteamAPI_SteamRemoteStorage_v016();
+            let mut total = 0;
+            let mut available = 0;
+            if sys::SteamAPI_ISteamRemoteStorage_GetQuota(
+                remote_storage,
+                &mut total,
+                &mut available,
+            ) {
+                self.total_bytes = total;
+                self.available_bytes = available;
+            }
+        }
+    }
+}
+
+/// Marks [`SteamCloudQuota`] as needing a refresh after a write.
+#[derive(Clone, Default)]
+pub(super) struct QuotaStale(Arc<AtomicBool>);
+
+impl QuotaStale {
+    pub(super) fn mark(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    fn take(&self) -> bool {
+        self.0.swap(false, Ordering::Relaxed)
+    }
+}
+
+#[derive(Resource)]
+pub(super) struct QuotaTracker(pub(super) QuotaStale);
+
+pub(super) fn init_quota(client: &Client) -> SteamCloudQuota {
+    let mut quota = SteamCloudQuota {
+        total_bytes: 0,
+        available_bytes: 0,
+        is_cloud_enabled_for_account: false,
+        is_cloud_enabled_for_app: false,
+        low_space_threshold: 1024 * 1024,
+    };
+    quota.refresh(client);
+    quota
+}
+
+pub(super) fn refresh_quota(
+    client: Res<Client>,
+    tracker: Res<QuotaTracker>,
+    mut quota: ResMut<SteamCloudQuota>,
+    mut low: EventWriter<CloudQuotaLow>,
+) {
+    if !tracker.0.take() {
+        return;
+    }
+    let was_low = quota.available_bytes < quota.low_space_threshold;
+    quota.refresh(&client);
+    if !was_low && quota.available_bytes < quota.low_space_threshold {
+        low.send(CloudQuotaLow {
+            total_bytes: quota.total_bytes,
+            available_bytes: quota.available_bytes,
+        });
+    }
+}
+
+pub(super) fn track_cloud_sync_state(
+    client: Res<Client>,
+    mut quota: ResMut<SteamCloudQuota>,
+    mut changed: EventWriter<CloudSyncStateChanged>,
+) {
+    let remote_storage = client.remote_storage();
+    let enabled_for_account = remote_storage.is_cloud_enabled_for_account();
+    let enabled_for_app = remote_storage.is_cloud_enabled_for_app();
+    if enabled_for_account == quota.is_cloud_enabled_for_account
+        && enabled_for_app == quota.is_cloud_enabled_for_app
+    {
+        return;
+    }
+    quota.is_cloud_enabled_for_account = enabled_for_account;
+    quota.is_cloud_enabled_for_app = enabled_for_app;
+    changed.send(CloudSyncStateChanged {
+        enabled_for_account,
+        enabled_for_app,
+    });
+}
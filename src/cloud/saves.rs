@@ -0,0 +1,408 @@
+use std::{ffi::CString, fmt, io};
+
+use bevy_ecs::system::Resource;
+use bevy_log::warn;
+use steamworks::sys;
+
+use crate::{Client, ShuttingDown};
+
+use super::{conflicts::ConflictTracker, quota::QuotaStale, writer::CloudWriteQueue};
+
+#[cfg(feature = "serde")]
+use serde::{de::DeserializeOwned, Serialize};
+
+/// The magic bytes at the start of every save written by [`SteamCloudSaves`].
+const MAGIC: [u8; 4] = *b"BSCS";
+/// The current version of the save file format.
+///
+/// Saves are written as [`MAGIC`], followed by this version as a little-endian
+/// `u16`, followed by the value encoded with `bincode`.
+const FORMAT_VERSION: u16 = 1;
+const HEADER_LEN: usize = MAGIC.len() + std::mem::size_of::<u16>();
+
+/// An error from loading or saving through [`SteamCloudSaves`].
+#[derive(Debug)]
+pub enum CloudSaveError {
+    /// No file with the given name exists in Steam Cloud.
+    NotFound,
+    /// Steam Cloud is disabled for the user's account or for this app.
+    ///
+    /// See [`SteamCloudSaves::set_cloud_enabled_for_app`].
+    CloudDisabled,
+    /// Reading or writing the file failed.
+    Io(io::Error),
+    /// The file is not a save written by [`SteamCloudSaves`] or was truncated.
+    Corrupted,
+    /// The file was written with an incompatible save format version.
+    VersionMismatch {
+        /// The format version found in the file.
+        found: u16,
+        /// The format version this crate reads and writes.
+        expected: u16,
+    },
+    /// The value could not be encoded or decoded.
+    #[cfg(feature = "serde")]
+    Encoding(bincode::Error),
+    /// Steam is shutting down.
+    ShuttingDown,
+}
+
+impl fmt::Display for CloudSaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotFound => f.write_str("the cloud save does not exist"),
+            Self::CloudDisabled => f.write_str("Steam Cloud is disabled"),
+            Self::Io(err) => write!(f, "failed to access the cloud save: {err}"),
+            Self::Corrupted => f.write_str("the cloud save is corrupted"),
+            Self::VersionMismatch { found, expected } => write!(
+                f,
+                "the cloud save has format version {found}, expected {expected}"
+            ),
+            #[cfg(feature = "serde")]
+            Self::Encoding(err) => write!(f, "failed to encode or decode the cloud save: {err}"),
+            Self::ShuttingDown => write!(f, "{ShuttingDown}"),
+        }
+    }
+}
+
+impl std::error::Error for CloudSaveError {}
+
+impl From<io::Error> for CloudSaveError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<ShuttingDown> for CloudSaveError {
+    fn from(_: ShuttingDown) -> Self {
+        Self::ShuttingDown
+    }
+}
+
+/// Metadata about a file stored in Steam Cloud, as returned by [`SteamCloudSaves::list`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CloudFileInfo {
+    /// The full name of the file, including any `/` separated prefix.
+    pub name: String,
+    /// The size of the file in bytes.
+    pub size: u64,
+    /// When the file was last written, as a Unix timestamp.
+    pub timestamp: i64,
+    /// Whether the file has been synced to Steam Cloud rather than only existing
+    /// locally. Only populated by [`SteamCloudSaves::list_with_persistence`].
+    pub persisted: Option<bool>,
+}
+
+/// Metadata about a single Steam Cloud file, as returned by [`SteamCloudSaves::metadata`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CloudFileMeta {
+    /// The size of the file in bytes.
+    pub size: u64,
+    /// When the file was last written, as a Unix timestamp.
+    pub unix_timestamp: i64,
+    /// Whether the file has been synced to Steam Cloud. `false` if the file so far
+    /// only exists locally.
+    pub persisted: bool,
+}
+
+/// Reads and writes versioned save files in Steam Cloud.
+///
+/// With the `serde` feature enabled, any serializable value can be stored with
+/// [`SteamCloudSaves::save`] and read back with [`SteamCloudSaves::load`]. Values are
+/// encoded with `bincode` behind a small header carrying a format version, and
+/// corrupted or mismatched files are reported as a [`CloudSaveError`].
+#[derive(Resource, Clone)]
+pub struct SteamCloudSaves {
+    pub(super) client: Client,
+    pub(super) quota_stale: QuotaStale,
+    pub(super) writes: CloudWriteQueue,
+    pub(super) conflicts: ConflictTracker,
+}
+
+impl SteamCloudSaves {
+    pub(super) fn new(
+        client: &Client,
+        quota_stale: QuotaStale,
+        writes: CloudWriteQueue,
+        conflicts: ConflictTracker,
+    ) -> Self {
+        Self {
+            client: client.clone(),
+            quota_stale,
+            writes,
+            conflicts,
+        }
+    }
+
+    /// Checks if Steam Cloud is enabled for the user's account. Returns `false`
+    /// once Steam is shutting down.
+    pub fn is_cloud_enabled_for_account(&self) -> bool {
+        !self.client.is_shutting_down()
+            && self.client.remote_storage().is_cloud_enabled_for_account()
+    }
+
+    /// Checks if Steam Cloud is enabled for this app. Returns `false` once Steam is
+    /// shutting down.
+    pub fn is_cloud_enabled_for_app(&self) -> bool {
+        !self.client.is_shutting_down() && self.client.remote_storage().is_cloud_enabled_for_app()
+    }
+
+    /// Enables or disables Steam Cloud for this app.
+    ///
+    /// A [`CloudSyncStateChanged`] event is sent once the change is observed.
+    ///
+    /// [`CloudSyncStateChanged`]: crate::CloudSyncStateChanged
+    pub fn set_cloud_enabled_for_app(&self, enabled: bool) -> Result<(), ShuttingDown> {
+        self.client.ensure_running()?;
+        self.client
+            .remote_storage()
+            .set_cloud_enabled_for_app(enabled);
+        Ok(())
+    }
+
+    /// Checks if a file with the given name exists in Steam Cloud. Returns `false`
+    /// once Steam is shutting down, or if `name` contains a nul character.
+    pub fn exists(&self, name: &str) -> bool {
+        !self.client.is_shutting_down()
+            && cloud_file_name(name).is_ok()
+            && self.client.remote_storage().file(name).exists()
+    }
+
+    /// Deletes a file from Steam Cloud. Returns `false` if the file could not be
+    /// deleted, including once Steam is shutting down or if `name` contains a nul
+    /// character.
+    pub fn delete(&self, name: &str) -> bool {
+        if self.client.is_shutting_down() || cloud_file_name(name).is_err() {
+            return false;
+        }
+        self.quota_stale.mark();
+        self.client.remote_storage().file(name).delete()
+    }
+
+    /// Lists the files in Steam Cloud, optionally only those whose names start with `prefix`.
+    ///
+    /// This only queries file metadata and never reads file contents. Returns no
+    /// files once Steam is shutting down.
+    pub fn list(&self, prefix: Option<&str>) -> Vec<CloudFileInfo> {
+        self.list_files(prefix, false)
+    }
+
+    /// Like [`SteamCloudSaves::list`], but also checks whether each file has been
+    /// synced to Steam Cloud.
+    pub fn list_with_persistence(&self, prefix: Option<&str>) -> Vec<CloudFileInfo> {
+        self.list_files(prefix, true)
+    }
+
+    fn list_files(&self, prefix: Option<&str>, include_persisted: bool) -> Vec<CloudFileInfo> {
+        if self.client.is_shutting_down() {
+            return Vec::new();
+        }
+        let remote_storage = self.client.remote_storage();
+        remote_storage
+            .files()
+            .into_iter()
+            .filter(|info| prefix.map_or(true, |prefix| info.name.starts_with(prefix)))
+            .map(|info| {
+                let file = remote_storage.file(&info.name);
+                CloudFileInfo {
+                    timestamp: file.timestamp(),
+                    persisted: include_persisted.then(|| file.is_persisted()),
+                    name: info.name,
+                    size: info.size,
+                }
+            })
+            .collect()
+    }
+
+    /// Gets the size, timestamp, and sync state of a single file.
+    ///
+    /// Returns `None` if the file does not exist or Steam is shutting down. A file
+    /// that exists but has not been synced yet is reported with `persisted` set to
+    /// `false`.
+    pub fn metadata(&self, name: &str) -> Option<CloudFileMeta> {
+        if self.client.is_shutting_down() {
+            return None;
+        }
+        let c_name = cloud_file_name(name).ok()?;
+        let file = self.client.remote_storage().file(name);
+        if !file.exists() {
+            return None;
+        }
+        // steamworks does not wrap GetFileSize, so it is called directly.
+        // SAFETY: The Steam API is initialized for as long as a `Client` exists.
+        let size = unsafe {
+            sys::SteamAPI_ISteamRemoteStorage_GetFileSize(
+                sys::SteamAPI_SteamRemoteStorage_v016(),
+                c_name.as_ptr(),
+            )
+        };
+        Some(CloudFileMeta {
+            size: size.max(0) as u64,
+            unix_timestamp: file.timestamp(),
+            persisted: file.is_persisted(),
+        })
+    }
+
+    /// Writes `payload` to Steam Cloud behind the save header.
+    ///
+    /// Fails with [`CloudSaveError::CloudDisabled`] if Steam Cloud is disabled.
+    pub fn write(&self, name: &str, payload: &[u8]) -> Result<(), CloudSaveError> {
+        self.client.ensure_running()?;
+        write_file(
+            &self.client,
+            &self.quota_stale,
+            &self.conflicts,
+            name,
+            payload,
+        )
+    }
+
+    /// Writes `payload` to Steam Cloud behind the save header on a background thread.
+    ///
+    /// Completes with a [`CloudFileWritten`] event. Writes are performed in the order
+    /// they are requested, and any still in flight when [`AppExit`] is sent are
+    /// flushed before the app exits. New writes can't be queued once Steam is
+    /// shutting down.
+    ///
+    /// [`CloudFileWritten`]: crate::CloudFileWritten
+    /// [`AppExit`]: bevy_app::AppExit
+    pub fn write_async(
+        &self,
+        name: impl Into<String>,
+        payload: Vec<u8>,
+    ) -> Result<(), ShuttingDown> {
+        self.client.ensure_running()?;
+        self.writes.write(name.into(), payload);
+        Ok(())
+    }
+
+    /// Reads a file written by [`SteamCloudSaves::write`] and returns its payload.
+    ///
+    /// If conflict detection is enabled, this may send a [`CloudSaveConflict`] event.
+    ///
+    /// [`CloudSaveConflict`]: crate::CloudSaveConflict
+    pub fn read(&self, name: &str) -> Result<Vec<u8>, CloudSaveError> {
+        let (payload, unix_timestamp) = self.read_unchecked(name)?;
+        self.conflicts.check(name, unix_timestamp, &payload);
+        Ok(payload)
+    }
+
+    /// Reads a save's payload and timestamp without checking for conflicts.
+    pub(super) fn read_unchecked(&self, name: &str) -> Result<(Vec<u8>, i64), CloudSaveError> {
+        let mut bytes = self.read_raw(name)?;
+        let unix_timestamp = self.client.remote_storage().file(name).timestamp();
+        if bytes.len() < HEADER_LEN || bytes[..MAGIC.len()] != MAGIC {
+            return Err(CloudSaveError::Corrupted);
+        }
+        let found = u16::from_le_bytes([bytes[MAGIC.len()], bytes[MAGIC.len() + 1]]);
+        if found != FORMAT_VERSION {
+            return Err(CloudSaveError::VersionMismatch {
+                found,
+                expected: FORMAT_VERSION,
+            });
+        }
+        bytes.drain(..HEADER_LEN);
+        Ok((bytes, unix_timestamp))
+    }
+
+    /// Reads the raw contents of a file in one call.
+    ///
+    /// steamworks reads by polling for a call result, which running callbacks at
+    /// the same time would consume, so this reads with `FileRead` instead while
+    /// callbacks are not being run.
+    pub(super) fn read_raw(&self, name: &str) -> Result<Vec<u8>, CloudSaveError> {
+        self.client.ensure_running()?;
+        let c_name = cloud_file_name(name)?;
+        self.client.with_raw(|_| {
+            // SAFETY: The Steam API is initialized for as long as a `Client` exists, and
+            // `bytes` outlives the call.
+            unsafe {
+                let remote_storage = sys::SteamAPI_SteamRemoteStorage_v016();
+                if !sys::SteamAPI_ISteamRemoteStorage_FileExists(remote_storage, c_name.as_ptr()) {
+                    return Err(CloudSaveError::NotFound);
+                }
+                let size =
+                    sys::SteamAPI_ISteamRemoteStorage_GetFileSize(remote_storage, c_name.as_ptr());
+                let mut bytes = vec![0; size.max(0) as usize];
+                let read = sys::SteamAPI_ISteamRemoteStorage_FileRead(
+                    remote_storage,
+                    c_name.as_ptr(),
+                    bytes.as_mut_ptr().cast(),
+                    size,
+                );
+                if read != size {
+                    return Err(io::Error::other("Steam Cloud failed to read the file").into());
+                }
+                Ok(bytes)
+            }
+        })
+    }
+
+    /// Serializes `value` and writes it to Steam Cloud.
+    #[cfg(feature = "serde")]
+    pub fn save<T: Serialize>(&self, name: &str, value: &T) -> Result<(), CloudSaveError> {
+        let payload = bincode::serialize(value).map_err(CloudSaveError::Encoding)?;
+        self.write(name, &payload)
+    }
+
+    /// Reads and deserializes a value saved with [`SteamCloudSaves::save`].
+    #[cfg(feature = "serde")]
+    pub fn load<T: DeserializeOwned>(&self, name: &str) -> Result<T, CloudSaveError> {
+        let payload = self.read(name)?;
+        bincode::deserialize(&payload).map_err(CloudSaveError::Encoding)
+    }
+}
+
+/// Converts a file name into a C string for Steam.
+///
+/// steamworks panics on names containing a nul character, so every name given by
+/// the user is checked with this before it is passed on.
+pub(super) fn cloud_file_name(name: &str) -> io::Result<CString> {
+    CString::new(name).map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))
+}
+
+pub(super) fn write_file(
+    client: &Client,
+    quota_stale: &QuotaStale,
+    conflicts: &ConflictTracker,
+    name: &str,
+    payload: &[u8],
+) -> Result<(), CloudSaveError> {
+    let remote_storage = client.remote_storage();
+    if !remote_storage.is_cloud_enabled_for_account() || !remote_storage.is_cloud_enabled_for_app()
+    {
+        return Err(CloudSaveError::CloudDisabled);
+    }
+    let mut bytes = Vec::with_capacity(HEADER_LEN + payload.len());
+    bytes.extend_from_slice(&MAGIC);
+    bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+    bytes.extend_from_slice(payload);
+    let c_name = cloud_file_name(name)?;
+    let len = i32::try_from(bytes.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "the cloud save is too large"))?;
+    // steamworks only writes through a stream, which commits whatever was written
+    // when dropped, so a failed write would leave a truncated save. FileWrite
+    // replaces the file in one call instead.
+    // SAFETY: The Steam API is initialized for as long as a `Client` exists, and
+    // `bytes` outlives the call.
+    let written = unsafe {
+        sys::SteamAPI_ISteamRemoteStorage_FileWrite(
+            sys::SteamAPI_SteamRemoteStorage_v016(),
+            c_name.as_ptr(),
+            bytes.as_ptr().cast(),
+            len,
+        )
+    };
+    quota_stale.mark();
+    if !written {
+        return Err(io::Error::other("Steam Cloud rejected the write").into());
+    }
+    let unix_timestamp = remote_storage.file(name).timestamp();
+    if let Err(err) = conflicts.record(name, unix_timestamp, payload) {
+        warn!(
+            "Failed to record cloud save {:?} for conflict detection: {}",
+            name, err
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,358 @@
+use std::{
+    ffi::CStr,
+    fs,
+    io::{self, Read, Write},
+    path::Path,
+    pin::Pin,
+    sync::{Arc, Mutex, PoisonError},
+    task::{Context, Poll, Waker},
+    thread::{self, JoinHandle},
+};
+
+use bevy_utils::synccell::SyncCell;
+use futures_io::{AsyncRead, AsyncWrite};
+use steamworks::{sys, ClientManager, SteamFileWriter};
+
+use super::{saves::cloud_file_name, CloudSaveError, SteamCloudSaves};
+use crate::Client;
+
+/// The default number of bytes read or written per chunk by [`CloudReader`] and
+/// [`CloudWriter`].
+pub const DEFAULT_CLOUD_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Streams the raw contents of a Steam Cloud file without buffering the whole file.
+///
+/// Implements both [`Read`] and [`AsyncRead`]. Each read returns at most
+/// `chunk_size` bytes. Created via [`SteamCloudSaves::open_read`].
+///
+/// Steam callbacks are not run while a chunk is being read, so [`Read::read`]
+/// blocks both the calling thread and [`SteamworksSystem::RunCallbacks`] until the
+/// chunk arrives. [`AsyncRead`] reads each chunk on a separate thread instead and
+/// wakes the task once it is done.
+///
+/// [`SteamworksSystem::RunCallbacks`]: crate::SteamworksSystem::RunCallbacks
+pub struct CloudReader {
+    client: Client,
+    name: Arc<CStr>,
+    offset: usize,
+    size: usize,
+    chunk_size: usize,
+    pending: Option<PendingChunk>,
+}
+
+/// A chunk being read on another thread for [`AsyncRead`].
+struct PendingChunk {
+    state: Arc<Mutex<ChunkState>>,
+    thread: JoinHandle<()>,
+}
+
+struct ChunkState {
+    data: Option<io::Result<Vec<u8>>>,
+    waker: Option<Waker>,
+}
+
+impl PendingChunk {
+    fn spawn(reader: &CloudReader, len: usize, waker: Waker) -> io::Result<Self> {
+        let state = Arc::new(Mutex::new(ChunkState {
+            data: None,
+            waker: Some(waker),
+        }));
+        let client = reader.client.clone();
+        let name = reader.name.clone();
+        let offset = reader.offset;
+        let thread_state = state.clone();
+        let thread = thread::Builder::new()
+            .name("steam-cloud-reader".into())
+            .spawn(move || {
+                let data = read_chunk(&client, &name, offset, len);
+                let mut state = thread_state.lock().unwrap_or_else(PoisonError::into_inner);
+                state.data = Some(data);
+                let waker = state.waker.take();
+                drop(state);
+                if let Some(waker) = waker {
+                    waker.wake();
+                }
+            })?;
+        Ok(Self { state, thread })
+    }
+
+    /// Takes the chunk if it has been read, otherwise registers `waker` to be woken
+    /// once it is.
+    fn poll(&self, waker: &Waker) -> Option<io::Result<Vec<u8>>> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let data = state.data.take();
+        if data.is_none() {
+            state.waker = Some(waker.clone());
+        }
+        data
+    }
+
+    fn wait(self) -> io::Result<Vec<u8>> {
+        let _ = self.thread.join();
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state
+            .data
+            .take()
+            .unwrap_or_else(|| Err(io::Error::other("the cloud reader thread panicked")))
+    }
+}
+
+impl CloudReader {
+    /// Sets the maximum number of bytes returned by a single read.
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    fn next_chunk_len(&self, buf_len: usize) -> usize {
+        buf_len.min(self.chunk_size).min(self.size - self.offset)
+    }
+
+    fn consume(&mut self, data: Vec<u8>, buf: &mut [u8]) -> usize {
+        let len = data.len().min(buf.len());
+        buf[..len].copy_from_slice(&data[..len]);
+        self.offset += len;
+        len
+    }
+}
+
+impl Drop for CloudReader {
+    fn drop(&mut self) {
+        // Wait for any chunk still being read, so the thread never holds the last
+        // client and shuts Steam down.
+        if let Some(pending) = self.pending.take() {
+            let _ = pending.wait();
+        }
+    }
+}
+
+impl Read for CloudReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let data = match self.pending.take() {
+            Some(pending) => pending.wait()?,
+            None => match self.next_chunk_len(buf.len()) {
+                0 => return Ok(0),
+                len => read_chunk(&self.client, &self.name, self.offset, len)?,
+            },
+        };
+        Ok(self.consume(data, buf))
+    }
+}
+
+impl AsyncRead for CloudReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        if this.pending.is_none() {
+            let len = this.next_chunk_len(buf.len());
+            if len == 0 {
+                return Poll::Ready(Ok(0));
+            }
+            match PendingChunk::spawn(this, len, cx.waker().clone()) {
+                Ok(pending) => this.pending = Some(pending),
+                Err(err) => return Poll::Ready(Err(err)),
+            }
+        }
+        let Some(data) = this
+            .pending
+            .as_ref()
+            .and_then(|pending| pending.poll(cx.waker()))
+        else {
+            return Poll::Pending;
+        };
+        if let Some(pending) = this.pending.take() {
+            let _ = pending.thread.join();
+        }
+        Poll::Ready(data.map(|data| this.consume(data, buf)))
+    }
+}
+
+/// Reads up to `len` bytes of the file `name`, starting at `offset`.
+///
+/// The read waits on a call result that running callbacks would consume, so it
+/// holds off callbacks until the chunk arrives.
+fn read_chunk(client: &Client, name: &CStr, offset: usize, len: usize) -> io::Result<Vec<u8>> {
+    client.with_raw(|_| {
+        // steamworks' reader can't be sent between threads, so the read is done
+        // directly.
+        // SAFETY: The Steam API is initialized for as long as a `Client` exists, and
+        // `data` outlives the call that writes to it.
+        unsafe {
+            let remote_storage = sys::SteamAPI_SteamRemoteStorage_v016();
+            let utils = sys::SteamAPI_SteamUtils_v010();
+            let call = sys::SteamAPI_ISteamRemoteStorage_FileReadAsync(
+                remote_storage,
+                name.as_ptr(),
+                offset as _,
+                len as _,
+            );
+            let mut failed = false;
+            while !sys::SteamAPI_ISteamUtils_IsAPICallCompleted(utils, call, &mut failed) {
+                thread::yield_now();
+            }
+            let mut result: sys::RemoteStorageFileReadAsyncComplete_t = std::mem::zeroed();
+            let received = sys::SteamAPI_ISteamUtils_GetAPICallResult(
+                utils,
+                call,
+                (&mut result as *mut sys::RemoteStorageFileReadAsyncComplete_t).cast(),
+                std::mem::size_of::<sys::RemoteStorageFileReadAsyncComplete_t>() as _,
+                sys::RemoteStorageFileReadAsyncComplete_t_k_iCallback as _,
+                &mut failed,
+            );
+            if failed || !received || result.m_eResult != sys::EResult::k_EResultOK {
+                return Err(io::Error::other("Steam Cloud failed to read the file"));
+            }
+            let mut data = vec![0; result.m_cubRead as usize];
+            sys::SteamAPI_ISteamRemoteStorage_FileReadAsyncComplete(
+                remote_storage,
+                result.m_hFileReadAsync,
+                data.as_mut_ptr().cast(),
+                result.m_cubRead,
+            );
+            Ok(data)
+        }
+    })
+}
+
+/// Streams raw data into a Steam Cloud file without buffering the whole file.
+///
+/// Implements both [`Write`] and [`AsyncWrite`]. Each write accepts at most
+/// `chunk_size` bytes. The file is committed once the writer is dropped or closed.
+/// Created via [`SteamCloudSaves::open_write`].
+pub struct CloudWriter {
+    writer: Option<SyncCell<SteamFileWriter<ClientManager>>>,
+    chunk_size: usize,
+}
+
+impl CloudWriter {
+    /// Sets the maximum number of bytes accepted by a single write.
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    fn closed() -> io::Error {
+        io::Error::new(
+            io::ErrorKind::BrokenPipe,
+            "the cloud file was already closed",
+        )
+    }
+}
+
+impl Write for CloudWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = buf.len().min(self.chunk_size);
+        match self.writer.as_mut() {
+            Some(writer) => writer.get().write(&buf[..len]),
+            None => Err(Self::closed()),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.writer.as_mut() {
+            Some(writer) => writer.get().flush(),
+            None => Err(Self::closed()),
+        }
+    }
+}
+
+impl AsyncWrite for CloudWriter {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Poll::Ready(self.get_mut().write(buf))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(self.get_mut().flush())
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        // Dropping the underlying writer commits the file.
+        self.get_mut().writer = None;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl SteamCloudSaves {
+    /// Opens a Steam Cloud file for streamed reading.
+    ///
+    /// Unlike [`SteamCloudSaves::read`], this reads the file's raw contents with no
+    /// save header. Fails with [`CloudSaveError::Io`] if `name` contains a nul
+    /// character.
+    pub fn open_read(&self, name: &str) -> Result<CloudReader, CloudSaveError> {
+        self.client.ensure_running()?;
+        let c_name = cloud_file_name(name)?;
+        let meta = self.metadata(name).ok_or(CloudSaveError::NotFound)?;
+        Ok(CloudReader {
+            client: self.client.clone(),
+            name: c_name.into(),
+            offset: 0,
+            size: meta.size as usize,
+            chunk_size: DEFAULT_CLOUD_CHUNK_SIZE,
+            pending: None,
+        })
+    }
+
+    /// Opens a Steam Cloud file for streamed writing, replacing its contents.
+    ///
+    /// Unlike [`SteamCloudSaves::write`], this writes raw contents with no save header.
+    /// Fails with [`CloudSaveError::Io`] if `name` contains a nul character.
+    pub fn open_write(&self, name: &str) -> Result<CloudWriter, CloudSaveError> {
+        self.client.ensure_running()?;
+        cloud_file_name(name)?;
+        let remote_storage = self.client.remote_storage();
+        if !remote_storage.is_cloud_enabled_for_account()
+            || !remote_storage.is_cloud_enabled_for_app()
+        {
+            return Err(CloudSaveError::CloudDisabled);
+        }
+        self.quota_stale.mark();
+        Ok(CloudWriter {
+            writer: Some(SyncCell::new(remote_storage.file(name).write())),
+            chunk_size: DEFAULT_CLOUD_CHUNK_SIZE,
+        })
+    }
+
+    /// Streams the raw contents of a Steam Cloud file to `path` on disk.
+    ///
+    /// `progress` is called after every chunk with the number of bytes copied so far
+    /// and the total size of the file. Returns the number of bytes copied.
+    pub fn copy_cloud_to_disk(
+        &self,
+        name: &str,
+        path: impl AsRef<Path>,
+        mut progress: impl FnMut(u64, u64),
+    ) -> Result<u64, CloudSaveError> {
+        self.client.ensure_running()?;
+        let total = self
+            .client
+            .remote_storage()
+            .files()
+            .into_iter()
+            .find(|info| info.name == name)
+            .map_or(0, |info| info.size);
+        let mut reader = self.open_read(name)?;
+        let mut output = io::BufWriter::new(fs::File::create(path)?);
+        let mut buffer = vec![0; reader.chunk_size];
+        let mut copied = 0;
+        loop {
+            let read = match reader.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(read) => read,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err.into()),
+            };
+            output.write_all(&buffer[..read])?;
+            copied += read as u64;
+            progress(copied, total);
+        }
+        output.flush()?;
+        Ok(copied)
+    }
+}
@@ -0,0 +1,140 @@
+use std::{
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use bevy_app::AppExit;
+use bevy_ecs::{
+    event::{Event, EventReader, EventWriter},
+    system::{Res, ResMut, Resource},
+};
+use bevy_log::warn;
+use bevy_utils::synccell::SyncCell;
+
+use crate::Client;
+
+use super::{
+    conflicts::ConflictTracker, quota::QuotaStale, saves::write_file, CloudSaveError,
+    SteamCloudSaves,
+};
+
+/// How long to wait for in-flight writes to finish when the app exits.
+const EXIT_FLUSH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Sent when a write started with [`SteamCloudSaves::write_async`] completes.
+#[derive(Event, Debug)]
+pub struct CloudFileWritten {
+    /// The name of the file that was written.
+    pub name: String,
+    /// The result of the write.
+    pub result: Result<(), CloudSaveError>,
+}
+
+enum WriteRequest {
+    Write { name: String, payload: Vec<u8> },
+    Flush(Sender<()>),
+}
+
+/// The sending half of the background cloud writer.
+#[derive(Clone)]
+pub(super) struct CloudWriteQueue(Arc<CloudWriter>);
+
+struct CloudWriter {
+    requests: Option<Sender<WriteRequest>>,
+    thread: Option<JoinHandle<()>>,
+    /// Released only once the thread has finished, so the writer thread never
+    /// holds the last client and shuts Steam down.
+    _client: Client,
+}
+
+impl Drop for CloudWriter {
+    fn drop(&mut self) {
+        // The thread stops once its queue is closed, after finishing queued writes.
+        self.requests.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl CloudWriteQueue {
+    fn send(&self, request: WriteRequest) -> bool {
+        self.0
+            .requests
+            .as_ref()
+            .is_some_and(|requests| requests.send(request).is_ok())
+    }
+
+    pub(super) fn write(&self, name: String, payload: Vec<u8>) {
+        self.send(WriteRequest::Write { name, payload });
+    }
+
+    /// Blocks until every write queued so far has completed, or `timeout` elapses.
+    pub(super) fn flush(&self, timeout: Duration) -> bool {
+        let (done, wait) = mpsc::channel();
+        self.send(WriteRequest::Flush(done)) && wait.recv_timeout(timeout).is_ok()
+    }
+}
+
+#[derive(Resource)]
+pub(super) struct CloudWriteResults(SyncCell<Receiver<CloudFileWritten>>);
+
+/// Spawns the thread that performs writes queued via [`SteamCloudSaves::write_async`].
+///
+/// A single thread handles every write so that writes to the same file are
+/// always applied in the order they were requested.
+pub(super) fn spawn_writer(
+    client: &Client,
+    quota_stale: QuotaStale,
+    conflicts: ConflictTracker,
+) -> (CloudWriteQueue, CloudWriteResults) {
+    let (requests, request_rx) = mpsc::channel();
+    let (results, result_rx) = mpsc::channel();
+    let writer_client = client.clone();
+    let client = client.clone();
+    let thread = thread::Builder::new()
+        .name("steam-cloud-writer".into())
+        .spawn(move || {
+            for request in request_rx {
+                match request {
+                    WriteRequest::Write { name, payload } => {
+                        let result = write_file(&client, &quota_stale, &conflicts, &name, &payload);
+                        let _ = results.send(CloudFileWritten { name, result });
+                    }
+                    WriteRequest::Flush(done) => {
+                        let _ = done.send(());
+                    }
+                }
+            }
+        })
+        .expect("Failed to spawn the Steam Cloud writer thread");
+    let writer = CloudWriter {
+        requests: Some(requests),
+        thread: Some(thread),
+        _client: writer_client,
+    };
+    (
+        CloudWriteQueue(Arc::new(writer)),
+        CloudWriteResults(SyncCell::new(result_rx)),
+    )
+}
+
+pub(super) fn flush_write_results(
+    mut results: ResMut<CloudWriteResults>,
+    mut written: EventWriter<CloudFileWritten>,
+) {
+    written.send_batch(results.0.get().try_iter());
+}
+
+pub(super) fn flush_writes_on_exit(saves: Res<SteamCloudSaves>, mut exit: EventReader<AppExit>) {
+    if exit.read().next().is_none() {
+        return;
+    }
+    if !saves.writes.flush(EXIT_FLUSH_TIMEOUT) {
+        warn!("Timed out waiting for Steam Cloud writes to finish before exiting");
+    }
+}
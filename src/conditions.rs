@@ -0,0 +1,18 @@
+use bevy_ecs::system::Res;
+
+use crate::SteamDeviceInfo;
+
+/// A run condition that is true when running on a Steam Deck.
+pub fn on_steam_deck(device: Res<SteamDeviceInfo>) -> bool {
+    device.is_steam_deck
+}
+
+/// A run condition that is true while Steam is in Big Picture mode.
+pub fn in_big_picture(device: Res<SteamDeviceInfo>) -> bool {
+    device.is_big_picture
+}
+
+/// A run condition that is true if the game was launched while Steam was in VR.
+pub fn launched_in_vr(device: Res<SteamDeviceInfo>) -> bool {
+    device.launched_in_vr
+}
@@ -0,0 +1,167 @@
+use std::{collections::VecDeque, time::Duration};
+
+use bevy_app::{App, First, Plugin};
+use bevy_ecs::{
+    event::EventReader,
+    reflect::ReflectResource,
+    schedule::IntoSystemConfigs,
+    system::{Res, ResMut, Resource},
+};
+use bevy_reflect::Reflect;
+use bevy_time::{Real, Time};
+use bevy_utils::HashMap;
+
+use crate::{
+    sends_steam_events, shutdown::steam_running, Client, SteamCallbacks, SteamworksEvent,
+    SteamworksSystem,
+};
+
+/// The Steam client's state as last observed, mirrored by [`SteamworksDevToolsPlugin`].
+#[derive(Resource, Reflect, Debug, Clone, Default)]
+#[reflect(Resource)]
+pub struct SteamDevState {
+    /// Whether the Steam overlay is open.
+    pub overlay_active: bool,
+    /// Whether the client is connected to Steam's servers.
+    pub connected: bool,
+    /// How long the last run of Steam callbacks took.
+    pub last_callback_run: Duration,
+    /// The longest run of Steam callbacks so far.
+    pub max_callback_run: Duration,
+}
+
+/// A forwarded Steam event, as recorded in [`SteamEventLog`].
+#[derive(Reflect, Debug, Clone)]
+pub struct SteamEventLogEntry {
+    /// Seconds since startup when the event was forwarded.
+    pub elapsed_secs: f64,
+    /// The kind of event, such as `"PersonaStateChange"`.
+    pub kind: String,
+}
+
+/// The most recently forwarded [`SteamworksEvent`]s, mirrored by
+/// [`SteamworksDevToolsPlugin`].
+#[derive(Resource, Reflect, Debug, Clone, Default)]
+#[reflect(Resource)]
+pub struct SteamEventLog {
+    /// The recorded events, oldest first.
+    pub entries: VecDeque<SteamEventLogEntry>,
+    #[reflect(ignore)]
+    len: usize,
+}
+
+/// How many of each kind of [`SteamworksEvent`] has been forwarded, mirrored by
+/// [`SteamworksDevToolsPlugin`].
+#[derive(Resource, Reflect, Debug, Clone, Default)]
+#[reflect(Resource)]
+pub struct SteamEventCounts(pub HashMap<String, u64>);
+
+/// An opt-in [`Plugin`] that mirrors the plugin's state into reflected resources,
+/// so it can be browsed with inspectors such as `bevy-inspector-egui`.
+///
+/// Adds [`SteamDevState`], [`SteamEventLog`] and [`SteamEventCounts`], and times
+/// every run of Steam callbacks. Must be added after
+/// [`SteamworksPlugin`](crate::SteamworksPlugin).
+pub struct SteamworksDevToolsPlugin {
+    /// How many of the most recent events [`SteamEventLog`] keeps.
+    pub event_log_len: usize,
+}
+
+impl Default for SteamworksDevToolsPlugin {
+    fn default() -> Self {
+        Self { event_log_len: 64 }
+    }
+}
+
+impl Plugin for SteamworksDevToolsPlugin {
+    fn build(&self, app: &mut App) {
+        let connected = app.world().resource::<Client>().user().logged_on();
+        app.world_mut().resource_mut::<SteamCallbacks>().time_runs = true;
+        app.register_type::<SteamDevState>()
+            .register_type::<SteamEventLog>()
+            .register_type::<SteamEventCounts>()
+            .insert_resource(SteamDevState {
+                connected,
+                ..Default::default()
+            })
+            .insert_resource(SteamEventLog {
+                entries: VecDeque::with_capacity(self.event_log_len),
+                len: self.event_log_len,
+            })
+            .init_resource::<SteamEventCounts>();
+        if sends_steam_events(app) {
+            app.add_systems(
+                First,
+                mirror_steam_state
+                    .after(SteamworksSystem::RunCallbacks)
+                    .run_if(steam_running),
+            );
+        }
+    }
+}
+
+fn mirror_steam_state(
+    callbacks: Res<SteamCallbacks>,
+    time: Option<Res<Time<Real>>>,
+    mut events: EventReader<SteamworksEvent>,
+    mut state: ResMut<SteamDevState>,
+    mut log: ResMut<SteamEventLog>,
+    mut counts: ResMut<SteamEventCounts>,
+) {
+    if let Some(last_run) = callbacks.last_run {
+        state.last_callback_run = last_run;
+        state.max_callback_run = state.max_callback_run.max(last_run);
+    }
+    let elapsed_secs = time.map_or(0.0, |time| time.elapsed_seconds_f64());
+    for event in events.read() {
+        match event {
+            SteamworksEvent::GameOverlayActivated(overlay) => {
+                state.overlay_active = overlay.active;
+            }
+            SteamworksEvent::SteamServersConnected(_) => state.connected = true,
+            SteamworksEvent::SteamServersDisconnected(_) => state.connected = false,
+            _ => {}
+        }
+        let kind = event_kind(event);
+        match counts.0.get_mut(kind) {
+            Some(count) => *count += 1,
+            None => {
+                counts.0.insert(kind.to_owned(), 1);
+            }
+        }
+        if log.len == 0 {
+            continue;
+        }
+        if log.entries.len() >= log.len {
+            log.entries.pop_front();
+        }
+        log.entries.push_back(SteamEventLogEntry {
+            elapsed_secs,
+            kind: kind.to_owned(),
+        });
+    }
+}
+
+fn event_kind(event: &SteamworksEvent) -> &'static str {
+    match event {
+        SteamworksEvent::AuthSessionTicketResponse(_) => "AuthSessionTicketResponse",
+        SteamworksEvent::DownloadItemResult(_) => "DownloadItemResult",
+        SteamworksEvent::GameOverlayActivated(_) => "GameOverlayActivated",
+        SteamworksEvent::GameLobbyJoinRequested(_) => "GameLobbyJoinRequested",
+        SteamworksEvent::LobbyChatUpdate(_) => "LobbyChatUpdate",
+        SteamworksEvent::MicroTxnAuthorizationResponse(_) => "MicroTxnAuthorizationResponse",
+        SteamworksEvent::P2PSessionConnectFail(_) => "P2PSessionConnectFail",
+        SteamworksEvent::P2PSessionRequest(_) => "P2PSessionRequest",
+        SteamworksEvent::PersonaStateChange(_) => "PersonaStateChange",
+        SteamworksEvent::RemotePlayConnected(_) => "RemotePlayConnected",
+        SteamworksEvent::RemotePlayDisconnected(_) => "RemotePlayDisconnected",
+        SteamworksEvent::SteamServerConnectFailure(_) => "SteamServerConnectFailure",
+        SteamworksEvent::SteamServersConnected(_) => "SteamServersConnected",
+        SteamworksEvent::SteamServersDisconnected(_) => "SteamServersDisconnected",
+        SteamworksEvent::TicketForWebApiResponse(_) => "TicketForWebApiResponse",
+        SteamworksEvent::UserAchievementStored(_) => "UserAchievementStored",
+        SteamworksEvent::UserStatsReceived(_) => "UserStatsReceived",
+        SteamworksEvent::UserStatsStored(_) => "UserStatsStored",
+        SteamworksEvent::ValidateAuthTicketResponse(_) => "ValidateAuthTicketResponse",
+    }
+}
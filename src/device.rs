@@ -0,0 +1,132 @@
+use bevy_app::{App, First};
+use bevy_ecs::{
+    event::EventReader,
+    schedule::IntoSystemConfigs,
+    system::{Res, ResMut, Resource},
+};
+use steamworks::{sys, SteamDeviceFormFactor};
+
+use crate::{shutdown::steam_running, Client, SteamworksEvent, SteamworksSystem};
+
+/// Information about the device and mode Steam is running in.
+#[derive(Resource, Debug, Clone)]
+pub struct SteamDeviceInfo {
+    /// Whether Steam is running on a Steam Deck.
+    pub is_steam_deck: bool,
+    /// The kind of device Steam is running on.
+    pub form_factor: SteamDeviceFormFactor,
+    /// Whether Steam is in Big Picture mode. This is refreshed whenever the Steam
+    /// overlay is opened or closed, as Big Picture can be toggled at any time.
+    pub is_big_picture: bool,
+    /// Whether Steam was running in VR when the game was launched, which is the
+    /// case when it was launched from the SteamVR dashboard.
+    ///
+    /// Steam has no notification for entering or leaving VR, so this is only a
+    /// snapshot taken at launch.
+    pub launched_in_vr: bool,
+    /// Whether streaming to a VR headset is enabled for the game, as a snapshot
+    /// taken at launch.
+    pub is_vr_headset_streaming: bool,
+}
+
+impl SteamDeviceInfo {
+    fn new(client: &Client) -> Self {
+        let utils = client.utils();
+        // steamworks does not wrap the VR queries, so they are called directly.
+        // SAFETY: The Steam API is initialized for as long as a `Client` exists.
+        let (launched_in_vr, is_vr_headset_streaming) = unsafe {
+            let utils = sys::SteamAPI_SteamUtils_v010();
+            (
+                sys::SteamAPI_ISteamUtils_IsSteamRunningInVR(utils),
+                sys::SteamAPI_ISteamUtils_IsVRHeadsetStreamingEnabled(utils),
+            )
+        };
+        Self {
+            is_steam_deck: utils.is_steam_running_on_steam_deck(),
+            form_factor: utils.get_device_form_factor(),
+            is_big_picture: utils.is_steam_in_big_picture_mode(),
+            launched_in_vr,
+            is_vr_headset_streaming,
+        }
+    }
+}
+
+/// The country the user is connecting from.
+///
+/// This is Steam's GeoIP lookup of the user's IP address, not their store
+/// region, so it can be wrong or change mid-session, for example when the user
+/// reconnects through a VPN. Use [`SteamLocation::refresh`] to read it again.
+#[derive(Resource, Clone)]
+pub struct SteamLocation {
+    client: Client,
+    country: String,
+}
+
+impl SteamLocation {
+    fn new(client: &Client) -> Self {
+        Self {
+            client: client.clone(),
+            country: client.utils().ip_country(),
+        }
+    }
+
+    /// Gets the two-letter ISO 3166-1 country code, such as `DE`.
+    pub fn country(&self) -> &str {
+        &self.country
+    }
+
+    /// Checks if the user is in any of the given countries.
+    ///
+    /// ```rust no_run
+    /// # use bevy_steamworks::SteamLocation;
+    /// # fn check(location: &SteamLocation) {
+    /// let in_dach = location.is_in(&["DE", "AT", "CH"]);
+    /// # }
+    /// ```
+    pub fn is_in(&self, countries: &[&str]) -> bool {
+        countries
+            .iter()
+            .any(|country| country.eq_ignore_ascii_case(&self.country))
+    }
+
+    /// Reads the country from Steam again. Keeps the last country once Steam is
+    /// shutting down.
+    pub fn refresh(&mut self) {
+        if self.client.is_shutting_down() {
+            return;
+        }
+        self.country = self.client.utils().ip_country();
+    }
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    let client = app.world().resource::<Client>();
+    let device = SteamDeviceInfo::new(client);
+    let location = SteamLocation::new(client);
+    app.insert_resource(device)
+        .insert_resource(location)
+        .add_systems(
+            First,
+            refresh_big_picture
+                .after(SteamworksSystem::RunCallbacks)
+                .run_if(steam_running),
+        );
+}
+
+fn refresh_big_picture(
+    client: Res<Client>,
+    mut device: ResMut<SteamDeviceInfo>,
+    mut events: EventReader<SteamworksEvent>,
+) {
+    let mut overlay_changed = false;
+    for event in events.read() {
+        overlay_changed |= matches!(event, SteamworksEvent::GameOverlayActivated(_));
+    }
+    if !overlay_changed {
+        return;
+    }
+    let is_big_picture = client.utils().is_steam_in_big_picture_mode();
+    if device.is_big_picture != is_big_picture {
+        device.is_big_picture = is_big_picture;
+    }
+}
@@ -0,0 +1,321 @@
+use std::collections::{vec_deque, VecDeque};
+
+use bevy_ecs::event::Event;
+use bevy_utils::HashMap;
+use steamworks::SteamId;
+
+use crate::SteamworksEvent;
+
+/// The capacity each buffer starts with and is never shrunk below.
+const MIN_CAPACITY: usize = 64;
+/// How many frames in a row usage must stay low before the buffers shrink.
+const SHRINK_AFTER_FRAMES: u32 = 300;
+
+/// What to do with a Steam event that arrives while the buffer of events waiting
+/// to be forwarded is full, set with
+/// [`SteamworksPlugin::with_max_pending_events`](crate::SteamworksPlugin::with_max_pending_events).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EventOverflowPolicy {
+    /// Drop the oldest buffered event to make room.
+    #[default]
+    DropOldest,
+    /// Drop the event that arrived.
+    DropNewest,
+    /// Panic, for catching floods of events in debug builds.
+    Panic,
+}
+
+/// Sent when Steam events were dropped because too many were buffered at once.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct SteamEventsDropped {
+    /// How many events were dropped since the last frame.
+    pub count: usize,
+}
+
+/// How the buffer of Steam events waiting to be forwarded is managed, set
+/// through [`SteamworksPlugin`](crate::SteamworksPlugin).
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct EventBufferConfig {
+    /// The most capacity kept between frames.
+    pub(crate) retained_capacity: usize,
+    /// Whether `PersonaStateChange`s for the same user within a frame are merged.
+    pub(crate) coalesce_persona_changes: bool,
+    /// The most events buffered at once.
+    pub(crate) max_pending: usize,
+    /// What to do with events beyond `max_pending`.
+    pub(crate) overflow: EventOverflowPolicy,
+    /// The most events forwarded per frame, with the rest carried over.
+    pub(crate) max_per_frame: Option<usize>,
+}
+
+impl Default for EventBufferConfig {
+    fn default() -> Self {
+        Self {
+            retained_capacity: 1024,
+            coalesce_persona_changes: false,
+            max_pending: 65_536,
+            overflow: EventOverflowPolicy::DropOldest,
+            max_per_frame: None,
+        }
+    }
+}
+
+/// Steam events pushed by callbacks, waiting to be forwarded.
+///
+/// Two buffers are swapped every frame so that neither is reallocated while the
+/// number of events per frame stays within their capacity. Capacity grown by a
+/// burst of events is kept up to [`EventBufferConfig::retained_capacity`], and
+/// given back once usage has stayed low for a while.
+///
+/// Events that didn't fit in a frame's [`EventBufferConfig::max_per_frame`] stay at
+/// the front of the spare buffer, and count towards
+/// [`EventBufferConfig::max_pending`] until they are forwarded.
+pub(crate) struct EventBuffer {
+    config: EventBufferConfig,
+    pending: VecDeque<SteamworksEvent>,
+    spare: VecDeque<SteamworksEvent>,
+    low_usage_frames: u32,
+    /// How many events were dropped since the last flush.
+    dropped: usize,
+    /// The index of each user's last `PersonaStateChange` this frame.
+    last_persona_change: HashMap<SteamId, usize>,
+}
+
+impl EventBuffer {
+    pub(crate) fn new(config: EventBufferConfig) -> Self {
+        let capacity = MIN_CAPACITY.min(config.retained_capacity);
+        Self {
+            config,
+            pending: VecDeque::with_capacity(capacity),
+            spare: VecDeque::with_capacity(capacity),
+            low_usage_frames: 0,
+            dropped: 0,
+            last_persona_change: HashMap::default(),
+        }
+    }
+
+    pub(crate) fn push(&mut self, event: SteamworksEvent) {
+        if self.pending.len() + self.spare.len() >= self.config.max_pending.max(1) {
+            match self.config.overflow {
+                EventOverflowPolicy::DropOldest => {
+                    if self.spare.pop_front().is_none() {
+                        self.pending.pop_front();
+                    }
+                }
+                EventOverflowPolicy::DropNewest => {
+                    self.dropped += 1;
+                    return;
+                }
+                EventOverflowPolicy::Panic => panic!(
+                    "More than {} Steam events were buffered at once",
+                    self.config.max_pending
+                ),
+            }
+            self.dropped += 1;
+        }
+        self.pending.push_back(event);
+    }
+
+    /// Gets the events that have not been forwarded yet.
+    #[cfg(test)]
+    fn pending(&self) -> impl Iterator<Item = &SteamworksEvent> {
+        self.spare.iter().chain(&self.pending)
+    }
+
+    /// Forwards pending events to `forward`, oldest first and up to
+    /// [`EventBufferConfig::max_per_frame`], returning how many events were dropped
+    /// since the last flush.
+    pub(crate) fn flush(
+        &mut self,
+        forward: impl FnOnce(vec_deque::Drain<'_, SteamworksEvent>),
+    ) -> usize {
+        // The next frame's callbacks push into the other buffer, so draining this
+        // one keeps its capacity for the frame after.
+        if self.spare.is_empty() {
+            std::mem::swap(&mut self.pending, &mut self.spare);
+        } else {
+            // Events carried over from earlier frames go first.
+            self.spare.append(&mut self.pending);
+        }
+        let used = self.spare.len();
+        if self.config.coalesce_persona_changes {
+            self.coalesce_persona_changes();
+        }
+        let count = self
+            .config
+            .max_per_frame
+            .map_or(self.spare.len(), |max| max.max(1).min(self.spare.len()));
+        if count > 0 {
+            forward(self.spare.drain(..count));
+        }
+        self.trim(used);
+        std::mem::take(&mut self.dropped)
+    }
+
+    /// Merges every user's `PersonaStateChange`s into their last one, combining
+    /// the change flags.
+    fn coalesce_persona_changes(&mut self) {
+        self.last_persona_change.clear();
+        let mut merged = false;
+        for index in (0..self.spare.len()).rev() {
+            let SteamworksEvent::PersonaStateChange(change) = &self.spare[index] else {
+                continue;
+            };
+            let (steam_id, flags) = (change.steam_id, change.flags);
+            let Some(&last) = self.last_persona_change.get(&steam_id) else {
+                self.last_persona_change.insert(steam_id, index);
+                continue;
+            };
+            if let SteamworksEvent::PersonaStateChange(last) = &mut self.spare[last] {
+                last.flags |= flags;
+            }
+            merged = true;
+        }
+        if !merged {
+            return;
+        }
+        let mut index = 0;
+        self.spare.retain(|event| {
+            let keep = match event {
+                SteamworksEvent::PersonaStateChange(change) => {
+                    self.last_persona_change.get(&change.steam_id) == Some(&index)
+                }
+                _ => true,
+            };
+            index += 1;
+            keep
+        });
+    }
+
+    fn trim(&mut self, used: usize) {
+        let capacity = self.spare.capacity();
+        if capacity > self.config.retained_capacity {
+            self.spare.shrink_to(self.config.retained_capacity);
+        }
+        if capacity > MIN_CAPACITY && used * 4 < capacity {
+            self.low_usage_frames += 1;
+        } else {
+            self.low_usage_frames = 0;
+        }
+        if self.low_usage_frames >= SHRINK_AFTER_FRAMES {
+            let capacity = (used * 2).max(MIN_CAPACITY);
+            self.pending.shrink_to(capacity);
+            self.spare.shrink_to(capacity);
+            self.low_usage_frames = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use steamworks::{PersonaChange, PersonaStateChange};
+
+    use super::*;
+
+    fn persona_change(id: u64) -> SteamworksEvent {
+        SteamworksEvent::PersonaStateChange(PersonaStateChange {
+            steam_id: SteamId::from_raw(id),
+            flags: PersonaChange::NAME,
+        })
+    }
+
+    fn flush_ids(buffer: &mut EventBuffer) -> Vec<u64> {
+        let mut ids = Vec::new();
+        buffer.flush(|events| {
+            ids.extend(events.map(|event| match event {
+                SteamworksEvent::PersonaStateChange(change) => change.steam_id.raw(),
+                _ => unreachable!(),
+            }));
+        });
+        ids
+    }
+
+    #[test]
+    fn capacity_is_stable_after_warm_up() {
+        let mut buffer = EventBuffer::new(EventBufferConfig::default());
+        let mut next_id = 0;
+        let mut frame = |buffer: &mut EventBuffer| {
+            for _ in 0..200 {
+                buffer.push(persona_change(next_id));
+                next_id += 1;
+            }
+            assert_eq!(flush_ids(buffer).len(), 200);
+        };
+        // Both buffers grow once, as each takes its turn receiving events.
+        frame(&mut buffer);
+        frame(&mut buffer);
+        let capacities = (buffer.pending.capacity(), buffer.spare.capacity());
+        for _ in 0..100 {
+            frame(&mut buffer);
+            assert_eq!(
+                (buffer.pending.capacity(), buffer.spare.capacity()),
+                capacities
+            );
+        }
+    }
+
+    #[test]
+    fn capacity_beyond_retained_is_given_back() {
+        let mut buffer = EventBuffer::new(EventBufferConfig {
+            retained_capacity: 128,
+            ..Default::default()
+        });
+        for id in 0..1000 {
+            buffer.push(persona_change(id));
+        }
+        assert_eq!(flush_ids(&mut buffer).len(), 1000);
+        assert!(buffer.spare.capacity() < 1000);
+    }
+
+    #[test]
+    fn max_per_frame_carries_events_over_in_order() {
+        let mut buffer = EventBuffer::new(EventBufferConfig {
+            max_per_frame: Some(100),
+            ..Default::default()
+        });
+        for id in 0..1000 {
+            buffer.push(persona_change(id));
+        }
+        let mut forwarded = Vec::new();
+        for _ in 0..10 {
+            let ids = flush_ids(&mut buffer);
+            assert_eq!(ids.len(), 100);
+            forwarded.extend(ids);
+        }
+        assert_eq!(forwarded, (0..1000).collect::<Vec<_>>());
+        assert!(flush_ids(&mut buffer).is_empty());
+        assert_eq!(buffer.pending().count(), 0);
+    }
+
+    #[test]
+    fn carried_over_events_go_before_new_ones() {
+        let mut buffer = EventBuffer::new(EventBufferConfig {
+            max_per_frame: Some(3),
+            ..Default::default()
+        });
+        for id in 0..5 {
+            buffer.push(persona_change(id));
+        }
+        assert_eq!(flush_ids(&mut buffer), [0, 1, 2]);
+        buffer.push(persona_change(5));
+        assert_eq!(flush_ids(&mut buffer), [3, 4, 5]);
+    }
+
+    #[test]
+    fn carried_over_events_count_towards_max_pending() {
+        let mut buffer = EventBuffer::new(EventBufferConfig {
+            max_per_frame: Some(1),
+            max_pending: 3,
+            ..Default::default()
+        });
+        for id in 0..3 {
+            buffer.push(persona_change(id));
+        }
+        assert_eq!(flush_ids(&mut buffer), [0]);
+        buffer.push(persona_change(3));
+        buffer.push(persona_change(4));
+        assert_eq!(buffer.pending().count(), 3);
+        let dropped = buffer.flush(|_| {});
+        assert_eq!(dropped, 1);
+    }
+}
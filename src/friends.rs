@@ -0,0 +1,164 @@
+pub(crate) mod chat;
+
+pub use chat::{FriendChatMessage, FriendChatMessageKind};
+
+use bevy_app::{App, First};
+use bevy_ecs::{
+    event::EventReader,
+    schedule::IntoSystemConfigs,
+    system::{ResMut, Resource},
+};
+use steamworks::{FriendFlags, PersonaChange, SteamId};
+
+use crate::{shutdown::steam_running, Client, ShuttingDown, SteamworksEvent, SteamworksSystem};
+
+/// A friend of the local user, as listed by [`SteamFriendList`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FriendEntry {
+    /// The friend's Steam ID.
+    pub steam_id: SteamId,
+    /// The friend's persona name.
+    pub name: String,
+    /// The private nickname the local user gave the friend, if any.
+    pub nickname: Option<String>,
+}
+
+impl FriendEntry {
+    fn new(client: &Client, steam_id: SteamId) -> Self {
+        let friend = client.friends().get_friend(steam_id);
+        Self {
+            steam_id,
+            name: friend.name(),
+            nickname: friend.nick_name(),
+        }
+    }
+}
+
+/// The local user's friends, kept current as they change names or the local user
+/// changes their nicknames.
+#[derive(Resource)]
+pub struct SteamFriendList {
+    client: Client,
+    friends: Vec<FriendEntry>,
+}
+
+impl SteamFriendList {
+    fn new(client: &Client) -> Self {
+        let mut list = Self {
+            client: client.clone(),
+            friends: Vec::new(),
+        };
+        list.refresh();
+        list
+    }
+
+    /// Iterates over every friend.
+    pub fn iter(&self) -> impl Iterator<Item = &FriendEntry> {
+        self.friends.iter()
+    }
+
+    /// Gets a friend by Steam ID.
+    pub fn get(&self, steam_id: SteamId) -> Option<&FriendEntry> {
+        self.friends
+            .iter()
+            .find(|friend| friend.steam_id == steam_id)
+    }
+
+    /// Lists the friends from Steam again. Keeps the last list once Steam is
+    /// shutting down.
+    pub fn refresh(&mut self) {
+        if self.client.is_shutting_down() {
+            return;
+        }
+        self.friends = self
+            .client
+            .friends()
+            .get_friends(FriendFlags::IMMEDIATE)
+            .into_iter()
+            .map(|friend| FriendEntry::new(&self.client, friend.id()))
+            .collect();
+    }
+}
+
+/// Looks up how the local user's friends should be shown.
+#[derive(Resource)]
+pub struct SteamFriends {
+    client: Client,
+    /// Whether [`SteamFriends::display_name`] prefers nicknames over persona
+    /// names, as the Steam client does. Defaults to `true`.
+    pub prefer_nicknames: bool,
+}
+
+impl SteamFriends {
+    fn new(client: &Client) -> Self {
+        Self {
+            client: client.clone(),
+            prefer_nicknames: true,
+        }
+    }
+
+    /// Gets the private nickname the local user gave a user, if any. Returns
+    /// `None` once Steam is shutting down.
+    pub fn nickname(&self, steam_id: SteamId) -> Option<String> {
+        if self.client.is_shutting_down() {
+            return None;
+        }
+        self.client.friends().get_friend(steam_id).nick_name()
+    }
+
+    /// Gets the name to show for a user: their nickname if they have one and
+    /// [`SteamFriends::prefer_nicknames`] is set, otherwise their persona name.
+    pub fn display_name(&self, steam_id: SteamId) -> Result<String, ShuttingDown> {
+        self.client.ensure_running()?;
+        let friend = self.client.friends().get_friend(steam_id);
+        Ok(self
+            .prefer_nicknames
+            .then(|| friend.nick_name())
+            .flatten()
+            .unwrap_or_else(|| friend.name()))
+    }
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    let client = app.world().resource::<Client>();
+    let friends = SteamFriends::new(client);
+    let friend_list = SteamFriendList::new(client);
+    app.insert_resource(friends)
+        .insert_resource(friend_list)
+        .add_systems(
+            First,
+            track_friend_list
+                .after(SteamworksSystem::RunCallbacks)
+                .run_if(steam_running),
+        );
+}
+
+fn track_friend_list(mut list: ResMut<SteamFriendList>, mut events: EventReader<SteamworksEvent>) {
+    let mut relationships_changed = false;
+    for event in events.read() {
+        let SteamworksEvent::PersonaStateChange(change) = event else {
+            continue;
+        };
+        if change.flags.contains(PersonaChange::RELATIONSHIP_CHANGE) {
+            relationships_changed = true;
+            continue;
+        }
+        if !change
+            .flags
+            .intersects(PersonaChange::NAME | PersonaChange::NICKNAME)
+        {
+            continue;
+        }
+        let entry = FriendEntry::new(&list.client, change.steam_id);
+        if let Some(friend) = list
+            .friends
+            .iter_mut()
+            .find(|friend| friend.steam_id == change.steam_id)
+        {
+            *friend = entry;
+        }
+    }
+    if relationships_changed {
+        list.refresh();
+    }
+}
@@ -0,0 +1,140 @@
+use std::ffi::c_void;
+
+use bevy_app::{App, First};
+use bevy_ecs::{
+    event::{Event, EventWriter},
+    schedule::IntoSystemConfigs,
+    system::{Res, Resource},
+};
+use steamworks::{sys, Callback, SteamId};
+
+use crate::{
+    call_result::CallResults,
+    shutdown::{steam_running, RegisteredCallback},
+    Client, SteamworksSystem,
+};
+
+/// The most bytes read from a single chat message.
+const MAX_MESSAGE_LEN: usize = 8 * 1024;
+
+/// What a friend sent in a [`FriendChatMessage`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FriendChatMessageKind {
+    /// A plain text message.
+    Text(String),
+    /// An invite to join the local user's game.
+    GameInvite {
+        /// The connect string from the friend's rich presence.
+        connect: String,
+    },
+}
+
+/// Sent when a friend sends the local user a message through Steam chat while
+/// [`SteamworksPlugin::listen_for_friends_messages`] is enabled.
+///
+/// [`SteamworksPlugin::listen_for_friends_messages`]: crate::SteamworksPlugin::listen_for_friends_messages
+#[derive(Event, Debug, Clone, PartialEq, Eq)]
+pub struct FriendChatMessage {
+    /// The friend who sent the message.
+    pub friend: SteamId,
+    /// What the friend sent.
+    pub kind: FriendChatMessageKind,
+}
+
+/// `GameConnectedFriendChatMsg_t`, which steamworks does not wrap.
+struct FriendChatMsgCallback(SteamId, i32);
+
+unsafe impl Callback for FriendChatMsgCallback {
+    const ID: i32 = 343;
+    const SIZE: i32 = std::mem::size_of::<sys::GameConnectedFriendChatMsg_t>() as i32;
+
+    unsafe fn from_raw(raw: *mut c_void) -> Self {
+        let val = &mut *(raw as *mut sys::GameConnectedFriendChatMsg_t);
+        Self(
+            SteamId::from_raw(val.m_steamIDUser.m_steamid.m_unAll64Bits),
+            val.m_iMessageID,
+        )
+    }
+}
+
+#[derive(Resource)]
+struct FriendChat {
+    received: CallResults<(SteamId, i32)>,
+    _callback: RegisteredCallback,
+}
+
+impl FriendChat {
+    fn read(&self, friend: SteamId, message_id: i32) -> Option<FriendChatMessageKind> {
+        let mut data = vec![0u8; MAX_MESSAGE_LEN];
+        let mut entry_type = sys::EChatEntryType::k_EChatEntryTypeInvalid;
+        // steamworks does not wrap GetFriendMessage, so it is called directly.
+        // SAFETY: The Steam API is initialized for as long as a `Client` exists, and
+        // Steam writes at most `data.len()` bytes.
+        let len = unsafe {
+            sys::SteamAPI_ISteamFriends_GetFriendMessage(
+                sys::SteamAPI_SteamFriends_v017(),
+                friend.raw(),
+                message_id,
+                data.as_mut_ptr().cast(),
+                data.len() as i32,
+                &mut entry_type,
+            )
+        };
+        data.truncate(len.max(0) as usize);
+        // Messages may include their nul terminator.
+        if data.last() == Some(&0) {
+            data.pop();
+        }
+        let text = String::from_utf8_lossy(&data).into_owned();
+        match entry_type {
+            sys::EChatEntryType::k_EChatEntryTypeChatMsg => Some(FriendChatMessageKind::Text(text)),
+            sys::EChatEntryType::k_EChatEntryTypeInviteGame => {
+                Some(FriendChatMessageKind::GameInvite { connect: text })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Asks Steam to deliver friends' chat messages to the game. Must be called
+/// before the chat callback is registered.
+pub(crate) fn listen_for_friends_messages(_client: &steamworks::Client) {
+    // steamworks does not wrap SetListenForFriendsMessages, so it is called directly.
+    // SAFETY: The Steam API is initialized for as long as a `Client` exists.
+    unsafe {
+        sys::SteamAPI_ISteamFriends_SetListenForFriendsMessages(
+            sys::SteamAPI_SteamFriends_v017(),
+            true,
+        );
+    }
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    let client = app.world().resource::<Client>();
+    let received = CallResults::new();
+    let received_in = received.clone();
+    let callback = RegisteredCallback::new(client.register_callback(
+        move |FriendChatMsgCallback(friend, message_id)| {
+            received_in.push((friend, message_id));
+        },
+    ));
+    app.insert_resource(FriendChat {
+        received,
+        _callback: callback,
+    })
+    .add_event::<FriendChatMessage>()
+    .add_systems(
+        First,
+        read_friend_chat
+            .after(SteamworksSystem::RunCallbacks)
+            .run_if(steam_running),
+    );
+}
+
+fn read_friend_chat(chat: Res<FriendChat>, mut messages: EventWriter<FriendChatMessage>) {
+    for (friend, message_id) in chat.received.drain() {
+        if let Some(kind) = chat.read(friend, message_id) {
+            messages.send(FriendChatMessage { friend, kind });
+        }
+    }
+}
@@ -0,0 +1,250 @@
+use bevy_app::{App, Plugin, Startup};
+use bevy_ecs::{
+    event::{Event, EventWriter},
+    system::{Res, Resource},
+};
+use steamworks::SteamAPIInitError;
+
+/// The outcome of initializing Steam, classified so each kind of failure can be
+/// shown to players with its own message.
+///
+/// Inserted as [`SteamInitStatus::Ok`] by [`SteamworksPlugin`](crate::SteamworksPlugin),
+/// or as the failure by [`SteamInitFailedPlugin`] when Steam could not be
+/// initialized. Failures keep the SDK's original message.
+///
+/// ```
+/// use bevy_steamworks::{SteamAPIInitError, SteamInitStatus};
+///
+/// let classify = |err| SteamInitStatus::from_error(&err);
+/// assert!(matches!(
+///     classify(SteamAPIInitError::NoSteamClient(
+///         "Cannot create IPC pipe to Steam client process.  Steam is probably not running."
+///             .to_owned()
+///     )),
+///     SteamInitStatus::SteamNotRunning { .. }
+/// ));
+/// assert!(matches!(
+///     classify(SteamAPIInitError::FailedGeneric(
+///         "No appID found.  Either launch the game from Steam, or put the file \
+///          steam_appid.txt containing the correct appID in your game folder."
+///             .to_owned()
+///     )),
+///     SteamInitStatus::NoAppId { .. }
+/// ));
+/// assert!(matches!(
+///     classify(SteamAPIInitError::VersionMismatch(
+///         "Steam client is out of date".to_owned()
+///     )),
+///     SteamInitStatus::VersionMismatch { .. }
+/// ));
+/// assert_eq!(
+///     classify(SteamAPIInitError::FailedGeneric("Unknown".to_owned())),
+///     SteamInitStatus::Other("Unknown".to_owned())
+/// );
+/// ```
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub enum SteamInitStatus {
+    /// Steam was initialized.
+    Ok,
+    /// The Steam client is not running, or could not be reached.
+    SteamNotRunning {
+        /// The SDK's error message.
+        message: String,
+    },
+    /// No app ID was found, as when the game is launched outside of Steam
+    /// without a `steam_appid.txt`.
+    NoAppId {
+        /// The SDK's error message.
+        message: String,
+    },
+    /// The installed Steam client does not support the SDK version the game was
+    /// built with, usually because it is out of date.
+    VersionMismatch {
+        /// The SDK's error message.
+        message: String,
+    },
+    /// Any other failure, with the SDK's error message.
+    Other(String),
+}
+
+impl SteamInitStatus {
+    /// Classifies an error from [`SteamworksPlugin::init_app`](crate::SteamworksPlugin::init_app)
+    /// or [`SteamworksPlugin::init`](crate::SteamworksPlugin::init).
+    pub fn from_error(err: &SteamAPIInitError) -> Self {
+        match err {
+            SteamAPIInitError::NoSteamClient(message) => Self::SteamNotRunning {
+                message: message.clone(),
+            },
+            SteamAPIInitError::VersionMismatch(message) => Self::VersionMismatch {
+                message: message.clone(),
+            },
+            SteamAPIInitError::FailedGeneric(message) => {
+                // Older clients report every failure as generic, so fall back to
+                // the SDK's wording.
+                let lowercase = message.to_lowercase();
+                if lowercase.contains("appid") {
+                    Self::NoAppId {
+                        message: message.clone(),
+                    }
+                } else if lowercase.contains("not running") || lowercase.contains("issteamrunning")
+                {
+                    Self::SteamNotRunning {
+                        message: message.clone(),
+                    }
+                } else {
+                    Self::Other(message.clone())
+                }
+            }
+        }
+    }
+
+    /// Checks if Steam was initialized.
+    pub fn is_ok(&self) -> bool {
+        matches!(self, Self::Ok)
+    }
+
+    /// Gets the SDK's error message, or `None` if Steam was initialized.
+    pub fn message(&self) -> Option<&str> {
+        match self {
+            Self::Ok => None,
+            Self::SteamNotRunning { message }
+            | Self::NoAppId { message }
+            | Self::VersionMismatch { message }
+            | Self::Other(message) => Some(message),
+        }
+    }
+}
+
+/// Sent once on [`Startup`] by [`SteamInitFailedPlugin`].
+#[derive(Event, Debug, Clone)]
+pub struct SteamInitFailed {
+    /// Why Steam could not be initialized.
+    pub status: SteamInitStatus,
+}
+
+/// A [`Plugin`] to add in place of [`SteamworksPlugin`](crate::SteamworksPlugin)
+/// when Steam could not be initialized, so the failure can be reported from
+/// within the app.
+///
+/// Inserts the classified [`SteamInitStatus`] and sends a [`SteamInitFailed`]
+/// event on [`Startup`].
+///
+/// ```rust no_run
+/// use bevy::prelude::*;
+/// use bevy_steamworks::*;
+///
+/// let mut app = App::new();
+/// match SteamworksPlugin::init_app(480) {
+///     Ok(plugin) => app.add_plugins(plugin),
+///     Err(err) => app.add_plugins(SteamInitFailedPlugin::new(&err)),
+/// };
+/// ```
+pub struct SteamInitFailedPlugin(SteamInitStatus);
+
+impl SteamInitFailedPlugin {
+    /// Creates the plugin from the error returned when initializing Steam.
+    pub fn new(err: &SteamAPIInitError) -> Self {
+        Self(SteamInitStatus::from_error(err))
+    }
+}
+
+impl Plugin for SteamInitFailedPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.0.clone())
+            .add_event::<SteamInitFailed>()
+            .add_systems(Startup, report_init_failure);
+    }
+}
+
+fn report_init_failure(status: Res<SteamInitStatus>, mut failed: EventWriter<SteamInitFailed>) {
+    failed.send(SteamInitFailed {
+        status: status.clone(),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_ecs::event::Events;
+
+    use super::*;
+
+    #[test]
+    fn classifies_sdk_errors() {
+        use SteamAPIInitError::*;
+
+        type Expected = fn(String) -> SteamInitStatus;
+        let not_running: Expected = |message| SteamInitStatus::SteamNotRunning { message };
+        let no_app_id: Expected = |message| SteamInitStatus::NoAppId { message };
+        let version_mismatch: Expected = |message| SteamInitStatus::VersionMismatch { message };
+        let other: Expected = SteamInitStatus::Other;
+
+        let cases: &[(fn(String) -> SteamAPIInitError, &str, Expected)] = &[
+            (
+                NoSteamClient,
+                "Cannot create IPC pipe to Steam client process.  Steam is probably not running.",
+                not_running,
+            ),
+            (NoSteamClient, "", not_running),
+            (
+                VersionMismatch,
+                "Steam client is out of date",
+                version_mismatch,
+            ),
+            (VersionMismatch, "", version_mismatch),
+            (
+                FailedGeneric,
+                "No appID found.  Either launch the game from Steam, or put the file \
+                 steam_appid.txt containing the correct appID in your game folder.",
+                no_app_id,
+            ),
+            (FailedGeneric, "NO APPID FOUND", no_app_id),
+            (FailedGeneric, "Steam is probably not running", not_running),
+            (
+                FailedGeneric,
+                "SteamAPI_IsSteamRunning() failed",
+                not_running,
+            ),
+            (FailedGeneric, "Unknown", other),
+            (FailedGeneric, "", other),
+        ];
+
+        for (error, message, expected) in cases {
+            let err = error(message.to_string());
+            assert_eq!(
+                SteamInitStatus::from_error(&err),
+                expected(message.to_string()),
+                "classifying {err:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn message_is_kept() {
+        let err = SteamAPIInitError::FailedGeneric("Unknown".to_owned());
+        let status = SteamInitStatus::from_error(&err);
+        assert!(!status.is_ok());
+        assert_eq!(status.message(), Some("Unknown"));
+        assert!(SteamInitStatus::Ok.is_ok());
+        assert_eq!(SteamInitStatus::Ok.message(), None);
+    }
+
+    #[test]
+    fn failed_plugin_reports_status() {
+        let err = SteamAPIInitError::VersionMismatch("out of date".to_owned());
+        let mut app = App::new();
+        app.add_plugins(SteamInitFailedPlugin::new(&err));
+        app.update();
+
+        let expected = SteamInitStatus::VersionMismatch {
+            message: "out of date".to_owned(),
+        };
+        assert_eq!(app.world().resource::<SteamInitStatus>(), &expected);
+        let events = app.world().resource::<Events<SteamInitFailed>>();
+        let sent: Vec<_> = events
+            .get_reader()
+            .read(events)
+            .map(|event| event.status.clone())
+            .collect();
+        assert_eq!(sent, [expected]);
+    }
+}
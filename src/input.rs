@@ -0,0 +1,142 @@
+mod action_sets;
+mod analog;
+mod binding_panel;
+mod controllers;
+mod digital;
+mod gamepad_bridge;
+#[cfg(feature = "bevy_render")]
+mod glyphs;
+mod haptics;
+
+pub use action_sets::*;
+pub use analog::*;
+pub use binding_panel::*;
+pub use controllers::*;
+pub use digital::*;
+pub use gamepad_bridge::*;
+#[cfg(feature = "bevy_render")]
+pub use glyphs::*;
+pub use haptics::SteamInputCommands;
+
+use std::time::Instant;
+
+use bevy_app::{App, AppExit, First, Last, Plugin, PreUpdate};
+use bevy_ecs::{
+    event::EventReader,
+    schedule::IntoSystemConfigs,
+    system::{Res, Resource},
+};
+use bevy_input::InputSystem;
+use bevy_log::warn;
+use bevy_utils::HashMap;
+use steamworks::{sys::InputHandle_t, ClientManager, Input};
+
+use crate::{sends_steam_events, shutdown::steam_running, Client, SteamworksSystem};
+
+/// A controller handle from Steam Input.
+pub type SteamControllerHandle = InputHandle_t;
+
+/// Targets every connected controller in calls that take a [`SteamControllerHandle`].
+pub const ALL_STEAM_CONTROLLERS: SteamControllerHandle = u64::MAX;
+
+/// Access to Steam Input, inserted by [`SteamInputPlugin`].
+///
+/// Steam Input's frame is run every tick in [`PreUpdate`], before Bevy's own input
+/// systems, so action data read from [`SteamInput::input`] is current for the frame.
+#[derive(Resource)]
+pub struct SteamInput {
+    client: Client,
+    /// When to stop each controller's rumble.
+    rumbles: HashMap<SteamControllerHandle, Instant>,
+    /// The controller whose binding panel is open in the overlay.
+    binding_panel: Option<SteamControllerHandle>,
+}
+
+impl SteamInput {
+    /// Gets the Steam Input interface.
+    pub fn input(&self) -> Input<ClientManager> {
+        self.client.input()
+    }
+}
+
+/// An opt-in [`Plugin`] that initializes Steam Input, the interface behind Steam
+/// Deck and controller remapping support.
+///
+/// Must be added after [`SteamworksPlugin`](crate::SteamworksPlugin). If Steam
+/// Input fails to initialize, a warning is logged and no [`SteamInput`] resource is
+/// inserted. Steam Input is shut down when [`AppExit`] is sent.
+pub struct SteamInputPlugin;
+
+impl Plugin for SteamInputPlugin {
+    fn build(&self, app: &mut App) {
+        let client = app.world().resource::<Client>().clone();
+        // Frames are run explicitly so they line up with Bevy's input handling.
+        if !client.input().init(true) {
+            warn!("Failed to initialize Steam Input");
+            return;
+        }
+        app.insert_resource(SteamInput {
+            client,
+            rumbles: HashMap::default(),
+            binding_panel: None,
+        })
+        .init_resource::<ConnectedSteamControllers>()
+        .init_resource::<SteamInputActionSets>()
+        .init_resource::<SteamDigitalActions>()
+        .init_resource::<SteamAnalogActions>()
+        .add_systems(
+            PreUpdate,
+            (
+                run_frame
+                    .in_set(SteamworksSystem::RunInputFrame)
+                    .before(InputSystem)
+                    .run_if(steam_running),
+                (
+                    controllers::track_controllers,
+                    action_sets::apply_action_sets,
+                    (
+                        digital::update_digital_actions,
+                        analog::update_analog_actions,
+                    ),
+                )
+                    .chain()
+                    .after(SteamworksSystem::RunInputFrame)
+                    .run_if(steam_running),
+                haptics::stop_expired_rumble
+                    .after(SteamworksSystem::RunInputFrame)
+                    .run_if(steam_running),
+            ),
+        )
+        .add_event::<SteamControllerConnected>()
+        .add_event::<SteamControllerDisconnected>()
+        .add_event::<BindingPanelClosed>()
+        .add_systems(Last, shutdown_on_exit);
+        if sends_steam_events(app) {
+            app.add_systems(
+                First,
+                binding_panel::detect_binding_panel_closed
+                    .after(SteamworksSystem::RunCallbacks)
+                    .run_if(steam_running),
+            );
+        }
+
+        #[cfg(feature = "bevy_render")]
+        app.init_resource::<SteamInputGlyphs>().add_systems(
+            PreUpdate,
+            glyphs::update_glyphs
+                .after(digital::update_digital_actions)
+                .after(analog::update_analog_actions)
+                .run_if(steam_running),
+        );
+    }
+}
+
+fn run_frame(input: Res<SteamInput>) {
+    input.input().run_frame();
+}
+
+fn shutdown_on_exit(input: Res<SteamInput>, mut exit: EventReader<AppExit>) {
+    if exit.read().next().is_some() {
+        input.input().shutdown();
+    }
+}
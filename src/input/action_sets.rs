@@ -0,0 +1,124 @@
+use bevy_ecs::system::{Res, ResMut, Resource};
+use bevy_log::warn;
+use bevy_utils::{HashMap, HashSet};
+use steamworks::sys::{self, InputActionSetHandle_t};
+
+use super::{SteamControllerHandle, SteamInput};
+
+/// The action sets the game uses with Steam Input, and which one is active on
+/// each controller along with any action set layers stacked on top of it.
+///
+/// Action sets are registered by name and resolved to Steam Input handles once
+/// controllers are connected. Steam requires the active set and layers to be
+/// reapplied every frame, which [`SteamInputPlugin`](crate::SteamInputPlugin) does after
+/// running Steam Input's frame.
+#[derive(Resource, Default)]
+pub struct SteamInputActionSets {
+    /// Registered action sets. A handle of 0 has not been resolved yet.
+    handles: HashMap<String, InputActionSetHandle_t>,
+    active: HashMap<SteamControllerHandle, String>,
+    layers: HashMap<SteamControllerHandle, Vec<String>>,
+    warned: HashSet<String>,
+}
+
+impl SteamInputActionSets {
+    /// Registers an action set by the name it has in the game's action manifest.
+    pub fn register(&mut self, name: impl Into<String>) {
+        self.handles.entry(name.into()).or_insert(0);
+    }
+
+    /// Activates an action set on a controller, or on every controller if
+    /// `controller` is [`ALL_STEAM_CONTROLLERS`](crate::ALL_STEAM_CONTROLLERS).
+    ///
+    /// The action set is registered if it hasn't been already.
+    pub fn set_active(&mut self, controller: SteamControllerHandle, name: impl Into<String>) {
+        let name = name.into();
+        self.register(name.clone());
+        self.active.insert(controller, name);
+    }
+
+    /// Gets the name of the action set last activated on a controller.
+    pub fn active(&self, controller: SteamControllerHandle) -> Option<&str> {
+        self.active.get(&controller).map(String::as_str)
+    }
+
+    /// Activates an action set layer on top of a controller's action set and any
+    /// layers already pushed. Pushing a layer that is already active does nothing.
+    ///
+    /// The layer is registered if it hasn't been already.
+    pub fn push_layer(&mut self, controller: SteamControllerHandle, name: impl Into<String>) {
+        let name = name.into();
+        self.register(name.clone());
+        let layers = self.layers.entry(controller).or_default();
+        if !layers.contains(&name) {
+            layers.push(name);
+        }
+    }
+
+    /// Deactivates the most recently pushed layer on a controller, returning its name.
+    pub fn pop_layer(&mut self, controller: SteamControllerHandle) -> Option<String> {
+        self.layers.get_mut(&controller)?.pop()
+    }
+
+    /// Deactivates every layer on a controller.
+    pub fn clear_layers(&mut self, controller: SteamControllerHandle) {
+        if let Some(layers) = self.layers.get_mut(&controller) {
+            layers.clear();
+        }
+    }
+
+    /// Gets the layers active on a controller, from the bottom of the stack to the top.
+    pub fn layers(&self, controller: SteamControllerHandle) -> &[String] {
+        self.layers
+            .get(&controller)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Gets the resolved Steam Input handle of a registered action set.
+    pub fn handle(&self, name: &str) -> Option<InputActionSetHandle_t> {
+        self.handles
+            .get(name)
+            .copied()
+            .filter(|handle| *handle != 0)
+    }
+}
+
+pub(super) fn apply_action_sets(input: Res<SteamInput>, mut sets: ResMut<SteamInputActionSets>) {
+    let input = input.input();
+    let sets = sets.as_mut();
+    // Handles only resolve once Steam has loaded the action manifest, which it
+    // does for the first connected controller.
+    if sets.handles.values().any(|handle| *handle == 0)
+        && !input.get_connected_controllers().is_empty()
+    {
+        for (name, handle) in sets.handles.iter_mut().filter(|(_, handle)| **handle == 0) {
+            *handle = input.get_action_set_handle(name);
+            if *handle == 0 && sets.warned.insert(name.clone()) {
+                warn!("Steam Input action set {:?} does not exist", name);
+            }
+        }
+    }
+    for (controller, name) in &sets.active {
+        if let Some(handle) = sets.handles.get(name).filter(|handle| **handle != 0) {
+            input.activate_action_set_handle(*controller, *handle);
+        }
+    }
+    // steamworks does not wrap the action set layer calls, so they are called directly.
+    // SAFETY: The Steam API is initialized for as long as a `Client` exists.
+    unsafe {
+        let steam_input = sys::SteamAPI_SteamInput_v006();
+        for (controller, layers) in &sets.layers {
+            sys::SteamAPI_ISteamInput_DeactivateAllActionSetLayers(steam_input, *controller);
+            for layer in layers {
+                if let Some(handle) = sets.handles.get(layer).filter(|handle| **handle != 0) {
+                    sys::SteamAPI_ISteamInput_ActivateActionSetLayer(
+                        steam_input,
+                        *controller,
+                        *handle,
+                    );
+                }
+            }
+        }
+    }
+}
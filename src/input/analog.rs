@@ -0,0 +1,169 @@
+use bevy_ecs::system::{Res, ResMut, Resource};
+use bevy_math::Vec2;
+use bevy_utils::HashMap;
+use steamworks::sys::{EInputSourceMode, InputAnalogActionHandle_t};
+
+use super::{SteamControllerHandle, SteamInput};
+
+#[derive(Clone, Copy)]
+struct AnalogValue {
+    raw: Vec2,
+    value: Vec2,
+    mode: EInputSourceMode,
+}
+
+/// The values of Steam Input analog actions, such as "Move" or "Camera".
+///
+/// Actions must be registered by the name they have in the game's action
+/// manifest. They are then read for every connected controller each frame by
+/// [`SteamInputPlugin`](crate::SteamInputPlugin), and a radial deadzone is applied
+/// to every value except those of mouse-like sources. Queries without a
+/// controller return the value with the largest magnitude across controllers.
+/// Actions that aren't bound in the active action set read as zero.
+#[derive(Resource)]
+pub struct SteamAnalogActions {
+    /// Registered actions. A handle of 0 has not been resolved yet.
+    handles: HashMap<String, InputAnalogActionHandle_t>,
+    values: HashMap<SteamControllerHandle, HashMap<String, AnalogValue>>,
+    deadzones: HashMap<String, f32>,
+    /// The radial deadzone applied to actions without their own, from 0 to 1.
+    pub deadzone: f32,
+}
+
+impl Default for SteamAnalogActions {
+    fn default() -> Self {
+        Self {
+            handles: HashMap::default(),
+            values: HashMap::default(),
+            deadzones: HashMap::default(),
+            deadzone: 0.1,
+        }
+    }
+}
+
+impl SteamAnalogActions {
+    /// Iterates over the registered actions whose handles have been resolved.
+    pub(super) fn handles(&self) -> impl Iterator<Item = (&str, InputAnalogActionHandle_t)> + '_ {
+        self.handles
+            .iter()
+            .filter(|(_, handle)| **handle != 0)
+            .map(|(name, handle)| (name.as_str(), *handle))
+    }
+
+    /// Registers an analog action to be read every frame.
+    pub fn register(&mut self, name: impl Into<String>) {
+        self.handles.entry(name.into()).or_insert(0);
+    }
+
+    /// Overrides [`SteamAnalogActions::deadzone`] for a single action.
+    pub fn set_deadzone(&mut self, name: impl Into<String>, deadzone: f32) {
+        self.deadzones.insert(name.into(), deadzone);
+    }
+
+    /// Gets the value of an action after the deadzone is applied.
+    pub fn value(&self, name: &str) -> Vec2 {
+        self.strongest(name).map_or(Vec2::ZERO, |value| value.value)
+    }
+
+    /// Gets the value of an action as reported by Steam, without a deadzone.
+    pub fn raw_value(&self, name: &str) -> Vec2 {
+        self.strongest(name).map_or(Vec2::ZERO, |value| value.raw)
+    }
+
+    /// Gets the kind of input an action is bound to, which determines whether its
+    /// value is an absolute position or a relative delta.
+    pub fn mode(&self, name: &str) -> Option<EInputSourceMode> {
+        self.strongest(name).map(|value| value.mode)
+    }
+
+    /// Gets the value of an action on a controller after the deadzone is applied.
+    pub fn value_on(&self, controller: SteamControllerHandle, name: &str) -> Vec2 {
+        self.get(controller, name)
+            .map_or(Vec2::ZERO, |value| value.value)
+    }
+
+    /// Gets the value of an action on a controller as reported by Steam, without
+    /// a deadzone.
+    pub fn raw_value_on(&self, controller: SteamControllerHandle, name: &str) -> Vec2 {
+        self.get(controller, name)
+            .map_or(Vec2::ZERO, |value| value.raw)
+    }
+
+    /// Gets the kind of input an action is bound to on a controller.
+    pub fn mode_on(
+        &self,
+        controller: SteamControllerHandle,
+        name: &str,
+    ) -> Option<EInputSourceMode> {
+        self.get(controller, name).map(|value| value.mode)
+    }
+
+    fn get(&self, controller: SteamControllerHandle, name: &str) -> Option<&AnalogValue> {
+        self.values.get(&controller)?.get(name)
+    }
+
+    fn strongest(&self, name: &str) -> Option<&AnalogValue> {
+        self.values
+            .values()
+            .filter_map(|values| values.get(name))
+            .max_by(|a, b| {
+                a.value
+                    .length_squared()
+                    .total_cmp(&b.value.length_squared())
+            })
+    }
+}
+
+fn apply_deadzone(value: Vec2, deadzone: f32) -> Vec2 {
+    let length = value.length();
+    if length <= deadzone || deadzone >= 1.0 {
+        return Vec2::ZERO;
+    }
+    // Rescale so the output still covers the full range past the deadzone.
+    let scaled = ((length - deadzone) / (1.0 - deadzone)).min(1.0);
+    value / length * scaled
+}
+
+pub(super) fn update_analog_actions(
+    input: Res<SteamInput>,
+    mut actions: ResMut<SteamAnalogActions>,
+) {
+    let input = input.input();
+    let actions = actions.as_mut();
+    for (name, handle) in actions
+        .handles
+        .iter_mut()
+        .filter(|(_, handle)| **handle == 0)
+    {
+        *handle = input.get_analog_action_handle(name);
+    }
+
+    actions.values.clear();
+    for controller in input.get_connected_controllers() {
+        let mut values = HashMap::default();
+        for (name, handle) in actions.handles.iter().filter(|(_, handle)| **handle != 0) {
+            let data = input.get_analog_action_data(controller, *handle);
+            if !data.bActive {
+                continue;
+            }
+            let raw = Vec2::new(data.x, data.y);
+            let value = match data.eMode {
+                EInputSourceMode::k_EInputSourceMode_AbsoluteMouse
+                | EInputSourceMode::k_EInputSourceMode_RelativeMouse => raw,
+                _ => {
+                    let deadzone = actions.deadzones.get(name).copied();
+                    apply_deadzone(raw, deadzone.unwrap_or(actions.deadzone))
+                }
+            };
+            values.insert(
+                name.clone(),
+                AnalogValue {
+                    raw,
+                    value,
+                    mode: data.eMode,
+                },
+            );
+        }
+        actions.values.insert(controller, values);
+    }
+}
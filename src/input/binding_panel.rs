@@ -0,0 +1,74 @@
+use bevy_ecs::{
+    event::{Event, EventReader, EventWriter},
+    system::{Commands, ResMut},
+    world::World,
+};
+use steamworks::sys;
+
+use crate::SteamworksEvent;
+
+use super::{SteamControllerHandle, SteamInput};
+
+/// Sent when the Steam overlay closes after [`SteamInput::show_binding_panel`]
+/// opened it, so cached glyphs and action origins can be refreshed.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct BindingPanelClosed {
+    /// The controller whose bindings were shown.
+    pub controller: SteamControllerHandle,
+}
+
+impl SteamInput {
+    /// Opens the Steam overlay's binding panel for a controller.
+    ///
+    /// Returns `false` if the panel could not be opened, such as when the overlay
+    /// is disabled or Steam is shutting down. A [`BindingPanelClosed`] event is sent
+    /// once the overlay closes.
+    pub fn show_binding_panel(&mut self, controller: SteamControllerHandle) -> bool {
+        if self.client.is_shutting_down() {
+            return false;
+        }
+        // steamworks does not wrap ShowBindingPanel, so it is called directly.
+        // SAFETY: The Steam API is initialized for as long as a `Client` exists.
+        let shown = unsafe {
+            sys::SteamAPI_ISteamInput_ShowBindingPanel(sys::SteamAPI_SteamInput_v006(), controller)
+        };
+        if shown {
+            self.binding_panel = Some(controller);
+        }
+        shown
+    }
+}
+
+/// Extends [`Commands`] with access to the Steam Input binding panel.
+pub trait SteamBindingPanelCommands {
+    /// Opens the binding panel for a controller. See [`SteamInput::show_binding_panel`].
+    fn steam_show_binding_panel(&mut self, controller: SteamControllerHandle);
+}
+
+impl SteamBindingPanelCommands for Commands<'_, '_> {
+    fn steam_show_binding_panel(&mut self, controller: SteamControllerHandle) {
+        self.add(move |world: &mut World| {
+            if let Some(mut input) = world.get_resource_mut::<SteamInput>() {
+                input.show_binding_panel(controller);
+            }
+        });
+    }
+}
+
+pub(super) fn detect_binding_panel_closed(
+    mut input: ResMut<SteamInput>,
+    mut events: EventReader<SteamworksEvent>,
+    mut closed: EventWriter<BindingPanelClosed>,
+) {
+    for event in events.read() {
+        let SteamworksEvent::GameOverlayActivated(overlay) = event else {
+            continue;
+        };
+        if overlay.active {
+            continue;
+        }
+        if let Some(controller) = input.binding_panel.take() {
+            closed.send(BindingPanelClosed { controller });
+        }
+    }
+}
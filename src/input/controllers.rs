@@ -0,0 +1,95 @@
+use bevy_ecs::{
+    event::{Event, EventWriter},
+    system::{Res, ResMut, Resource},
+};
+use steamworks::sys::ESteamInputType;
+
+use super::{SteamControllerHandle, SteamInput};
+
+/// Sent when a controller is connected through Steam Input.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct SteamControllerConnected {
+    /// The handle of the controller.
+    pub handle: SteamControllerHandle,
+    /// The kind of controller, such as a Steam Deck or DualSense controller.
+    pub input_type: ESteamInputType,
+}
+
+/// Sent when a controller connected through Steam Input is disconnected.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct SteamControllerDisconnected {
+    /// The handle of the controller.
+    pub handle: SteamControllerHandle,
+    /// The kind of controller, such as a Steam Deck or DualSense controller.
+    pub input_type: ESteamInputType,
+}
+
+/// The controllers currently connected through Steam Input, in the order Steam
+/// reports them.
+#[derive(Resource, Default)]
+pub struct ConnectedSteamControllers {
+    controllers: Vec<(SteamControllerHandle, ESteamInputType)>,
+}
+
+impl ConnectedSteamControllers {
+    /// Iterates over every connected controller and its kind.
+    pub fn iter(&self) -> impl Iterator<Item = (SteamControllerHandle, ESteamInputType)> + '_ {
+        self.controllers.iter().copied()
+    }
+
+    /// Checks if a controller is connected.
+    pub fn contains(&self, handle: SteamControllerHandle) -> bool {
+        self.input_type(handle).is_some()
+    }
+
+    /// Gets the kind of a connected controller.
+    pub fn input_type(&self, handle: SteamControllerHandle) -> Option<ESteamInputType> {
+        self.iter()
+            .find(|(controller, _)| *controller == handle)
+            .map(|(_, input_type)| input_type)
+    }
+
+    /// The number of connected controllers.
+    pub fn len(&self) -> usize {
+        self.controllers.len()
+    }
+
+    /// Checks if no controllers are connected.
+    pub fn is_empty(&self) -> bool {
+        self.controllers.is_empty()
+    }
+}
+
+pub(super) fn track_controllers(
+    input: Res<SteamInput>,
+    mut connected: ResMut<ConnectedSteamControllers>,
+    mut connects: EventWriter<SteamControllerConnected>,
+    mut disconnects: EventWriter<SteamControllerDisconnected>,
+) {
+    let input = input.input();
+    let current = input.get_connected_controllers();
+    let unchanged = current.len() == connected.len()
+        && current.iter().all(|handle| connected.contains(*handle));
+    if unchanged {
+        return;
+    }
+
+    for (handle, input_type) in connected.iter() {
+        if !current.contains(&handle) {
+            disconnects.send(SteamControllerDisconnected { handle, input_type });
+        }
+    }
+    let controllers = current
+        .into_iter()
+        .map(|handle| {
+            let input_type = connected
+                .input_type(handle)
+                .unwrap_or_else(|| input.get_input_type_for_handle(handle));
+            if !connected.contains(handle) {
+                connects.send(SteamControllerConnected { handle, input_type });
+            }
+            (handle, input_type)
+        })
+        .collect();
+    connected.controllers = controllers;
+}
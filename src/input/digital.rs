@@ -0,0 +1,111 @@
+use bevy_ecs::system::{Res, ResMut, Resource};
+use bevy_utils::{HashMap, HashSet};
+use steamworks::sys::InputDigitalActionHandle_t;
+
+use super::{SteamControllerHandle, SteamInput};
+
+/// The state of Steam Input digital actions, such as "Jump", with semantics like
+/// Bevy's `ButtonInput`.
+///
+/// Actions must be registered by the name they have in the game's action
+/// manifest. They are then read for every connected controller each frame by
+/// [`SteamInputPlugin`](crate::SteamInputPlugin). Queries without a controller
+/// consider every controller, and actions that aren't bound in the active action
+/// set read as released.
+#[derive(Resource, Default)]
+pub struct SteamDigitalActions {
+    /// Registered actions. A handle of 0 has not been resolved yet.
+    handles: HashMap<String, InputDigitalActionHandle_t>,
+    pressed: HashMap<SteamControllerHandle, HashSet<String>>,
+    previous: HashMap<SteamControllerHandle, HashSet<String>>,
+}
+
+impl SteamDigitalActions {
+    /// Iterates over the registered actions whose handles have been resolved.
+    pub(super) fn handles(&self) -> impl Iterator<Item = (&str, InputDigitalActionHandle_t)> + '_ {
+        self.handles
+            .iter()
+            .filter(|(_, handle)| **handle != 0)
+            .map(|(name, handle)| (name.as_str(), *handle))
+    }
+
+    /// Registers a digital action to be read every frame.
+    pub fn register(&mut self, name: impl Into<String>) {
+        self.handles.entry(name.into()).or_insert(0);
+    }
+
+    /// Checks if the action is pressed on any controller.
+    pub fn pressed(&self, name: &str) -> bool {
+        self.pressed.values().any(|pressed| pressed.contains(name))
+    }
+
+    /// Checks if the action started being pressed this frame on any controller.
+    pub fn just_pressed(&self, name: &str) -> bool {
+        self.pressed
+            .keys()
+            .any(|controller| self.just_pressed_on(*controller, name))
+    }
+
+    /// Checks if the action stopped being pressed this frame on any controller.
+    pub fn just_released(&self, name: &str) -> bool {
+        self.previous
+            .keys()
+            .any(|controller| self.just_released_on(*controller, name))
+    }
+
+    /// Checks if the action is pressed on a controller.
+    pub fn pressed_on(&self, controller: SteamControllerHandle, name: &str) -> bool {
+        contains(&self.pressed, controller, name)
+    }
+
+    /// Checks if the action started being pressed this frame on a controller.
+    pub fn just_pressed_on(&self, controller: SteamControllerHandle, name: &str) -> bool {
+        contains(&self.pressed, controller, name) && !contains(&self.previous, controller, name)
+    }
+
+    /// Checks if the action stopped being pressed this frame on a controller.
+    pub fn just_released_on(&self, controller: SteamControllerHandle, name: &str) -> bool {
+        !contains(&self.pressed, controller, name) && contains(&self.previous, controller, name)
+    }
+}
+
+fn contains(
+    actions: &HashMap<SteamControllerHandle, HashSet<String>>,
+    controller: SteamControllerHandle,
+    name: &str,
+) -> bool {
+    actions
+        .get(&controller)
+        .map_or(false, |pressed| pressed.contains(name))
+}
+
+pub(super) fn update_digital_actions(
+    input: Res<SteamInput>,
+    mut actions: ResMut<SteamDigitalActions>,
+) {
+    let input = input.input();
+    let actions = actions.as_mut();
+    for (name, handle) in actions
+        .handles
+        .iter_mut()
+        .filter(|(_, handle)| **handle == 0)
+    {
+        *handle = input.get_digital_action_handle(name);
+    }
+
+    // Steam only reports the current state, so edges come from the last frame's.
+    actions.previous = std::mem::take(&mut actions.pressed);
+    for controller in input.get_connected_controllers() {
+        let pressed: HashSet<String> = actions
+            .handles
+            .iter()
+            .filter(|(_, handle)| **handle != 0)
+            .filter(|(_, handle)| {
+                let data = input.get_digital_action_data(controller, **handle);
+                data.bActive && data.bState
+            })
+            .map(|(name, _)| name.clone())
+            .collect();
+        actions.pressed.insert(controller, pressed);
+    }
+}
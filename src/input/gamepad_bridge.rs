@@ -0,0 +1,138 @@
+use bevy_app::{App, Plugin, PreUpdate};
+use bevy_ecs::{
+    event::{EventReader, EventWriter},
+    schedule::IntoSystemConfigs,
+    system::{Local, Res, ResMut, Resource},
+};
+use bevy_input::{
+    gamepad::{
+        Gamepad, GamepadAxisChangedEvent, GamepadAxisType, GamepadButtonChangedEvent,
+        GamepadButtonType, GamepadConnection, GamepadConnectionEvent, GamepadEvent, GamepadInfo,
+    },
+    InputSystem,
+};
+use bevy_utils::HashMap;
+
+use crate::shutdown::steam_running;
+
+use super::{
+    SteamAnalogActions, SteamControllerConnected, SteamControllerDisconnected,
+    SteamControllerHandle, SteamDigitalActions,
+};
+
+/// Gamepad IDs handed out to Steam Input controllers start here, well clear of
+/// the small IDs used for gamepads read through gilrs.
+const FIRST_GAMEPAD_ID: usize = 0x5354_0000;
+
+/// Maps Steam Input actions onto the buttons and axes of the virtual gamepads
+/// created by [`SteamGamepadBridgePlugin`].
+#[derive(Resource, Default)]
+pub struct SteamGamepadMapping {
+    /// Digital actions and the gamepad button each is reported as.
+    pub buttons: HashMap<String, GamepadButtonType>,
+    /// Analog actions and the gamepad axes their x and y values are reported as.
+    pub axes: HashMap<String, (GamepadAxisType, GamepadAxisType)>,
+}
+
+/// An opt-in [`Plugin`] that exposes every Steam Input controller as a Bevy
+/// [`Gamepad`], for code that reads `ButtonInput<GamepadButton>` and
+/// `Axis<GamepadAxis>`.
+///
+/// Action values are mapped through [`SteamGamepadMapping`] and sent as
+/// [`GamepadEvent`]s before Bevy's input systems run. Virtual gamepads are given
+/// IDs that don't collide with gamepads read through gilrs.
+///
+/// Must be added after [`SteamInputPlugin`](crate::SteamInputPlugin).
+pub struct SteamGamepadBridgePlugin;
+
+impl Plugin for SteamGamepadBridgePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SteamGamepadMapping>().add_systems(
+            PreUpdate,
+            bridge_gamepads
+                .after(super::digital::update_digital_actions)
+                .after(super::analog::update_analog_actions)
+                .before(InputSystem)
+                .run_if(steam_running),
+        );
+    }
+}
+
+#[derive(Default)]
+struct BridgeState {
+    gamepads: HashMap<SteamControllerHandle, Gamepad>,
+    next_id: usize,
+    /// The last value sent for each of a gamepad's buttons and axes.
+    buttons: HashMap<(Gamepad, GamepadButtonType), f32>,
+    axes: HashMap<(Gamepad, GamepadAxisType), f32>,
+}
+
+fn bridge_gamepads(
+    mut state: Local<BridgeState>,
+    mapping: Res<SteamGamepadMapping>,
+    mut digital: ResMut<SteamDigitalActions>,
+    mut analog: ResMut<SteamAnalogActions>,
+    mut connected: EventReader<SteamControllerConnected>,
+    mut disconnected: EventReader<SteamControllerDisconnected>,
+    mut events: EventWriter<GamepadEvent>,
+) {
+    if mapping.is_changed() {
+        for name in mapping.buttons.keys() {
+            digital.register(name.clone());
+        }
+        for name in mapping.axes.keys() {
+            analog.register(name.clone());
+        }
+    }
+
+    let state = &mut *state;
+    for event in connected.read() {
+        let gamepad = Gamepad::new(FIRST_GAMEPAD_ID + state.next_id);
+        state.next_id += 1;
+        state.gamepads.insert(event.handle, gamepad);
+        events.send(GamepadEvent::Connection(GamepadConnectionEvent::new(
+            gamepad,
+            GamepadConnection::Connected(GamepadInfo {
+                name: format!("Steam Input {:?}", event.input_type),
+            }),
+        )));
+    }
+    for event in disconnected.read() {
+        let Some(gamepad) = state.gamepads.remove(&event.handle) else {
+            continue;
+        };
+        state.buttons.retain(|(pad, _), _| *pad != gamepad);
+        state.axes.retain(|(pad, _), _| *pad != gamepad);
+        events.send(GamepadEvent::Connection(GamepadConnectionEvent::new(
+            gamepad,
+            GamepadConnection::Disconnected,
+        )));
+    }
+
+    for (controller, gamepad) in &state.gamepads {
+        for (name, button) in &mapping.buttons {
+            let value = if digital.pressed_on(*controller, name) {
+                1.0
+            } else {
+                0.0
+            };
+            let last = state.buttons.insert((*gamepad, *button), value);
+            if last != Some(value) {
+                events.send(GamepadEvent::Button(GamepadButtonChangedEvent::new(
+                    *gamepad, *button, value,
+                )));
+            }
+        }
+        for (name, (x_axis, y_axis)) in &mapping.axes {
+            let value = analog.value_on(*controller, name);
+            for (axis, value) in [(*x_axis, value.x), (*y_axis, value.y)] {
+                let last = state.axes.insert((*gamepad, axis), value);
+                if last != Some(value) {
+                    events.send(GamepadEvent::Axis(GamepadAxisChangedEvent::new(
+                        *gamepad, axis, value,
+                    )));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use std::path::PathBuf;
+
+use bevy_asset::{AssetServer, Handle};
+use bevy_ecs::system::{Res, ResMut, Resource};
+use bevy_render::texture::Image;
+use bevy_utils::HashMap;
+use steamworks::sys::EInputActionOrigin;
+
+use super::{
+    SteamAnalogActions, SteamControllerHandle, SteamDigitalActions, SteamInput,
+    SteamInputActionSets, ALL_STEAM_CONTROLLERS,
+};
+
+/// Button prompt glyphs for the physical inputs bound to Steam Input actions.
+///
+/// Every action registered with [`SteamDigitalActions`] or [`SteamAnalogActions`]
+/// has its bound inputs looked up each frame on the controller in use, so
+/// glyphs follow the player's bindings as they are changed in the overlay.
+/// Glyphs are loaded through the [`AssetServer`] and cached by origin.
+#[derive(Resource, Default)]
+pub struct SteamInputGlyphs {
+    /// The controller whose bindings are shown. Defaults to the first connected
+    /// controller.
+    pub controller: Option<SteamControllerHandle>,
+    origins: HashMap<String, Vec<EInputActionOrigin>>,
+    glyphs: HashMap<String, Vec<Handle<Image>>>,
+    by_origin: HashMap<EInputActionOrigin, Handle<Image>>,
+}
+
+impl SteamInputGlyphs {
+    /// Gets the glyph of the first input bound to an action.
+    pub fn glyph_for(&self, action: &str) -> Option<Handle<Image>> {
+        self.glyphs_for(action).first().cloned()
+    }
+
+    /// Gets the glyphs of every input bound to an action, in the order Steam
+    /// reports them, so prompts can show alternatives like "A / Enter".
+    pub fn glyphs_for(&self, action: &str) -> &[Handle<Image>] {
+        self.glyphs
+            .get(action)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Gets the inputs bound to an action, in the order Steam reports them.
+    pub fn origins_for(&self, action: &str) -> &[EInputActionOrigin] {
+        self.origins
+            .get(action)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+}
+
+pub(super) fn update_glyphs(
+    input: Res<SteamInput>,
+    action_sets: Res<SteamInputActionSets>,
+    digital: Res<SteamDigitalActions>,
+    analog: Res<SteamAnalogActions>,
+    asset_server: Res<AssetServer>,
+    mut glyphs: ResMut<SteamInputGlyphs>,
+) {
+    let input = input.input();
+    let controller = glyphs
+        .controller
+        .or_else(|| input.get_connected_controllers().first().copied());
+    let Some(controller) = controller else {
+        return;
+    };
+    let Some(action_set) = action_sets
+        .active(controller)
+        .or_else(|| action_sets.active(ALL_STEAM_CONTROLLERS))
+        .and_then(|name| action_sets.handle(name))
+    else {
+        return;
+    };
+
+    let glyphs = glyphs.as_mut();
+    let digital_origins = digital.handles().map(|(name, handle)| {
+        let origins = input.get_digital_action_origins(controller, action_set, handle);
+        (name, origins)
+    });
+    let analog_origins = analog.handles().map(|(name, handle)| {
+        let origins = input.get_analog_action_origins(controller, action_set, handle);
+        (name, origins)
+    });
+    for (name, origins) in digital_origins.chain(analog_origins) {
+        if glyphs.origins.get(name) == Some(&origins) {
+            continue;
+        }
+        let handles = origins
+            .iter()
+            .map(|origin| {
+                glyphs
+                    .by_origin
+                    .entry(*origin)
+                    .or_insert_with(|| {
+                        let path = input.get_glyph_for_action_origin(*origin);
+                        asset_server.load(PathBuf::from(path))
+                    })
+                    .clone()
+            })
+            .collect();
+        glyphs.glyphs.insert(name.to_owned(), handles);
+        glyphs.origins.insert(name.to_owned(), origins);
+    }
+}
@@ -0,0 +1,139 @@
+use std::time::{Duration, Instant};
+
+use bevy_ecs::{
+    system::{Commands, ResMut},
+    world::World,
+};
+use bevy_log::debug;
+use steamworks::sys::{self, EControllerHapticLocation};
+
+use super::{SteamControllerHandle, SteamInput};
+
+impl SteamInput {
+    /// Vibrates a controller's rumble motors at `intensity`, from 0 to 1, for
+    /// `duration`.
+    ///
+    /// The rumble is stopped by [`SteamInputPlugin`](crate::SteamInputPlugin) once
+    /// the duration has passed. Calls for controllers that aren't connected, or
+    /// made once Steam is shutting down, are ignored.
+    pub fn rumble(
+        &mut self,
+        controller: SteamControllerHandle,
+        duration: Duration,
+        intensity: f32,
+    ) {
+        if !self.is_connected(controller) {
+            debug!("Ignoring rumble for disconnected controller {}", controller);
+            return;
+        }
+        let speed = (intensity.clamp(0.0, 1.0) * f32::from(u16::MAX)) as u16;
+        trigger_vibration(controller, speed);
+        self.rumbles.insert(controller, Instant::now() + duration);
+    }
+
+    /// Stops a controller's rumble motors.
+    pub fn stop_rumble(&mut self, controller: SteamControllerHandle) {
+        self.rumbles.remove(&controller);
+        if self.is_connected(controller) {
+            trigger_vibration(controller, 0);
+        }
+    }
+
+    /// Plays a haptic pulse on controllers that support Steam Input's newer
+    /// haptics, such as the Steam Deck and DualSense controllers.
+    ///
+    /// `intensity` is from 0 to 4 and `gain_db` adjusts the pulse's volume. Calls
+    /// for controllers that aren't connected, or made once Steam is shutting down,
+    /// are ignored.
+    pub fn haptic_pulse(
+        &self,
+        controller: SteamControllerHandle,
+        location: EControllerHapticLocation,
+        intensity: u8,
+        gain_db: i8,
+    ) {
+        if !self.is_connected(controller) {
+            debug!(
+                "Ignoring haptics for disconnected controller {}",
+                controller
+            );
+            return;
+        }
+        // steamworks does not wrap TriggerSimpleHapticEvent, so it is called directly.
+        // SAFETY: The Steam API is initialized for as long as a `Client` exists.
+        unsafe {
+            sys::SteamAPI_ISteamInput_TriggerSimpleHapticEvent(
+                sys::SteamAPI_SteamInput_v006(),
+                controller,
+                location,
+                intensity,
+                gain_db as _,
+                intensity,
+                gain_db as _,
+            );
+        }
+    }
+
+    fn is_connected(&self, controller: SteamControllerHandle) -> bool {
+        !self.client.is_shutting_down()
+            && self
+                .input()
+                .get_connected_controllers()
+                .contains(&controller)
+    }
+}
+
+fn trigger_vibration(controller: SteamControllerHandle, speed: u16) {
+    // steamworks does not wrap TriggerVibration, so it is called directly.
+    // SAFETY: The Steam API is initialized for as long as a `Client` exists.
+    unsafe {
+        sys::SteamAPI_ISteamInput_TriggerVibration(
+            sys::SteamAPI_SteamInput_v006(),
+            controller,
+            speed,
+            speed,
+        );
+    }
+}
+
+/// Extends [`Commands`] with Steam Input haptics.
+pub trait SteamInputCommands {
+    /// Vibrates a controller's rumble motors. See [`SteamInput::rumble`].
+    fn steam_rumble(
+        &mut self,
+        controller: SteamControllerHandle,
+        duration: Duration,
+        intensity: f32,
+    );
+}
+
+impl SteamInputCommands for Commands<'_, '_> {
+    fn steam_rumble(
+        &mut self,
+        controller: SteamControllerHandle,
+        duration: Duration,
+        intensity: f32,
+    ) {
+        self.add(move |world: &mut World| {
+            if let Some(mut input) = world.get_resource_mut::<SteamInput>() {
+                input.rumble(controller, duration, intensity);
+            }
+        });
+    }
+}
+
+pub(super) fn stop_expired_rumble(mut input: ResMut<SteamInput>) {
+    if input.rumbles.is_empty() {
+        return;
+    }
+    let now = Instant::now();
+    let expired: Vec<SteamControllerHandle> = input
+        .rumbles
+        .iter()
+        .filter(|(_, stop_at)| **stop_at <= now)
+        .map(|(controller, _)| *controller)
+        .collect();
+    for controller in expired {
+        input.stop_rumble(controller);
+    }
+}
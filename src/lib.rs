@@ -4,7 +4,7 @@
 //! the Steamworks SDK.
 //!
 //! The underlying steamworks crate comes bundled with the redistributable dynamic
-//! libraries a compatible version of the SDK. Currently it's v153a.
+//! libraries a compatible version of the SDK. Currently it's v158a.
 //!
 //! ## Usage
 //!
@@ -56,26 +56,95 @@
 //! }
 //! ```
 
+mod apps;
+mod auth;
+mod call_result;
+mod cloud;
+mod conditions;
+#[cfg(feature = "dev-tools")]
+mod dev_tools;
+mod device;
+mod event_buffer;
+mod friends;
+mod init_status;
+mod input;
+mod lobby;
+mod microtxn;
+mod p2p;
+mod remote_play;
+mod restrictions;
+mod rich_presence;
+mod screenshots;
+mod server_browser;
+mod shutdown;
+mod stats;
+#[cfg(feature = "test-utils")]
+pub mod test;
+mod text_input;
+mod time;
+mod voice;
+mod watchdog;
+mod workshop;
+
+pub use crate::apps::*;
+pub use crate::auth::*;
+pub use crate::cloud::*;
+pub use crate::conditions::*;
+#[cfg(feature = "dev-tools")]
+pub use crate::dev_tools::*;
+pub use crate::device::*;
+pub use crate::event_buffer::{EventOverflowPolicy, SteamEventsDropped};
+pub use crate::friends::*;
+pub use crate::init_status::*;
+pub use crate::input::*;
+pub use crate::lobby::*;
+pub use crate::microtxn::*;
+pub use crate::p2p::*;
+pub use crate::remote_play::*;
+pub use crate::restrictions::*;
+pub use crate::rich_presence::*;
+pub use crate::screenshots::*;
+pub use crate::server_browser::*;
+pub use crate::shutdown::{ShuttingDown, SteamShuttingDown};
+pub use crate::stats::*;
+pub use crate::text_input::*;
+pub use crate::time::*;
+pub use crate::voice::*;
+pub use crate::watchdog::SteamCallbackOverrun;
+pub use crate::workshop::*;
+
 use std::{
     ops::Deref,
-    sync::{Arc, Mutex},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::{Duration, Instant},
 };
 
 use bevy_app::{App, First, Plugin};
 use bevy_ecs::{
-    event::EventWriter,
+    event::{EventWriter, Events},
     prelude::Event,
     schedule::*,
-    system::{Res, ResMut, Resource},
+    system::{Res, ResMut, Resource, RunSystemOnce},
+    world::World,
 };
+use bevy_log::warn;
 use bevy_utils::{synccell::SyncCell, syncunsafecell::SyncUnsafeCell};
+
+use crate::{
+    event_buffer::{EventBuffer, EventBufferConfig},
+    shutdown::{steam_running, RegisteredCallback},
+    watchdog::CallbackWatchdog,
+};
+
+#[cfg(feature = "raw-sys")]
+pub use steamworks::sys;
 // Reexport everything from steamworks except for the clients
 pub use steamworks::{
-    networking_messages, networking_sockets, networking_utils, restart_app_if_necessary, AccountId,
-    AppIDs, AppId, Apps, AuthSessionError, AuthSessionTicketResponse, AuthSessionValidateError,
-    AuthTicket, Callback, CallbackHandle, ChatMemberStateChange, ComparisonFilter,
-    CreateQueryError, DistanceFilter, DownloadItemResult, FileType,
-    FloatingGamepadTextInputDismissed, FloatingGamepadTextInputMode, Friend, FriendFlags,
+    networking_messages, networking_sockets, networking_types, networking_utils,
+    restart_app_if_necessary, AccountId, AppIDs, AppId, Apps, AuthSessionError,
+    AuthSessionTicketResponse, AuthSessionValidateError, AuthTicket, Callback, CallbackHandle,
+    ChatMemberStateChange, ComparisonFilter, CreateQueryError, DistanceFilter, DownloadItemResult,
+    FileType, FloatingGamepadTextInputDismissed, FloatingGamepadTextInputMode, Friend, FriendFlags,
     FriendGame, FriendState, Friends, GameId, GameLobbyJoinRequested, GameOverlayActivated,
     GamepadTextInputDismissed, GamepadTextInputLineMode, GamepadTextInputMode, Input, InstallInfo,
     InvalidErrorCode, ItemState, Leaderboard, LeaderboardDataRequest, LeaderboardDisplayType,
@@ -95,23 +164,78 @@ pub use steamworks::{
     UserStatsReceived, UserStatsStored, Utils, ValidateAuthTicketResponse, RESULTS_PER_PAGE, UGC,
 };
 
+/// Owns the registered callbacks along with the clients that run them.
+///
+/// Keeping them in one resource fixes the order they are torn down in when the
+/// [`World`](bevy_ecs::world::World) is dropped, rather than leaving it to the
+/// ECS: fields are dropped in declaration order, so callbacks are disconnected
+/// first, then the client is released, then the single client.
+///
+/// Other resources may still hold clients of their own, so this is not
+/// necessarily where Steam is shut down. Those that hand a client to another
+/// thread wait for it before releasing their own, so the last client is released
+/// on the thread dropping the world.
 #[derive(Resource)]
-struct SteamEvents {
-    _callbacks: Vec<CallbackHandle>,
-    pending: Arc<SyncUnsafeCell<Vec<SteamworksEvent>>>,
+struct SteamCallbacks {
+    _callbacks: Vec<RegisteredCallback>,
+    _client: steamworks::Client,
+    single: SyncCell<steamworks::SingleClient>,
+    /// Where callbacks buffer events until they are sent. `None` with
+    /// [`SteamworksPlugin::minimal`], which sends no events.
+    pending: Option<Arc<SyncUnsafeCell<EventBuffer>>>,
+    watchdog: Option<CallbackWatchdog>,
+    /// Whether to time every run of callbacks, even with no watchdog.
+    time_runs: bool,
+    /// How long the last timed run of callbacks took.
+    last_run: Option<Duration>,
 }
 
 /// A Bevy-compatible wrapper around various Steamworks events.
+///
+/// Events are sent in [`First`], in [`SteamworksSystem::RunCallbacks`], and can be
+/// read from `FixedUpdate` as well as `Update`. Every event sent by this crate is
+/// registered with [`App::add_event`], so with Bevy's `TimePlugin` added, events are
+/// kept until at least one fixed tick has run. An `EventReader` in `FixedUpdate`
+/// therefore sees each event exactly once: events sent on a frame without a fixed
+/// tick are read on the next frame that has one, and on a frame with several
+/// ticks, only the first tick sees them.
+///
+/// ```rust no_run
+/// use bevy::prelude::*;
+/// use bevy_steamworks::*;
+///
+/// fn simulate(mut events: EventReader<SteamworksEvent>) {
+///     for event in events.read() {
+///         if let SteamworksEvent::P2PSessionRequest(request) = event {
+///             // Runs once per request, no matter how many fixed ticks a frame has.
+///             println!("P2P session requested by {:?}", request.remote);
+///         }
+///     }
+/// }
+///
+/// App::new()
+///     .add_plugins(SteamworksPlugin::init_app(480).unwrap())
+///     .add_plugins(DefaultPlugins)
+///     .add_systems(FixedUpdate, simulate)
+///     .run();
+/// ```
+///
+/// Readers in `FixedUpdate` should not also be run from another schedule, since
+/// each system keeps its own read position.
 #[derive(Event)]
 #[allow(missing_docs)]
 pub enum SteamworksEvent {
     AuthSessionTicketResponse(steamworks::AuthSessionTicketResponse),
     DownloadItemResult(steamworks::DownloadItemResult),
+    GameOverlayActivated(steamworks::GameOverlayActivated),
     GameLobbyJoinRequested(steamworks::GameLobbyJoinRequested),
     LobbyChatUpdate(steamworks::LobbyChatUpdate),
+    MicroTxnAuthorizationResponse(steamworks::MicroTxnAuthorizationResponse),
     P2PSessionConnectFail(steamworks::P2PSessionConnectFail),
     P2PSessionRequest(steamworks::P2PSessionRequest),
     PersonaStateChange(steamworks::PersonaStateChange),
+    RemotePlayConnected(steamworks::RemotePlayConnected),
+    RemotePlayDisconnected(steamworks::RemotePlayDisconnected),
     SteamServerConnectFailure(steamworks::SteamServerConnectFailure),
     SteamServersConnected(steamworks::SteamServersConnected),
     SteamServersDisconnected(steamworks::SteamServersDisconnected),
@@ -123,23 +247,29 @@ pub enum SteamworksEvent {
 }
 
 macro_rules! register_event_callbacks {
-    ($client: ident, $($event_name: ident),+) => {
+    ($client: ident, $single: ident, $config: expr, $watchdog: expr $(, $event_name: ident)*) => {
         {
-            let pending = Arc::new(SyncUnsafeCell::new(Vec::new()));
-            SteamEvents {
+            let pending = Arc::new(SyncUnsafeCell::new(EventBuffer::new($config)));
+            SteamCallbacks {
                 _callbacks: vec![
                     $({
                         let pending_in = pending.clone();
-                        $client.register_callback::<steamworks::$event_name, _>(move |evt| {
+                        let handle = $client.register_callback::<steamworks::$event_name, _>(move |evt| {
                             // SAFETY: The callback is only called during `run_steam_callbacks` which cannot run
                             // while any of the flush_events systems are running. This cannot alias.
                             unsafe {
                                 (&mut *pending_in.get()).push(SteamworksEvent::$event_name(evt));
                             }
-                        })
-                    }),+
+                        });
+                        RegisteredCallback::new(handle)
+                    }),*
                 ],
-                pending,
+                _client: $client,
+                single: SyncCell::new($single),
+                pending: Some(pending),
+                watchdog: $watchdog,
+                time_runs: false,
+                last_run: None,
             }
         }
     };
@@ -151,8 +281,52 @@ macro_rules! register_event_callbacks {
 /// used.
 ///
 /// For more information on how to use it, see [`steamworks::Client`].
+///
+/// # Raw SDK calls
+///
+/// Interfaces that neither this crate nor steamworks wrap can be called through
+/// `steamworks::sys`, re-exported as [`sys`] with the `raw-sys` feature. Such
+/// calls should be made inside [`Client::with_raw`], which guarantees that Steam
+/// callbacks aren't being run at the same time.
 #[derive(Resource, Clone)]
-pub struct Client(steamworks::Client);
+pub struct Client(steamworks::Client, SteamShuttingDown, Arc<Mutex<()>>);
+
+impl Client {
+    /// Checks if Steam is shutting down. See [`SteamShuttingDown`].
+    pub fn is_shutting_down(&self) -> bool {
+        self.1.is_set()
+    }
+
+    /// Fails with [`ShuttingDown`] once Steam is shutting down, for helpers that
+    /// shouldn't call into Steam past that point.
+    pub fn ensure_running(&self) -> Result<(), ShuttingDown> {
+        if self.is_shutting_down() {
+            Err(ShuttingDown)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Calls `f` with the underlying client, while Steam callbacks are not being
+    /// run.
+    ///
+    /// `f` runs on the calling thread, and blocks [`SteamworksSystem::RunCallbacks`]
+    /// from running callbacks until it returns, so anything it reads or writes
+    /// through the raw API is never interleaved with a callback. Call results and
+    /// callbacks registered inside `f` are delivered on a later run of callbacks.
+    ///
+    /// This must not be called from within a Steam callback or call result, which
+    /// already run with callbacks locked, or it deadlocks. See the `raw_sdk_call`
+    /// example for a raw call made this way.
+    pub fn with_raw<R>(&self, f: impl FnOnce(&steamworks::Client) -> R) -> R {
+        let _pump = self.lock_callbacks();
+        f(&self.0)
+    }
+
+    fn lock_callbacks(&self) -> MutexGuard<'_, ()> {
+        self.2.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
 
 impl Deref for Client {
     type Target = steamworks::Client;
@@ -161,12 +335,13 @@ impl Deref for Client {
     }
 }
 
-#[derive(Resource)]
-struct SingleClient(SyncCell<steamworks::SingleClient>);
-
 /// A Bevy [`Plugin`] for adding support for the Steam SDK.
 pub struct SteamworksPlugin {
     steam: Mutex<Option<(steamworks::Client, steamworks::SingleClient)>>,
+    events: EventBufferConfig,
+    watchdog: Option<CallbackWatchdog>,
+    minimal: bool,
+    listen_for_friends_messages: bool,
 }
 
 impl SteamworksPlugin {
@@ -175,6 +350,10 @@ impl SteamworksPlugin {
     pub fn init_app(app_id: impl Into<AppId>) -> Result<Self, SteamAPIInitError> {
         Ok(Self {
             steam: Mutex::new(Some(steamworks::Client::init_app(app_id.into())?)),
+            events: EventBufferConfig::default(),
+            watchdog: None,
+            minimal: false,
+            listen_for_friends_messages: false,
         })
     }
 
@@ -185,8 +364,105 @@ impl SteamworksPlugin {
     pub fn init() -> Result<Self, SteamAPIInitError> {
         Ok(Self {
             steam: Mutex::new(Some(steamworks::Client::init()?)),
+            events: EventBufferConfig::default(),
+            watchdog: None,
+            minimal: false,
+            listen_for_friends_messages: false,
         })
     }
+
+    /// Sets how many Steam events' worth of buffer capacity is kept between frames.
+    /// Defaults to 1024.
+    ///
+    /// Events are buffered as callbacks run and forwarded as [`SteamworksEvent`]s
+    /// once per frame. Capacity beyond this that a burst of events needed is freed
+    /// right away, and unused capacity below it is freed once it has gone unused
+    /// for a few seconds.
+    pub fn with_event_buffer_capacity(mut self, capacity: usize) -> Self {
+        self.events.retained_capacity = capacity;
+        self
+    }
+
+    /// Sets whether [`SteamworksEvent::PersonaStateChange`]s for the same user
+    /// within a frame are merged into one. Off by default.
+    ///
+    /// Users with many friends can receive dozens of redundant persona changes at
+    /// once. When merged, a single event is sent in place of the user's last
+    /// change that frame, with the change flags of every merged event combined.
+    pub fn coalesce_persona_changes(mut self, coalesce: bool) -> Self {
+        self.events.coalesce_persona_changes = coalesce;
+        self
+    }
+
+    /// Sets the most Steam events buffered at once, and what to do with events
+    /// beyond that. Defaults to 65536 events, dropping the oldest.
+    ///
+    /// The buffer only fills up if Steam sends a flood of callbacks within a
+    /// single frame, such as during a long hitch. Dropped events are reported
+    /// with a [`SteamEventsDropped`] event and a warning.
+    pub fn with_max_pending_events(mut self, max: usize, overflow: EventOverflowPolicy) -> Self {
+        self.events.max_pending = max;
+        self.events.overflow = overflow;
+        self
+    }
+
+    /// Sets the most [`SteamworksEvent`]s forwarded per frame. Unlimited by default.
+    ///
+    /// Bursts of events, such as the persona changes of a large friends list
+    /// syncing, are spread over several frames in the order they arrived. Events
+    /// waiting for a later frame still count towards
+    /// [`SteamworksPlugin::with_max_pending_events`], so a burst that outgrows it
+    /// drops events as usual.
+    pub fn with_max_events_per_frame(mut self, max: usize) -> Self {
+        self.events.max_per_frame = Some(max);
+        self
+    }
+
+    /// Reports frames where running Steam callbacks takes longer than `threshold`
+    /// with a [`SteamCallbackOverrun`] event, and if `log` is set, a warning.
+    ///
+    /// Completed stat stores and UGC queries can make a single run of callbacks
+    /// slow. The run is only timed while the watchdog is enabled.
+    pub fn with_callback_watchdog(mut self, threshold: Duration, log: bool) -> Self {
+        self.watchdog = Some(CallbackWatchdog { threshold, log });
+        self
+    }
+
+    /// Only inserts [`Client`] and runs Steam callbacks in
+    /// [`SteamworksSystem::RunCallbacks`], for apps that make their own Steam calls.
+    ///
+    /// No callbacks are registered for [`SteamworksEvent`]s, which are never sent
+    /// or registered as events, and none of the crate's helper resources are
+    /// inserted. Opt-in plugins such as [`SteamInputPlugin`] and [`SteamVoicePlugin`]
+    /// can still be added, but skip everything driven by [`SteamworksEvent`]s, such
+    /// as [`BindingPanelClosed`] and pausing push-to-talk while the overlay is open.
+    ///
+    /// ```rust no_run
+    /// use bevy::prelude::*;
+    /// use bevy_steamworks::*;
+    ///
+    /// fn print_name(client: Res<Client>) {
+    ///     println!("Playing as {}", client.friends().name());
+    /// }
+    ///
+    /// App::new()
+    ///     .add_plugins(SteamworksPlugin::init_app(480).unwrap().minimal())
+    ///     .add_plugins(DefaultPlugins)
+    ///     .add_systems(Startup, print_name)
+    ///     .run();
+    /// ```
+    pub fn minimal(mut self) -> Self {
+        self.minimal = true;
+        self
+    }
+
+    /// Sets whether messages friends send through Steam chat are delivered to the
+    /// game as [`FriendChatMessage`] events, including game invites. Disabled by
+    /// default, and ignored by [`SteamworksPlugin::minimal`].
+    pub fn listen_for_friends_messages(mut self, listen: bool) -> Self {
+        self.listen_for_friends_messages = listen;
+        self
+    }
 }
 
 impl Plugin for SteamworksPlugin {
@@ -198,34 +474,115 @@ impl Plugin for SteamworksPlugin {
             .take()
             .expect("The SteamworksPlugin was initialized more than once");
 
-        app.insert_resource(Client(client.clone()))
-            .insert_resource(SingleClient(SyncCell::new(single)))
-            .insert_resource(register_event_callbacks!(
-                client,
-                AuthSessionTicketResponse,
-                DownloadItemResult,
-                GameLobbyJoinRequested,
-                LobbyChatUpdate,
-                P2PSessionConnectFail,
-                P2PSessionRequest,
-                PersonaStateChange,
-                SteamServerConnectFailure,
-                SteamServersConnected,
-                SteamServersDisconnected,
-                TicketForWebApiResponse,
-                UserAchievementStored,
-                UserStatsReceived,
-                UserStatsStored,
-                ValidateAuthTicketResponse
-            ))
+        let shutting_down = SteamShuttingDown::default();
+        app.insert_resource(Client(
+            client.clone(),
+            shutting_down.clone(),
+            Arc::default(),
+        ));
+        let shutdown_callback = shutdown::register_shutdown_callback(&client, &shutting_down);
+        if self.minimal {
+            let callbacks = SteamCallbacks {
+                _callbacks: vec![shutdown_callback],
+                _client: client,
+                single: SyncCell::new(single),
+                pending: None,
+                watchdog: None,
+                time_runs: false,
+                last_run: None,
+            };
+            app.insert_resource(shutting_down)
+                .insert_resource(callbacks)
+                .insert_resource(SteamInitStatus::Ok)
+                .configure_sets(First, SteamworksSystem::RunCallbacks)
+                .add_systems(
+                    First,
+                    (
+                        shutdown::detect_app_exit.before(SteamworksSystem::RunCallbacks),
+                        pump_steam_callbacks
+                            .in_set(SteamworksSystem::RunCallbacks)
+                            .run_if(steam_running),
+                    ),
+                );
+            return;
+        }
+
+        // Steam only delivers friends' messages to callbacks registered after
+        // listening is enabled.
+        if self.listen_for_friends_messages {
+            friends::chat::listen_for_friends_messages(&client);
+        }
+        let mut callbacks = register_event_callbacks!(
+            client,
+            single,
+            self.events,
+            self.watchdog,
+            AuthSessionTicketResponse,
+            DownloadItemResult,
+            GameOverlayActivated,
+            GameLobbyJoinRequested,
+            LobbyChatUpdate,
+            MicroTxnAuthorizationResponse,
+            P2PSessionConnectFail,
+            P2PSessionRequest,
+            PersonaStateChange,
+            RemotePlayConnected,
+            RemotePlayDisconnected,
+            SteamServerConnectFailure,
+            SteamServersConnected,
+            SteamServersDisconnected,
+            TicketForWebApiResponse,
+            UserAchievementStored,
+            UserStatsReceived,
+            UserStatsStored,
+            ValidateAuthTicketResponse
+        );
+        callbacks._callbacks.push(shutdown_callback);
+
+        app.insert_resource(shutting_down)
+            .insert_resource(callbacks)
+            .insert_resource(SteamInitStatus::Ok)
             .add_event::<SteamworksEvent>()
+            .add_event::<SteamEventsDropped>()
+            .add_event::<SteamCallbackOverrun>()
             .configure_sets(First, SteamworksSystem::RunCallbacks)
             .add_systems(
                 First,
-                run_steam_callbacks
-                    .in_set(SteamworksSystem::RunCallbacks)
-                    .before(bevy_ecs::event::EventUpdates),
-            );
+                (
+                    shutdown::detect_app_exit.before(SteamworksSystem::RunCallbacks),
+                    run_steam_callbacks
+                        .in_set(SteamworksSystem::RunCallbacks)
+                        .before(bevy_ecs::event::EventUpdates)
+                        .run_if(steam_running),
+                ),
+            )
+            // Plugin tuples are limited in length, so they are nested.
+            .add_plugins((
+                (
+                    apps::plugin,
+                    auth::plugin,
+                    cloud::plugin,
+                    device::plugin,
+                    friends::plugin,
+                    lobby::plugin,
+                    microtxn::plugin,
+                    p2p::plugin,
+                    remote_play::plugin,
+                ),
+                (
+                    restrictions::plugin,
+                    rich_presence::plugin,
+                    screenshots::plugin,
+                    server_browser::plugin,
+                    stats::plugin,
+                    text_input::plugin,
+                    time::plugin,
+                    workshop::plugin,
+                ),
+            ));
+        if self.listen_for_friends_messages {
+            app.add_plugins(friends::chat::plugin);
+        }
     }
 }
 
@@ -238,19 +595,167 @@ pub enum SteamworksSystem {
     /// Steam API results should scheduled after this. This runs in
     /// [`First`].
     RunCallbacks,
+    /// A system set that runs Steam Input's frame when [`SteamInputPlugin`] is
+    /// added. Anything reading Steam Input action data should be scheduled after
+    /// this. This runs in [`PreUpdate`](bevy_app::PreUpdate).
+    RunInputFrame,
+}
+
+fn pump_steam_callbacks(client: Res<Client>, mut callbacks: ResMut<SteamCallbacks>) {
+    let _pump = client.lock_callbacks();
+    callbacks.single.get().run_callbacks();
+}
+
+/// Runs callbacks and forwards their events as [`SteamworksSystem::RunCallbacks`]
+/// does, for work done on exit that has to wait on Steam after [`First`] has run.
+///
+/// Taking the world exclusively keeps the systems that drain call results, which
+/// are otherwise ordered after [`SteamworksSystem::RunCallbacks`], from running
+/// at the same time.
+pub(crate) fn run_steam_callbacks_exclusive(world: &mut World) {
+    world.run_system_once(run_steam_callbacks);
+}
+
+/// Checks if [`SteamworksEvent`]s are sent to `app`, which they aren't with
+/// [`SteamworksPlugin::minimal`]. Opt-in plugins only add systems reading them if so.
+pub(crate) fn sends_steam_events(app: &App) -> bool {
+    app.world().contains_resource::<Events<SteamworksEvent>>()
 }
 
 fn run_steam_callbacks(
-    mut client: ResMut<SingleClient>,
-    events: Res<SteamEvents>,
+    client: Res<Client>,
+    mut callbacks: ResMut<SteamCallbacks>,
     mut output: EventWriter<SteamworksEvent>,
+    mut dropped: EventWriter<SteamEventsDropped>,
+    mut overrun: EventWriter<SteamCallbackOverrun>,
 ) {
-    client.0.get().run_callbacks();
-    // SAFETY: The callback is only called during `run_steam_callbacks` which cannot run
-    // while any of the flush_events systems are running. The system is registered only once for
-    // the client. This cannot alias.
-    let pending = unsafe { &mut *events.pending.get() };
-    if !pending.is_empty() {
-        output.send_batch(pending.drain(0..));
+    let timed = callbacks.time_runs || callbacks.watchdog.is_some();
+    let started = timed.then(Instant::now);
+    {
+        let _pump = client.lock_callbacks();
+        callbacks.single.get().run_callbacks();
+    }
+    let duration = started.map(|started| started.elapsed());
+    callbacks.last_run = duration;
+    let mut forwarded = 0;
+    let mut count = 0;
+    if let Some(pending) = &callbacks.pending {
+        // SAFETY: The callback is only called during `run_steam_callbacks` which cannot run
+        // while any of the flush_events systems are running. The system is registered only
+        // once for the client. This cannot alias.
+        let pending = unsafe { &mut *pending.get() };
+        count = pending.flush(|events| {
+            forwarded = events.len();
+            output.send_batch(events);
+        });
+    }
+    if let (Some(watchdog), Some(duration)) = (callbacks.watchdog, duration) {
+        if duration > watchdog.threshold {
+            if watchdog.log {
+                warn!(
+                    "Running Steam callbacks took {:?}, forwarding {} events",
+                    duration, forwarded
+                );
+            }
+            overrun.send(SteamCallbackOverrun {
+                duration,
+                events: forwarded,
+            });
+        }
+    }
+    if count > 0 {
+        warn!(
+            "Dropped {} Steam events because too many were buffered at once",
+            count
+        );
+        dropped.send(SteamEventsDropped { count });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_app::FixedUpdate;
+    use bevy_ecs::event::{EventReader, EventUpdates};
+    use bevy_time::{Fixed, Time, TimePlugin, TimeUpdateStrategy};
+
+    use super::*;
+
+    const FIXED_TIMESTEP: Duration = Duration::from_millis(10);
+
+    /// How many overlay events to send on the next frame.
+    #[derive(Resource, Default)]
+    struct ToSend(usize);
+
+    #[derive(Resource, Default)]
+    struct FixedReads {
+        ticks: usize,
+        overlay_events: usize,
+    }
+
+    /// Sends events the way [`run_steam_callbacks`] does, before events are updated.
+    fn send_events(mut to_send: ResMut<ToSend>, mut events: EventWriter<SteamworksEvent>) {
+        for _ in 0..std::mem::take(&mut to_send.0) {
+            events.send(SteamworksEvent::GameOverlayActivated(
+                GameOverlayActivated { active: true },
+            ));
+        }
+    }
+
+    fn read_in_fixed_update(
+        mut events: EventReader<SteamworksEvent>,
+        mut reads: ResMut<FixedReads>,
+    ) {
+        reads.ticks += 1;
+        reads.overlay_events += events.read().count();
+    }
+
+    /// Runs a frame that advances time by `fixed_ticks` fixed timesteps, returning
+    /// how many ticks ran and how many overlay events they read.
+    fn fixed_frame(app: &mut App, fixed_ticks: u32) -> (usize, usize) {
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(
+            FIXED_TIMESTEP * fixed_ticks,
+        ))
+        .insert_resource(FixedReads::default());
+        app.update();
+        let reads = app.world().resource::<FixedReads>();
+        (reads.ticks, reads.overlay_events)
+    }
+
+    fn send_overlay_event(app: &mut App) {
+        app.world_mut().resource_mut::<ToSend>().0 += 1;
+    }
+
+    #[test]
+    fn fixed_update_reads_events_once() {
+        let mut app = App::new();
+        app.add_plugins(TimePlugin)
+            .add_event::<SteamworksEvent>()
+            .insert_resource(Time::<Fixed>::from_duration(FIXED_TIMESTEP))
+            .init_resource::<ToSend>()
+            .init_resource::<FixedReads>()
+            .add_systems(First, send_events.before(EventUpdates))
+            .add_systems(FixedUpdate, read_in_fixed_update);
+        for _ in 0..3 {
+            fixed_frame(&mut app, 1);
+        }
+
+        // Frames without a fixed tick keep the event until one runs.
+        send_overlay_event(&mut app);
+        assert_eq!(fixed_frame(&mut app, 0), (0, 0));
+        assert_eq!(fixed_frame(&mut app, 0), (0, 0));
+        assert_eq!(fixed_frame(&mut app, 1), (1, 1));
+        assert_eq!(fixed_frame(&mut app, 1), (1, 0));
+
+        // Only the first of several ticks in a frame reads the event.
+        send_overlay_event(&mut app);
+        assert_eq!(fixed_frame(&mut app, 3), (3, 1));
+        assert_eq!(fixed_frame(&mut app, 2), (2, 0));
+
+        // Events from a frame without a tick and a frame with several are each read once.
+        send_overlay_event(&mut app);
+        assert_eq!(fixed_frame(&mut app, 0), (0, 0));
+        send_overlay_event(&mut app);
+        assert_eq!(fixed_frame(&mut app, 4), (4, 2));
+        assert_eq!(fixed_frame(&mut app, 1), (1, 0));
     }
 }
@@ -0,0 +1,268 @@
+mod filter;
+mod matchmaking;
+
+pub use filter::*;
+pub use matchmaking::*;
+
+use std::fmt;
+
+use bevy_app::{App, First};
+use bevy_ecs::{
+    event::{Event, EventReader, EventWriter},
+    schedule::IntoSystemConfigs,
+    system::{ResMut, Resource},
+};
+use steamworks::{sys, LobbyId, SteamId};
+
+use crate::{shutdown::steam_running, Client, ShuttingDown, SteamworksEvent, SteamworksSystem};
+
+/// An error from [`CurrentLobby::transfer_ownership`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LobbyOwnershipError {
+    /// There is no current lobby.
+    NotInLobby,
+    /// The local user doesn't own the current lobby.
+    NotOwner,
+    /// The new owner isn't a member of the current lobby.
+    NotMember,
+    /// Steam refused the transfer.
+    Failed,
+    /// Steam is shutting down.
+    ShuttingDown,
+}
+
+impl fmt::Display for LobbyOwnershipError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotInLobby => f.write_str("not in a lobby"),
+            Self::NotOwner => f.write_str("the local user does not own the lobby"),
+            Self::NotMember => f.write_str("the new owner is not a member of the lobby"),
+            Self::Failed => f.write_str("Steam refused to transfer lobby ownership"),
+            Self::ShuttingDown => write!(f, "{ShuttingDown}"),
+        }
+    }
+}
+
+impl std::error::Error for LobbyOwnershipError {}
+
+impl From<ShuttingDown> for LobbyOwnershipError {
+    fn from(_: ShuttingDown) -> Self {
+        Self::ShuttingDown
+    }
+}
+
+/// Sent when the owner of the [`CurrentLobby`] changes, such as when Steam
+/// promotes another member after the owner leaves.
+///
+/// Host migration can be driven from this event:
+///
+/// ```rust no_run
+/// use bevy::prelude::*;
+/// use bevy_steamworks::*;
+///
+/// fn migrate_host(
+///     mut changes: EventReader<LobbyOwnerChanged>,
+///     mut sessions: ResMut<P2PSessions>,
+/// ) {
+///     for change in changes.read() {
+///         if change.i_am_now_owner {
+///             // Start accepting P2P sessions from the other members.
+///             println!("Now hosting {:?}", change.lobby);
+///         } else {
+///             // Reconnect to the new host.
+///             sessions.track(change.new_owner);
+///         }
+///     }
+/// }
+/// ```
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LobbyOwnerChanged {
+    /// The lobby whose owner changed.
+    pub lobby: LobbyId,
+    /// The previous owner.
+    pub old_owner: SteamId,
+    /// The new owner.
+    pub new_owner: SteamId,
+    /// Whether the local user is the new owner.
+    pub i_am_now_owner: bool,
+}
+
+/// The lobby the local user is in, along with its owner and members.
+///
+/// Steam doesn't say which lobby the game considers current, so it is set with
+/// [`CurrentLobby::enter`] once a lobby has been joined or created. Members and
+/// ownership are then kept current, with a [`LobbyOwnerChanged`] event sent when
+/// the owner changes. The current lobby is cleared once the local user is no
+/// longer one of its members, such as after being kicked.
+#[derive(Resource)]
+pub struct CurrentLobby {
+    client: Client,
+    lobby: Option<LobbyId>,
+    owner: Option<SteamId>,
+    members: Vec<SteamId>,
+}
+
+impl CurrentLobby {
+    fn new(client: &Client) -> Self {
+        Self {
+            client: client.clone(),
+            lobby: None,
+            owner: None,
+            members: Vec::new(),
+        }
+    }
+
+    /// Gets the current lobby.
+    pub fn id(&self) -> Option<LobbyId> {
+        self.lobby
+    }
+
+    /// Gets the owner of the current lobby, or `None` until Steam reports one.
+    pub fn owner(&self) -> Option<SteamId> {
+        self.owner
+    }
+
+    /// Gets the members of the current lobby, including the local user.
+    pub fn members(&self) -> &[SteamId] {
+        &self.members
+    }
+
+    /// Checks if the local user owns the current lobby. Returns `false` once Steam
+    /// is shutting down.
+    pub fn is_owner(&self) -> bool {
+        !self.client.is_shutting_down() && self.owner == Some(self.client.user().steam_id())
+    }
+
+    /// Makes a lobby the local user has joined or created the current lobby.
+    pub fn enter(&mut self, lobby: LobbyId) -> Result<(), ShuttingDown> {
+        self.client.ensure_running()?;
+        self.enter_unchecked(lobby);
+        Ok(())
+    }
+
+    fn enter_unchecked(&mut self, lobby: LobbyId) {
+        self.lobby = Some(lobby);
+        self.owner = self.lobby_owner(lobby);
+        self.refresh_members();
+    }
+
+    /// Leaves the current lobby. Does nothing if there is no current lobby.
+    pub fn leave(&mut self) -> Result<(), ShuttingDown> {
+        self.client.ensure_running()?;
+        let Some(lobby) = self.lobby else {
+            return Ok(());
+        };
+        self.client.matchmaking().leave_lobby(lobby);
+        self.clear();
+        Ok(())
+    }
+
+    /// Makes another member the owner of the current lobby.
+    ///
+    /// Fails if the local user doesn't own the lobby. A [`LobbyOwnerChanged`]
+    /// event is sent once Steam has made the change.
+    pub fn transfer_ownership(&self, member: SteamId) -> Result<(), LobbyOwnershipError> {
+        self.client.ensure_running()?;
+        let lobby = self.lobby.ok_or(LobbyOwnershipError::NotInLobby)?;
+        if !self.is_owner() {
+            return Err(LobbyOwnershipError::NotOwner);
+        }
+        if !self.members.contains(&member) {
+            return Err(LobbyOwnershipError::NotMember);
+        }
+        // steamworks does not wrap SetLobbyOwner, so it is called directly.
+        // SAFETY: The Steam API is initialized for as long as a `Client` exists.
+        let transferred = unsafe {
+            sys::SteamAPI_ISteamMatchmaking_SetLobbyOwner(
+                sys::SteamAPI_SteamMatchmaking_v009(),
+                lobby.raw(),
+                member.raw(),
+            )
+        };
+        if !transferred {
+            return Err(LobbyOwnershipError::Failed);
+        }
+        Ok(())
+    }
+
+    fn clear(&mut self) {
+        self.lobby = None;
+        self.owner = None;
+        self.members.clear();
+    }
+
+    /// Gets the owner of `lobby`, or `None` if Steam doesn't know it, such as while
+    /// lobby data is still arriving or once the local user has left.
+    fn lobby_owner(&self, lobby: LobbyId) -> Option<SteamId> {
+        let owner = self.client.matchmaking().lobby_owner(lobby);
+        // Steam reports an unknown owner as an invalid ID with no account.
+        (owner.account_id().raw() != 0).then_some(owner)
+    }
+
+    fn refresh_members(&mut self) {
+        self.members = match self.lobby {
+            Some(lobby) => self.client.matchmaking().lobby_members(lobby),
+            None => Vec::new(),
+        };
+    }
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    let client = app.world().resource::<Client>();
+    let lobby = CurrentLobby::new(client);
+    let matchmaking = SteamMatchmaking::new(client);
+    app.insert_resource(lobby)
+        .insert_resource(matchmaking)
+        .add_event::<LobbyOwnerChanged>()
+        .add_event::<QuickmatchSearching>()
+        .add_event::<QuickmatchJoining>()
+        .add_event::<QuickmatchComplete>()
+        .add_event::<QuickmatchFailed>()
+        .add_systems(
+            First,
+            (matchmaking::drive_quickmatch, track_current_lobby)
+                .chain()
+                .after(SteamworksSystem::RunCallbacks)
+                .run_if(steam_running),
+        );
+}
+
+pub(crate) fn track_current_lobby(
+    mut current: ResMut<CurrentLobby>,
+    mut events: EventReader<SteamworksEvent>,
+    mut owner_changed: EventWriter<LobbyOwnerChanged>,
+) {
+    let Some(lobby) = current.lobby else {
+        events.clear();
+        return;
+    };
+    // Every event is read, even after a match, so none are seen again next frame.
+    let members_changed = events.read().fold(false, |changed, event| {
+        changed
+            || matches!(event, SteamworksEvent::LobbyChatUpdate(update) if update.lobby == lobby)
+    });
+    if members_changed {
+        current.refresh_members();
+        if !current.members.contains(&current.client.user().steam_id()) {
+            current.clear();
+            return;
+        }
+    }
+    // Ownership changes arrive as lobby data updates, so the owner is compared
+    // every frame rather than only when members leave. An unknown owner is
+    // skipped, keeping the last one known.
+    let Some(new_owner) = current.lobby_owner(lobby) else {
+        return;
+    };
+    let Some(old_owner) = current.owner.replace(new_owner) else {
+        return;
+    };
+    if old_owner != new_owner {
+        owner_changed.send(LobbyOwnerChanged {
+            lobby,
+            old_owner,
+            new_owner,
+            i_am_now_owner: new_owner == current.client.user().steam_id(),
+        });
+    }
+}
@@ -0,0 +1,260 @@
+use steamworks::{
+    ComparisonFilter, DistanceFilter, LobbyKey, LobbyKeyTooLongError, LobbyListFilter, NearFilter,
+    NumberFilter, StringFilter, StringFilterKind,
+};
+
+/// Builds a [`LobbyListFilter`] for searching lobbies, such as through
+/// [`QuickmatchConfig::filters`](crate::QuickmatchConfig::filters).
+///
+/// Keys are checked against Steam's length limit when the filter is built.
+///
+/// ```rust
+/// use bevy_steamworks::*;
+///
+/// let filter = LobbyFilterBuilder::new()
+///     .string("mode", "ctf", StringFilterKind::Include)
+///     .number_at_least("min_level", 10)
+///     .near("elo", 1200)
+///     .slots_available(1)
+///     .distance(DistanceFilter::Far)
+///     .max_results(50)
+///     .build();
+/// assert!(filter.is_ok());
+///
+/// let key = "k".repeat(256);
+/// let filter = LobbyFilterBuilder::new().near(&key, 0).build();
+/// assert!(filter.is_err());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LobbyFilterBuilder<'a> {
+    strings: Vec<(&'a str, &'a str, StringFilterKind)>,
+    numbers: Vec<(&'a str, i32, ComparisonFilter)>,
+    near: Vec<(&'a str, i32)>,
+    open_slots: Option<u8>,
+    distance: Option<DistanceFilter>,
+    max_results: Option<u64>,
+}
+
+impl<'a> LobbyFilterBuilder<'a> {
+    /// Creates a builder that matches every lobby.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Filters on a string value of the lobby's data.
+    pub fn string(mut self, key: &'a str, value: &'a str, kind: StringFilterKind) -> Self {
+        self.strings.push((key, value, kind));
+        self
+    }
+
+    /// Filters on a numerical value of the lobby's data.
+    pub fn number(mut self, key: &'a str, value: i32, comparison: ComparisonFilter) -> Self {
+        self.numbers.push((key, value, comparison));
+        self
+    }
+
+    /// Only matches lobbies whose value for `key` is at least `value`.
+    pub fn number_at_least(self, key: &'a str, value: i32) -> Self {
+        self.number(key, value, ComparisonFilter::EqualToOrGreaterThan)
+    }
+
+    /// Only matches lobbies whose value for `key` is at most `value`.
+    pub fn number_at_most(self, key: &'a str, value: i32) -> Self {
+        self.number(key, value, ComparisonFilter::EqualToOrLessThan)
+    }
+
+    /// Only matches lobbies whose value for `key` is exactly `value`.
+    pub fn number_equal(self, key: &'a str, value: i32) -> Self {
+        self.number(key, value, ComparisonFilter::Equal)
+    }
+
+    /// Sorts lobbies by how close their value for `key` is to `value`. Earlier
+    /// calls take precedence when sorting.
+    pub fn near(mut self, key: &'a str, value: i32) -> Self {
+        self.near.push((key, value));
+        self
+    }
+
+    /// Only matches lobbies with at least `slots` open member slots.
+    pub fn slots_available(mut self, slots: u8) -> Self {
+        self.open_slots = Some(slots);
+        self
+    }
+
+    /// Limits how far away lobbies may be, by region.
+    pub fn distance(mut self, distance: DistanceFilter) -> Self {
+        self.distance = Some(distance);
+        self
+    }
+
+    /// Limits how many lobbies are returned.
+    pub fn max_results(mut self, count: u64) -> Self {
+        self.max_results = Some(count);
+        self
+    }
+
+    /// Builds the filter, failing if any key is too long for Steam.
+    pub fn build(self) -> Result<LobbyListFilter<'a>, LobbyKeyTooLongError> {
+        let strings = self
+            .strings
+            .into_iter()
+            .map(|(key, value, kind)| Ok(StringFilter(LobbyKey::try_new(key)?, value, kind)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let numbers = self
+            .numbers
+            .into_iter()
+            .map(|(key, value, comparison)| {
+                Ok(NumberFilter(LobbyKey::try_new(key)?, value, comparison))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let near = self
+            .near
+            .into_iter()
+            .map(|(key, value)| Ok(NearFilter(LobbyKey::try_new(key)?, value)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(LobbyListFilter {
+            string: (!strings.is_empty()).then_some(strings),
+            number: (!numbers.is_empty()).then_some(numbers),
+            near_value: (!near.is_empty()).then_some(near),
+            open_slots: self.open_slots,
+            distance: self.distance,
+            count: self.max_results,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_builder_matches_every_lobby() {
+        let filter = LobbyFilterBuilder::new().build().unwrap();
+        assert!(filter.string.is_none());
+        assert!(filter.number.is_none());
+        assert!(filter.near_value.is_none());
+        assert_eq!(filter.open_slots, None);
+        assert_eq!(filter.distance, None);
+        assert_eq!(filter.count, None);
+    }
+
+    #[test]
+    fn string_filters() {
+        let filter = LobbyFilterBuilder::new()
+            .string("mode", "ctf", StringFilterKind::Include)
+            .string("map", "dust", StringFilterKind::Exclude)
+            .build()
+            .unwrap();
+        assert_eq!(
+            filter.string,
+            Some(vec![
+                StringFilter(LobbyKey::new("mode"), "ctf", StringFilterKind::Include),
+                StringFilter(LobbyKey::new("map"), "dust", StringFilterKind::Exclude),
+            ])
+        );
+    }
+
+    #[test]
+    fn number_filters() {
+        let filter = LobbyFilterBuilder::new()
+            .number("rank", 3, ComparisonFilter::GreaterThan)
+            .number_at_least("min_level", 10)
+            .number_at_most("max_level", 20)
+            .number_equal("version", 7)
+            .build()
+            .unwrap();
+        assert_eq!(
+            filter.number,
+            Some(vec![
+                NumberFilter(LobbyKey::new("rank"), 3, ComparisonFilter::GreaterThan),
+                NumberFilter(
+                    LobbyKey::new("min_level"),
+                    10,
+                    ComparisonFilter::EqualToOrGreaterThan
+                ),
+                NumberFilter(
+                    LobbyKey::new("max_level"),
+                    20,
+                    ComparisonFilter::EqualToOrLessThan
+                ),
+                NumberFilter(LobbyKey::new("version"), 7, ComparisonFilter::Equal),
+            ])
+        );
+    }
+
+    #[test]
+    fn near_filters_keep_their_order() {
+        let filter = LobbyFilterBuilder::new()
+            .near("elo", 1200)
+            .near("ping", 50)
+            .build()
+            .unwrap();
+        assert_eq!(
+            filter.near_value,
+            Some(vec![
+                NearFilter(LobbyKey::new("elo"), 1200),
+                NearFilter(LobbyKey::new("ping"), 50),
+            ])
+        );
+    }
+
+    #[test]
+    fn slots_distance_and_count() {
+        let filter = LobbyFilterBuilder::new()
+            .slots_available(2)
+            .distance(DistanceFilter::Worldwide)
+            .max_results(50)
+            .build()
+            .unwrap();
+        assert_eq!(filter.open_slots, Some(2));
+        assert_eq!(filter.distance, Some(DistanceFilter::Worldwide));
+        assert_eq!(filter.count, Some(50));
+    }
+
+    #[test]
+    fn later_calls_replace_single_values() {
+        let filter = LobbyFilterBuilder::new()
+            .slots_available(4)
+            .slots_available(1)
+            .max_results(10)
+            .max_results(100)
+            .build()
+            .unwrap();
+        assert_eq!(filter.open_slots, Some(1));
+        assert_eq!(filter.count, Some(100));
+    }
+
+    #[test]
+    fn rejects_keys_that_are_too_long() {
+        let key = "k".repeat(256);
+        assert!(LobbyFilterBuilder::new()
+            .string(&key, "value", StringFilterKind::Include)
+            .build()
+            .is_err());
+        assert!(LobbyFilterBuilder::new()
+            .number_equal(&key, 0)
+            .build()
+            .is_err());
+        assert!(LobbyFilterBuilder::new().near(&key, 0).build().is_err());
+    }
+
+    #[test]
+    fn filter_counts_are_not_limited() {
+        // Steam only limits key lengths, so however many filters are added are kept.
+        let keys: Vec<String> = (0..300).map(|i| format!("key_{i}")).collect();
+        let mut builder = LobbyFilterBuilder::new();
+        for key in &keys {
+            builder = builder
+                .string(key, "value", StringFilterKind::Include)
+                .number_equal(key, 0)
+                .near(key, 0);
+        }
+        let filter = builder.build().unwrap();
+        assert_eq!(filter.string.map(|filters| filters.len()), Some(keys.len()));
+        assert_eq!(filter.number.map(|filters| filters.len()), Some(keys.len()));
+        assert_eq!(
+            filter.near_value.map(|filters| filters.len()),
+            Some(keys.len())
+        );
+    }
+}
@@ -0,0 +1,223 @@
+use std::{collections::VecDeque, fmt};
+
+use bevy_ecs::{
+    event::{Event, EventWriter},
+    system::{ResMut, Resource},
+};
+use steamworks::{LobbyId, LobbyListFilter, LobbyType, SteamError};
+
+use super::CurrentLobby;
+use crate::{call_result::CallResults, Client, ShuttingDown};
+
+/// How [`SteamMatchmaking::quickmatch`] finds or creates a lobby.
+pub struct QuickmatchConfig<'a> {
+    /// The filters lobbies must match to be joined.
+    pub filters: LobbyListFilter<'a>,
+    /// The type of lobby to create if none can be joined.
+    pub lobby_type: LobbyType,
+    /// The most members a created lobby allows.
+    pub max_members: u32,
+    /// How many of the lobbies found are tried before creating one.
+    pub join_attempts: usize,
+}
+
+/// Why a quickmatch started with [`SteamMatchmaking::quickmatch`] failed.
+#[derive(Debug, Clone)]
+pub enum QuickmatchError {
+    /// Searching for lobbies failed.
+    SearchFailed(SteamError),
+    /// No lobby could be joined and creating one failed.
+    CreateFailed(SteamError),
+}
+
+impl fmt::Display for QuickmatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SearchFailed(err) => write!(f, "failed to search for lobbies: {err}"),
+            Self::CreateFailed(err) => write!(f, "failed to create a lobby: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for QuickmatchError {}
+
+/// Sent when a quickmatch starts searching for lobbies.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuickmatchSearching;
+
+/// Sent when a quickmatch tries to join a lobby it found.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuickmatchJoining(pub LobbyId);
+
+/// Sent when a quickmatch has joined or created a lobby, which is now the
+/// [`CurrentLobby`].
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuickmatchComplete {
+    /// The lobby that was joined or created.
+    pub lobby: LobbyId,
+    /// Whether the lobby was created rather than joined.
+    pub created: bool,
+}
+
+/// Sent when a quickmatch fails.
+#[derive(Event, Debug, Clone)]
+pub struct QuickmatchFailed(pub QuickmatchError);
+
+enum QuickmatchStep {
+    Listed(Result<Vec<LobbyId>, SteamError>),
+    Joined(Result<LobbyId, ()>),
+    Created(Result<LobbyId, SteamError>),
+}
+
+struct Quickmatch {
+    id: u64,
+    lobby_type: LobbyType,
+    max_members: u32,
+    join_attempts: usize,
+    candidates: VecDeque<LobbyId>,
+}
+
+/// Finds lobbies to play in.
+#[derive(Resource)]
+pub struct SteamMatchmaking {
+    client: Client,
+    active: Option<Quickmatch>,
+    next_id: u64,
+    searching: bool,
+    results: CallResults<(u64, QuickmatchStep)>,
+}
+
+impl SteamMatchmaking {
+    pub(super) fn new(client: &Client) -> Self {
+        Self {
+            client: client.clone(),
+            active: None,
+            next_id: 0,
+            searching: false,
+            results: CallResults::new(),
+        }
+    }
+
+    /// Joins the first suitable lobby matching `config.filters`, or creates one if
+    /// none can be joined.
+    ///
+    /// Progress is reported with [`QuickmatchSearching`] and [`QuickmatchJoining`]
+    /// events, ending in a [`QuickmatchComplete`] or [`QuickmatchFailed`] event.
+    /// Lobbies that are full or gone by the time they are joined are skipped.
+    /// Starting a quickmatch cancels any that is already running.
+    pub fn quickmatch(&mut self, config: QuickmatchConfig<'_>) -> Result<(), ShuttingDown> {
+        self.client.ensure_running()?;
+        self.cancel();
+        let id = self.next_id;
+        self.next_id += 1;
+        self.active = Some(Quickmatch {
+            id,
+            lobby_type: config.lobby_type,
+            max_members: config.max_members,
+            join_attempts: config.join_attempts,
+            candidates: VecDeque::new(),
+        });
+        self.searching = true;
+        let matchmaking = self.client.matchmaking();
+        matchmaking.set_lobby_list_filter(config.filters);
+        let results = self.results.clone();
+        matchmaking.request_lobby_list(move |result| {
+            results.push((id, QuickmatchStep::Listed(result)));
+        });
+        Ok(())
+    }
+
+    /// Stops the running quickmatch, if any. No more lobbies are joined, and a
+    /// lobby entered after cancelling is left right away.
+    pub fn cancel(&mut self) {
+        self.active = None;
+        self.searching = false;
+    }
+
+    /// Checks if a quickmatch is running.
+    pub fn is_quickmatching(&self) -> bool {
+        self.active.is_some()
+    }
+
+    /// Joins the next candidate, or creates a lobby once they run out. Returns the
+    /// lobby being joined.
+    fn advance(&mut self) -> Option<LobbyId> {
+        let quickmatch = self.active.as_mut()?;
+        let id = quickmatch.id;
+        let matchmaking = self.client.matchmaking();
+        let results = self.results.clone();
+        if let Some(lobby) = quickmatch.candidates.pop_front() {
+            matchmaking.join_lobby(lobby, move |result| {
+                results.push((id, QuickmatchStep::Joined(result)));
+            });
+            return Some(lobby);
+        }
+        matchmaking.create_lobby(
+            quickmatch.lobby_type,
+            quickmatch.max_members,
+            move |result| {
+                results.push((id, QuickmatchStep::Created(result)));
+            },
+        );
+        None
+    }
+}
+
+pub(super) fn drive_quickmatch(
+    mut matchmaking: ResMut<SteamMatchmaking>,
+    mut current: ResMut<CurrentLobby>,
+    mut searching: EventWriter<QuickmatchSearching>,
+    mut joining: EventWriter<QuickmatchJoining>,
+    mut complete: EventWriter<QuickmatchComplete>,
+    mut failed: EventWriter<QuickmatchFailed>,
+) {
+    if std::mem::take(&mut matchmaking.searching) {
+        searching.send(QuickmatchSearching);
+    }
+    for (id, step) in matchmaking.results.drain() {
+        let active = matchmaking
+            .active
+            .as_ref()
+            .is_some_and(|quickmatch| quickmatch.id == id);
+        if !active {
+            // The quickmatch was cancelled, so leave any lobby it still entered.
+            if let QuickmatchStep::Joined(Ok(lobby)) | QuickmatchStep::Created(Ok(lobby)) = step {
+                matchmaking.client.matchmaking().leave_lobby(lobby);
+            }
+            continue;
+        }
+        let (lobby, created) = match step {
+            QuickmatchStep::Listed(Ok(lobbies)) => {
+                if let Some(quickmatch) = &mut matchmaking.active {
+                    let attempts = quickmatch.join_attempts;
+                    quickmatch.candidates = lobbies.into_iter().take(attempts).collect();
+                }
+                if let Some(lobby) = matchmaking.advance() {
+                    joining.send(QuickmatchJoining(lobby));
+                }
+                continue;
+            }
+            QuickmatchStep::Joined(Err(())) => {
+                if let Some(lobby) = matchmaking.advance() {
+                    joining.send(QuickmatchJoining(lobby));
+                }
+                continue;
+            }
+            QuickmatchStep::Listed(Err(err)) => {
+                matchmaking.active = None;
+                failed.send(QuickmatchFailed(QuickmatchError::SearchFailed(err)));
+                continue;
+            }
+            QuickmatchStep::Created(Err(err)) => {
+                matchmaking.active = None;
+                failed.send(QuickmatchFailed(QuickmatchError::CreateFailed(err)));
+                continue;
+            }
+            QuickmatchStep::Joined(Ok(lobby)) => (lobby, false),
+            QuickmatchStep::Created(Ok(lobby)) => (lobby, true),
+        };
+        matchmaking.active = None;
+        current.enter_unchecked(lobby);
+        complete.send(QuickmatchComplete { lobby, created });
+    }
+}
@@ -0,0 +1,136 @@
+use std::time::{Duration, Instant};
+
+use bevy_app::{App, First};
+use bevy_ecs::{
+    event::{Event, EventReader, EventWriter},
+    schedule::IntoSystemConfigs,
+    system::{ResMut, Resource},
+};
+use bevy_utils::HashMap;
+
+use crate::{shutdown::steam_running, SteamworksEvent, SteamworksSystem};
+
+/// Sent when the user authorizes or cancels an order started with
+/// [`SteamMicroTxn::begin`].
+#[derive(Event, Debug, Clone)]
+pub struct PurchaseResolved {
+    /// The ID of the order.
+    pub order_id: u64,
+    /// The item descriptor passed to [`SteamMicroTxn::begin`].
+    pub item: String,
+    /// Whether the user authorized the purchase.
+    pub authorized: bool,
+}
+
+/// Sent when an order started with [`SteamMicroTxn::begin`] gets no response
+/// within [`SteamMicroTxn::timeout`].
+#[derive(Event, Debug, Clone)]
+pub struct PurchaseTimedOut {
+    /// The ID of the order.
+    pub order_id: u64,
+    /// The item descriptor passed to [`SteamMicroTxn::begin`].
+    pub item: String,
+}
+
+struct PendingOrder {
+    item: String,
+    started: Instant,
+}
+
+/// Tracks in-game purchases between starting an order and the user authorizing
+/// it in the Steam overlay.
+///
+/// Orders are started through the Steam Web API by the game's backend, after
+/// which the order ID is recorded here with [`SteamMicroTxn::begin`]. The
+/// `MicroTxnAuthorizationResponse` from Steam is then matched to the order and
+/// reported as a [`PurchaseResolved`] event carrying the item, so the system
+/// granting items needs no bookkeeping of its own.
+#[derive(Resource)]
+pub struct SteamMicroTxn {
+    /// How long to wait for the user to respond before sending
+    /// [`PurchaseTimedOut`]. Defaults to 10 minutes.
+    pub timeout: Duration,
+    pending: HashMap<u64, PendingOrder>,
+}
+
+impl Default for SteamMicroTxn {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(10 * 60),
+            pending: HashMap::default(),
+        }
+    }
+}
+
+impl SteamMicroTxn {
+    /// Records an order the game's backend has started, so the user's response
+    /// can be matched to it.
+    pub fn begin(&mut self, order_id: u64, item: impl Into<String>) {
+        self.pending.insert(
+            order_id,
+            PendingOrder {
+                item: item.into(),
+                started: Instant::now(),
+            },
+        );
+    }
+
+    /// Checks if an order is still waiting for the user to respond.
+    pub fn is_pending(&self, order_id: u64) -> bool {
+        self.pending.contains_key(&order_id)
+    }
+
+    /// Stops tracking an order without sending any event. Returns the item
+    /// descriptor if the order was pending.
+    pub fn forget(&mut self, order_id: u64) -> Option<String> {
+        self.pending.remove(&order_id).map(|order| order.item)
+    }
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<SteamMicroTxn>()
+        .add_event::<PurchaseResolved>()
+        .add_event::<PurchaseTimedOut>()
+        .add_systems(
+            First,
+            resolve_purchases
+                .after(SteamworksSystem::RunCallbacks)
+                .run_if(steam_running),
+        );
+}
+
+fn resolve_purchases(
+    mut microtxn: ResMut<SteamMicroTxn>,
+    mut events: EventReader<SteamworksEvent>,
+    mut resolved: EventWriter<PurchaseResolved>,
+    mut timed_out: EventWriter<PurchaseTimedOut>,
+) {
+    for event in events.read() {
+        let SteamworksEvent::MicroTxnAuthorizationResponse(response) = event else {
+            continue;
+        };
+        let Some(order) = microtxn.pending.remove(&response.order_id) else {
+            continue;
+        };
+        resolved.send(PurchaseResolved {
+            order_id: response.order_id,
+            item: order.item,
+            authorized: response.authorized,
+        });
+    }
+
+    if microtxn.pending.is_empty() {
+        return;
+    }
+    let timeout = microtxn.timeout;
+    microtxn.pending.retain(|&order_id, order| {
+        if order.started.elapsed() < timeout {
+            return true;
+        }
+        timed_out.send(PurchaseTimedOut {
+            order_id,
+            item: std::mem::take(&mut order.item),
+        });
+        false
+    });
+}
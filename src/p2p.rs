@@ -0,0 +1,228 @@
+use std::time::{Duration, Instant};
+
+use bevy_app::{App, First};
+use bevy_ecs::{
+    event::{Event, EventReader, EventWriter},
+    schedule::IntoSystemConfigs,
+    system::{ResMut, Resource},
+};
+use bevy_utils::HashMap;
+use steamworks::{sys, SteamId};
+
+use crate::{shutdown::steam_running, Client, SteamworksEvent, SteamworksSystem};
+
+/// An error reported for a legacy P2P session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum P2PSessionError {
+    /// The local user doesn't own the app that is running.
+    NoRightsToApp,
+    /// The remote user didn't respond, or the connection couldn't be negotiated.
+    Timeout,
+    /// An error not known to this crate, carrying the raw `EP2PSessionError` value.
+    Other(u8),
+}
+
+impl P2PSessionError {
+    fn from_raw(error: u8) -> Option<Self> {
+        match error {
+            0 => None,
+            2 => Some(Self::NoRightsToApp),
+            4 => Some(Self::Timeout),
+            other => Some(Self::Other(other)),
+        }
+    }
+}
+
+/// The state of a legacy P2P session with a peer, as tracked by [`P2PSessions`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct P2PSessionState {
+    /// Whether the session is open.
+    pub active: bool,
+    /// Whether the session is still being negotiated.
+    pub connecting: bool,
+    /// Whether traffic is being relayed through Steam rather than sent directly.
+    pub relayed: bool,
+    /// How many bytes are waiting to be sent to the peer.
+    pub bytes_queued: u32,
+    /// How many packets are waiting to be sent to the peer.
+    pub packets_queued: u32,
+    /// The last error reported for the session, if any.
+    pub last_error: Option<P2PSessionError>,
+}
+
+/// Why a [`P2PSessionDegraded`] event was sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum P2PDegradeReason {
+    /// Traffic to the peer started being relayed through Steam, which usually
+    /// means higher latency.
+    Relayed,
+    /// The session reported an error.
+    Error(P2PSessionError),
+}
+
+/// Sent when a session tracked by [`P2PSessions`] starts being relayed or
+/// reports an error.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct P2PSessionDegraded {
+    /// The peer the session is with.
+    pub remote: SteamId,
+    /// What changed.
+    pub reason: P2PDegradeReason,
+}
+
+/// The state of the legacy P2P sessions with every tracked peer.
+///
+/// Peers are tracked once they request a session or fail to connect, and can be
+/// tracked by hand with [`P2PSessions::track`]. Their state is refreshed every
+/// [`P2PSessions::refresh_interval`].
+#[derive(Resource)]
+pub struct P2PSessions {
+    client: Client,
+    peers: HashMap<SteamId, P2PSessionState>,
+    /// How often the state of every tracked session is queried from Steam.
+    pub refresh_interval: Duration,
+    last_refresh: Option<Instant>,
+    /// Sessions that degraded since the last frame.
+    degraded: Vec<P2PSessionDegraded>,
+}
+
+impl P2PSessions {
+    fn new(client: &Client) -> Self {
+        Self {
+            client: client.clone(),
+            peers: HashMap::default(),
+            refresh_interval: Duration::from_secs(1),
+            last_refresh: None,
+            degraded: Vec::new(),
+        }
+    }
+
+    /// Starts tracking the session with a peer, such as after sending them the
+    /// first packet. Does nothing once Steam is shutting down.
+    pub fn track(&mut self, remote: SteamId) {
+        if self.client.is_shutting_down() || self.peers.contains_key(&remote) {
+            return;
+        }
+        let state = self.query(remote);
+        self.peers.insert(remote, state);
+    }
+
+    /// Stops tracking the session with a peer. Returns `false` if the peer wasn't
+    /// tracked.
+    pub fn untrack(&mut self, remote: SteamId) -> bool {
+        self.peers.remove(&remote).is_some()
+    }
+
+    /// Gets the last known state of the session with a peer.
+    pub fn get(&self, remote: SteamId) -> Option<&P2PSessionState> {
+        self.peers.get(&remote)
+    }
+
+    /// Iterates over every tracked peer and the last known state of their session.
+    pub fn iter(&self) -> impl Iterator<Item = (SteamId, &P2PSessionState)> {
+        self.peers.iter().map(|(remote, state)| (*remote, state))
+    }
+
+    /// Queries the state of every tracked session from Steam again. Keeps the last
+    /// states once Steam is shutting down.
+    pub fn refresh(&mut self) {
+        if self.client.is_shutting_down() {
+            return;
+        }
+        self.last_refresh = Some(Instant::now());
+        let remotes: Vec<_> = self.peers.keys().copied().collect();
+        for remote in remotes {
+            let state = self.query(remote);
+            self.update(remote, state);
+        }
+    }
+
+    fn query(&self, remote: SteamId) -> P2PSessionState {
+        // steamworks does not wrap GetP2PSessionState, so it is called directly.
+        // SAFETY: The Steam API is initialized for as long as a `Client` exists, and
+        // `P2PSessionState_t` is plain data.
+        let (found, raw) = unsafe {
+            let mut raw: sys::P2PSessionState_t = std::mem::zeroed();
+            let found = sys::SteamAPI_ISteamNetworking_GetP2PSessionState(
+                sys::SteamAPI_SteamNetworking_v006(),
+                remote.raw(),
+                &mut raw,
+            );
+            (found, raw)
+        };
+        // Steam forgets closed sessions, so keep the last error around.
+        let last_error = self.peers.get(&remote).and_then(|state| state.last_error);
+        if !found {
+            return P2PSessionState {
+                last_error,
+                ..Default::default()
+            };
+        }
+        P2PSessionState {
+            active: raw.m_bConnectionActive != 0,
+            connecting: raw.m_bConnecting != 0,
+            relayed: raw.m_bUsingRelay != 0,
+            bytes_queued: raw.m_nBytesQueuedForSend.max(0) as u32,
+            packets_queued: raw.m_nPacketsQueuedForSend.max(0) as u32,
+            last_error: P2PSessionError::from_raw(raw.m_eP2PSessionError).or(last_error),
+        }
+    }
+
+    fn update(&mut self, remote: SteamId, state: P2PSessionState) {
+        let previous = self.peers.insert(remote, state).unwrap_or_default();
+        if state.relayed && !previous.relayed {
+            self.degraded.push(P2PSessionDegraded {
+                remote,
+                reason: P2PDegradeReason::Relayed,
+            });
+        }
+        if let Some(error) = state.last_error {
+            if previous.last_error != Some(error) {
+                self.degraded.push(P2PSessionDegraded {
+                    remote,
+                    reason: P2PDegradeReason::Error(error),
+                });
+            }
+        }
+    }
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    let client = app.world().resource::<Client>();
+    let sessions = P2PSessions::new(client);
+    app.insert_resource(sessions)
+        .add_event::<P2PSessionDegraded>()
+        .add_systems(
+            First,
+            refresh_p2p_sessions
+                .after(SteamworksSystem::RunCallbacks)
+                .run_if(steam_running),
+        );
+}
+
+fn refresh_p2p_sessions(
+    mut sessions: ResMut<P2PSessions>,
+    mut events: EventReader<SteamworksEvent>,
+    mut degraded: EventWriter<P2PSessionDegraded>,
+) {
+    for event in events.read() {
+        match event {
+            SteamworksEvent::P2PSessionRequest(request) => sessions.track(request.remote),
+            SteamworksEvent::P2PSessionConnectFail(fail) => {
+                let mut state = sessions.get(fail.remote).copied().unwrap_or_default();
+                state.active = false;
+                state.connecting = false;
+                state.last_error = P2PSessionError::from_raw(fail.error);
+                sessions.update(fail.remote, state);
+            }
+            _ => {}
+        }
+    }
+    let due = sessions
+        .last_refresh
+        .map_or(true, |last| last.elapsed() >= sessions.refresh_interval);
+    if due {
+        sessions.refresh();
+    }
+    degraded.send_batch(std::mem::take(&mut sessions.degraded));
+}
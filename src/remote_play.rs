@@ -0,0 +1,198 @@
+use bevy_app::{App, First};
+use bevy_ecs::{
+    event::{Event, EventReader, EventWriter},
+    schedule::IntoSystemConfigs,
+    system::{ResMut, Resource},
+};
+use steamworks::{sys, RemotePlaySessionId, SteamDeviceFormFactor, SteamId};
+
+use crate::{shutdown::steam_running, Client, SteamworksEvent, SteamworksSystem};
+
+/// Sent when a Remote Play session connects, and again once its streaming
+/// resolution is known if it wasn't yet, so per-guest settings such as UI scale
+/// can be applied.
+#[derive(Event, Debug, Clone)]
+pub struct RemotePlayGuestProfile {
+    /// The ID of the session.
+    pub session: RemotePlaySessionId,
+    /// The kind of device the guest is streaming to.
+    pub form_factor: Option<SteamDeviceFormFactor>,
+    /// The guest's streaming resolution, or `None` if it hasn't been negotiated yet.
+    pub resolution: Option<(u32, u32)>,
+}
+
+/// An active Remote Play session, as listed by [`RemotePlaySessions`].
+#[derive(Debug, Clone)]
+pub struct RemotePlaySessionInfo {
+    /// The ID of the session.
+    pub id: RemotePlaySessionId,
+    /// The user streaming the game in this session.
+    pub user: SteamId,
+    /// The kind of device the guest is streaming to.
+    pub form_factor: Option<SteamDeviceFormFactor>,
+    /// The guest's streaming resolution, or `None` if it hasn't been negotiated
+    /// yet. Updated once it is known.
+    pub resolution: Option<(u32, u32)>,
+}
+
+/// The active Remote Play sessions, including Remote Play Together guests.
+///
+/// Kept current as sessions connect and disconnect, with a
+/// [`RemotePlayGuestProfile`] event sent for each guest. Empty when no one is
+/// streaming the game.
+#[derive(Resource)]
+pub struct RemotePlaySessions {
+    client: Client,
+    sessions: Vec<RemotePlaySessionInfo>,
+    /// Sessions whose profile changed since the last frame.
+    profiles_changed: Vec<RemotePlaySessionId>,
+}
+
+impl RemotePlaySessions {
+    fn new(client: &Client) -> Self {
+        let mut sessions = Self {
+            client: client.clone(),
+            sessions: Vec::new(),
+            profiles_changed: Vec::new(),
+        };
+        sessions.refresh();
+        sessions
+    }
+
+    /// Gets every active session.
+    pub fn iter(&self) -> impl Iterator<Item = &RemotePlaySessionInfo> {
+        self.sessions.iter()
+    }
+
+    /// Gets an active session by ID.
+    pub fn get(&self, id: RemotePlaySessionId) -> Option<&RemotePlaySessionInfo> {
+        self.sessions.iter().find(|session| session.id == id)
+    }
+
+    /// Gets the number of active sessions.
+    pub fn len(&self) -> usize {
+        self.sessions.len()
+    }
+
+    /// Checks if there are no active sessions.
+    pub fn is_empty(&self) -> bool {
+        self.sessions.is_empty()
+    }
+
+    /// Invites a friend to join the game through Remote Play Together. Returns
+    /// `false` if the invite could not be sent, including once Steam is shutting
+    /// down.
+    pub fn invite_remote_play_together(&self, steam_id: SteamId) -> bool {
+        !self.client.is_shutting_down() && self.client.remote_play().invite(steam_id)
+    }
+
+    /// Starts Remote Play Together and opens the overlay for inviting friends.
+    /// Returns `false` if Remote Play Together could not be started, including
+    /// once Steam is shutting down.
+    pub fn open_remote_play_invite_overlay(&self) -> bool {
+        if self.client.is_shutting_down() {
+            return false;
+        }
+        // steamworks does not wrap BStartRemotePlayTogether, so it is called directly.
+        // SAFETY: The Steam API is initialized for as long as a `Client` exists.
+        unsafe {
+            sys::SteamAPI_ISteamRemotePlay_BStartRemotePlayTogether(
+                sys::SteamAPI_SteamRemotePlay_v002(),
+                true,
+            )
+        }
+    }
+
+    /// Lists the active sessions from Steam again. Keeps the last list once Steam
+    /// is shutting down.
+    pub fn refresh(&mut self) {
+        if self.client.is_shutting_down() {
+            return;
+        }
+        // steamworks does not expose session IDs when listing sessions, so they are
+        // read directly.
+        // SAFETY: The Steam API is initialized for as long as a `Client` exists.
+        let ids = unsafe {
+            let remote_play = sys::SteamAPI_SteamRemotePlay_v002();
+            let count = sys::SteamAPI_ISteamRemotePlay_GetSessionCount(remote_play);
+            (0..count as i32)
+                .map(|index| sys::SteamAPI_ISteamRemotePlay_GetSessionID(remote_play, index))
+                .collect::<Vec<_>>()
+        };
+        self.sessions.clear();
+        for id in ids {
+            // A session that ended while listing has an ID of 0.
+            if id != 0 {
+                self.add(RemotePlaySessionId::from_raw(id));
+            }
+        }
+    }
+
+    fn add(&mut self, id: RemotePlaySessionId) {
+        if self.get(id).is_some() {
+            return;
+        }
+        let session = self.client.remote_play().session(id);
+        self.sessions.push(RemotePlaySessionInfo {
+            id,
+            user: session.user(),
+            form_factor: session.client_form_factor(),
+            resolution: session.client_resolution(),
+        });
+        self.profiles_changed.push(id);
+    }
+
+    /// Checks again for the resolution of sessions that haven't reported one.
+    fn poll_resolutions(&mut self) {
+        let remote_play = self.client.remote_play();
+        for session in &mut self.sessions {
+            if session.resolution.is_some() {
+                continue;
+            }
+            session.resolution = remote_play.session(session.id).client_resolution();
+            if session.resolution.is_some() {
+                self.profiles_changed.push(session.id);
+            }
+        }
+    }
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    let client = app.world().resource::<Client>();
+    let sessions = RemotePlaySessions::new(client);
+    app.insert_resource(sessions)
+        .add_event::<RemotePlayGuestProfile>()
+        .add_systems(
+            First,
+            track_remote_play_sessions
+                .after(SteamworksSystem::RunCallbacks)
+                .run_if(steam_running),
+        );
+}
+
+fn track_remote_play_sessions(
+    mut sessions: ResMut<RemotePlaySessions>,
+    mut events: EventReader<SteamworksEvent>,
+    mut profiles: EventWriter<RemotePlayGuestProfile>,
+) {
+    for event in events.read() {
+        match event {
+            SteamworksEvent::RemotePlayConnected(connected) => sessions.add(connected.session),
+            SteamworksEvent::RemotePlayDisconnected(disconnected) => sessions
+                .sessions
+                .retain(|session| session.id != disconnected.session),
+            _ => {}
+        }
+    }
+    sessions.poll_resolutions();
+    for id in std::mem::take(&mut sessions.profiles_changed) {
+        let Some(session) = sessions.get(id) else {
+            continue;
+        };
+        profiles.send(RemotePlayGuestProfile {
+            session: id,
+            form_factor: session.form_factor,
+            resolution: session.resolution,
+        });
+    }
+}
@@ -0,0 +1,112 @@
+use std::fmt;
+
+use bevy_app::App;
+use bevy_ecs::system::Resource;
+use steamworks::UserRestriction;
+
+use crate::Client;
+
+/// An error for an action the user's account is restricted from, such as by
+/// Steam Families parental controls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UserRestrictedError {
+    /// The restriction that prevented the action.
+    pub restriction: UserRestriction,
+}
+
+impl fmt::Display for UserRestrictedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the user is restricted from this action ({:?})",
+            self.restriction
+        )
+    }
+}
+
+impl std::error::Error for UserRestrictedError {}
+
+/// The chat and content restrictions on the user's account, such as those set
+/// through Steam Families parental controls or on cybercafé accounts.
+///
+/// The restrictions are read when the plugin is built. This crate does not
+/// enforce them, so it is up to the game to check them before sending chat or
+/// showing user-generated content, such as Workshop items, and to hide the UI for
+/// those features when the user is restricted. The `check_` methods return a
+/// [`UserRestrictedError`] for code that reports restrictions as errors.
+#[derive(Resource, Clone)]
+pub struct SteamUserRestrictions {
+    client: Client,
+    restrictions: UserRestriction,
+}
+
+impl SteamUserRestrictions {
+    fn new(client: &Client) -> Self {
+        Self {
+            client: client.clone(),
+            restrictions: client.friends().get_user_restrictions(),
+        }
+    }
+
+    /// Gets every restriction on the user's account.
+    pub fn get(&self) -> UserRestriction {
+        self.restrictions
+    }
+
+    /// Checks if the user may send and receive text chat.
+    pub fn chat_allowed(&self) -> bool {
+        self.check_chat().is_ok()
+    }
+
+    /// Checks if the user may use voice chat.
+    pub fn voice_chat_allowed(&self) -> bool {
+        self.check(UserRestriction::ANY_CHAT | UserRestriction::VOICE_CHAT)
+            .is_ok()
+    }
+
+    /// Checks if the user may view user-generated content, such as Workshop items.
+    pub fn user_content_allowed(&self) -> bool {
+        self.check_user_content().is_ok()
+    }
+
+    /// Checks if the user may send and receive game invites.
+    pub fn game_invites_allowed(&self) -> bool {
+        self.check(UserRestriction::GAME_INVITES).is_ok()
+    }
+
+    /// Like [`SteamUserRestrictions::chat_allowed`], but returns the restriction as
+    /// an error.
+    pub fn check_chat(&self) -> Result<(), UserRestrictedError> {
+        self.check(UserRestriction::ANY_CHAT | UserRestriction::GROUP_CHAT)
+    }
+
+    /// Like [`SteamUserRestrictions::user_content_allowed`], but returns the
+    /// restriction as an error.
+    pub fn check_user_content(&self) -> Result<(), UserRestrictedError> {
+        self.check(UserRestriction::RATING)
+    }
+
+    fn check(&self, restriction: UserRestriction) -> Result<(), UserRestrictedError> {
+        let restriction = self.restrictions & restriction;
+        if restriction.is_empty() {
+            Ok(())
+        } else {
+            Err(UserRestrictedError { restriction })
+        }
+    }
+
+    /// Reads the restrictions from Steam again. Keeps the last restrictions once
+    /// Steam is shutting down.
+    pub fn refresh(&mut self) {
+        if self.client.is_shutting_down() {
+            return;
+        }
+        self.restrictions = self.client.friends().get_user_restrictions();
+    }
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    let client = app.world().resource::<Client>();
+    let restrictions = SteamUserRestrictions::new(client);
+    app.insert_resource(restrictions);
+}
@@ -0,0 +1,155 @@
+use bevy_app::{App, First};
+use bevy_ecs::{
+    schedule::IntoSystemConfigs,
+    system::{Res, ResMut, Resource},
+};
+use bevy_utils::HashMap;
+
+use crate::{lobby, shutdown::steam_running, Client, CurrentLobby, ShuttingDown};
+
+/// The rich presence keys published while in a lobby when
+/// [`RichPresence::lobby_presence`] is set.
+///
+/// `steam_player_group` and `steam_player_group_size` are always published, so
+/// friends see the size of the party and can join through the friends list. In
+/// both templates, `{lobby}` is replaced with the lobby's ID and `{members}` with
+/// its member count.
+#[derive(Debug, Clone, Default)]
+pub struct LobbyPresence {
+    /// The template for the `connect` key, the command line friends join with.
+    pub connect: Option<String>,
+    /// The template for the `steam_display` key, a localization token from the
+    /// game's rich presence file.
+    pub steam_display: Option<String>,
+}
+
+impl LobbyPresence {
+    fn keys(&self, lobby: &CurrentLobby) -> HashMap<String, String> {
+        let Some(id) = lobby.id() else {
+            return HashMap::default();
+        };
+        let id = id.raw().to_string();
+        let members = lobby.members().len().to_string();
+        let fill = |template: &str| {
+            template
+                .replace("{lobby}", &id)
+                .replace("{members}", &members)
+        };
+        let mut keys = HashMap::default();
+        if let Some(connect) = &self.connect {
+            keys.insert("connect".to_owned(), fill(connect));
+        }
+        if let Some(steam_display) = &self.steam_display {
+            keys.insert("steam_display".to_owned(), fill(steam_display));
+        }
+        keys.insert("steam_player_group".to_owned(), id.clone());
+        keys.insert("steam_player_group_size".to_owned(), members);
+        keys
+    }
+}
+
+/// Sets the local user's rich presence, shown to friends in their friends list.
+///
+/// Keys derived from the [`CurrentLobby`] are published automatically when
+/// [`RichPresence::lobby_presence`] is set, and cleared when leaving the lobby.
+/// Keys set with [`RichPresence::set`] always win over the automatic values.
+#[derive(Resource)]
+pub struct RichPresence {
+    client: Client,
+    /// Keys set by hand.
+    manual: HashMap<String, String>,
+    /// Keys last published from the current lobby.
+    automatic: HashMap<String, String>,
+    /// Which keys to publish from the current lobby. `None` disables publishing
+    /// them.
+    pub lobby_presence: Option<LobbyPresence>,
+}
+
+impl RichPresence {
+    fn new(client: &Client) -> Self {
+        Self {
+            client: client.clone(),
+            manual: HashMap::default(),
+            automatic: HashMap::default(),
+            lobby_presence: None,
+        }
+    }
+
+    /// Sets a rich presence key, overriding any value published automatically.
+    /// Returns `false` if Steam rejected the key or value, or is shutting down.
+    pub fn set(&mut self, key: &str, value: &str) -> bool {
+        if self.client.is_shutting_down() {
+            return false;
+        }
+        self.manual.insert(key.to_owned(), value.to_owned());
+        self.publish(key, Some(value))
+    }
+
+    /// Clears a key set with [`RichPresence::set`], falling back to the value
+    /// published automatically, if any.
+    pub fn clear(&mut self, key: &str) -> Result<(), ShuttingDown> {
+        self.client.ensure_running()?;
+        if self.manual.remove(key).is_none() {
+            return Ok(());
+        }
+        let value = self.automatic.get(key).cloned();
+        self.publish(key, value.as_deref());
+        Ok(())
+    }
+
+    /// Clears every rich presence key, including those published automatically.
+    ///
+    /// Automatic keys are published again on the next change to the lobby.
+    pub fn clear_all(&mut self) -> Result<(), ShuttingDown> {
+        self.client.ensure_running()?;
+        self.manual.clear();
+        self.automatic.clear();
+        self.client.friends().clear_rich_presence();
+        Ok(())
+    }
+
+    /// Gets the value of a key as last set by this resource.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.manual
+            .get(key)
+            .or_else(|| self.automatic.get(key))
+            .map(String::as_str)
+    }
+
+    fn publish(&self, key: &str, value: Option<&str>) -> bool {
+        self.client.friends().set_rich_presence(key, value)
+    }
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    let client = app.world().resource::<Client>();
+    let rich_presence = RichPresence::new(client);
+    app.insert_resource(rich_presence).add_systems(
+        First,
+        publish_lobby_presence
+            .after(lobby::track_current_lobby)
+            .run_if(steam_running),
+    );
+}
+
+fn publish_lobby_presence(mut rich_presence: ResMut<RichPresence>, lobby: Res<CurrentLobby>) {
+    let keys = rich_presence
+        .lobby_presence
+        .as_ref()
+        .map(|presence| presence.keys(&lobby))
+        .unwrap_or_default();
+    if keys == rich_presence.automatic {
+        return;
+    }
+    let previous = std::mem::replace(&mut rich_presence.automatic, keys);
+    for key in previous.keys() {
+        if !rich_presence.automatic.contains_key(key) && !rich_presence.manual.contains_key(key) {
+            rich_presence.publish(key, None);
+        }
+    }
+    for (key, value) in &rich_presence.automatic {
+        if previous.get(key) != Some(value) && !rich_presence.manual.contains_key(key) {
+            rich_presence.publish(key, Some(value));
+        }
+    }
+}
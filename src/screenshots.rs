@@ -0,0 +1,271 @@
+#[cfg(feature = "bevy_render")]
+mod write;
+
+#[cfg(feature = "bevy_render")]
+pub use write::*;
+
+use std::{
+    collections::VecDeque,
+    ffi::{c_void, CString},
+};
+
+use bevy_app::{App, First};
+use bevy_ecs::{
+    event::{Event, EventWriter},
+    schedule::IntoSystemConfigs,
+    system::{ResMut, Resource},
+};
+use steamworks::{sys, Callback, SteamError, SteamId};
+
+use crate::{
+    call_result::CallResults,
+    shutdown::{steam_running, RegisteredCallback},
+    Client, ShuttingDown, SteamworksSystem,
+};
+
+/// A handle to a screenshot in the user's Steam library.
+pub type ScreenshotHandle = sys::ScreenshotHandle;
+
+/// Sent when a screenshot has been written to the user's Steam library, whether
+/// taken through [`SteamScreenshots`] or by the user pressing the screenshot key.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ScreenshotReady {
+    /// The handle of the screenshot, for tagging it.
+    pub handle: ScreenshotHandle,
+    /// Whether the screenshot was written successfully.
+    pub result: Result<(), SteamError>,
+}
+
+/// Sent when the user presses the screenshot key while screenshots are hooked with
+/// [`SteamScreenshots::hook`], so the game can capture and write its own.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ScreenshotRequested;
+
+/// `ScreenshotRequested_t`, which steamworks does not wrap.
+struct ScreenshotRequestedCallback;
+
+unsafe impl Callback for ScreenshotRequestedCallback {
+    const ID: i32 = 2302;
+    const SIZE: i32 = std::mem::size_of::<sys::ScreenshotRequested_t>() as i32;
+
+    unsafe fn from_raw(_: *mut c_void) -> Self {
+        Self
+    }
+}
+
+/// `ScreenshotReady_t`, which steamworks does not wrap.
+struct ScreenshotReadyCallback(ScreenshotHandle, Result<(), SteamError>);
+
+unsafe impl Callback for ScreenshotReadyCallback {
+    const ID: i32 = 2301;
+    const SIZE: i32 = std::mem::size_of::<sys::ScreenshotReady_t>() as i32;
+
+    unsafe fn from_raw(raw: *mut c_void) -> Self {
+        let val = &mut *(raw as *mut sys::ScreenshotReady_t);
+        let result = match val.m_eResult {
+            sys::EResult::k_EResultOK => Ok(()),
+            err => Err(err.into()),
+        };
+        Self(val.m_hLocal, result)
+    }
+}
+
+struct ScreenshotTags {
+    users: Vec<SteamId>,
+    location: String,
+}
+
+/// Takes screenshots into the user's Steam library and tags them.
+#[derive(Resource)]
+pub struct SteamScreenshots {
+    client: Client,
+    pending_tags: VecDeque<ScreenshotTags>,
+    ready: CallResults<(ScreenshotHandle, Result<(), SteamError>)>,
+    _ready_callback: RegisteredCallback,
+    requested: CallResults<()>,
+    _requested_callback: RegisteredCallback,
+    #[cfg(feature = "bevy_render")]
+    writes: write::ScreenshotWrites,
+}
+
+impl SteamScreenshots {
+    fn new(client: &Client) -> Self {
+        let ready = CallResults::new();
+        let ready_in = ready.clone();
+        let ready_callback = RegisteredCallback::new(client.register_callback(
+            move |ScreenshotReadyCallback(handle, result)| {
+                ready_in.push((handle, result));
+            },
+        ));
+        let requested = CallResults::new();
+        let requested_in = requested.clone();
+        let requested_callback = RegisteredCallback::new(client.register_callback(
+            move |ScreenshotRequestedCallback| {
+                requested_in.push(());
+            },
+        ));
+        Self {
+            client: client.clone(),
+            pending_tags: VecDeque::new(),
+            ready,
+            _ready_callback: ready_callback,
+            requested,
+            _requested_callback: requested_callback,
+            #[cfg(feature = "bevy_render")]
+            writes: write::ScreenshotWrites::new(client),
+        }
+    }
+
+    /// Takes a screenshot, as if the user pressed the screenshot key.
+    ///
+    /// A [`ScreenshotReady`] event is sent once it has been written.
+    pub fn trigger(&self) -> Result<(), ShuttingDown> {
+        self.client.ensure_running()?;
+        // steamworks does not wrap ISteamScreenshots, so it is called directly.
+        // SAFETY: The Steam API is initialized for as long as a `Client` exists.
+        unsafe {
+            sys::SteamAPI_ISteamScreenshots_TriggerScreenshot(sys::SteamAPI_SteamScreenshots_v003());
+        }
+        Ok(())
+    }
+
+    /// Sets whether the game takes its own screenshots when the user presses the
+    /// screenshot key.
+    ///
+    /// While hooked, Steam sends a [`ScreenshotRequested`] event instead of
+    /// capturing the screen, and the game is expected to write a screenshot of its
+    /// own, such as with [`SteamScreenshots::write_image`] when the `bevy_render`
+    /// feature is enabled.
+    pub fn hook(&self, hook: bool) -> Result<(), ShuttingDown> {
+        self.client.ensure_running()?;
+        // steamworks does not wrap ISteamScreenshots, so it is called directly.
+        // SAFETY: The Steam API is initialized for as long as a `Client` exists.
+        unsafe {
+            sys::SteamAPI_ISteamScreenshots_HookScreenshots(
+                sys::SteamAPI_SteamScreenshots_v003(),
+                hook,
+            );
+        }
+        Ok(())
+    }
+
+    /// Checks if screenshots are hooked with [`SteamScreenshots::hook`]. Returns
+    /// `false` once Steam is shutting down.
+    pub fn is_hooked(&self) -> bool {
+        if self.client.is_shutting_down() {
+            return false;
+        }
+        // steamworks does not wrap ISteamScreenshots, so it is called directly.
+        // SAFETY: The Steam API is initialized for as long as a `Client` exists.
+        unsafe {
+            sys::SteamAPI_ISteamScreenshots_IsScreenshotsHooked(
+                sys::SteamAPI_SteamScreenshots_v003(),
+            )
+        }
+    }
+
+    /// Takes a screenshot and tags it with the users in it and where it was taken
+    /// once it is ready.
+    ///
+    /// The tags are applied to the next screenshot Steam reports as ready, so a
+    /// screenshot the user takes at the same moment may receive them instead.
+    pub fn trigger_tagged(
+        &mut self,
+        users: &[SteamId],
+        location: &str,
+    ) -> Result<(), ShuttingDown> {
+        self.trigger()?;
+        self.pending_tags.push_back(ScreenshotTags {
+            users: users.to_vec(),
+            location: location.to_owned(),
+        });
+        Ok(())
+    }
+
+    /// Tags a screenshot with a user who is visible in it. Returns `false` if the
+    /// tag could not be added, including once Steam is shutting down.
+    pub fn tag_user(&self, handle: ScreenshotHandle, steam_id: SteamId) -> bool {
+        if self.client.is_shutting_down() {
+            return false;
+        }
+        // steamworks does not wrap ISteamScreenshots, so it is called directly.
+        // SAFETY: The Steam API is initialized for as long as a `Client` exists.
+        unsafe {
+            sys::SteamAPI_ISteamScreenshots_TagUser(
+                sys::SteamAPI_SteamScreenshots_v003(),
+                handle,
+                steam_id.raw(),
+            )
+        }
+    }
+
+    /// Sets where a screenshot was taken, such as the name of a map. Returns
+    /// `false` if the location could not be set, including once Steam is shutting
+    /// down.
+    pub fn set_location(&self, handle: ScreenshotHandle, location: &str) -> bool {
+        if self.client.is_shutting_down() {
+            return false;
+        }
+        let Ok(location) = CString::new(location) else {
+            return false;
+        };
+        // steamworks does not wrap ISteamScreenshots, so it is called directly.
+        // SAFETY: The Steam API is initialized for as long as a `Client` exists, and
+        // the string outlives the call.
+        unsafe {
+            sys::SteamAPI_ISteamScreenshots_SetLocation(
+                sys::SteamAPI_SteamScreenshots_v003(),
+                handle,
+                location.as_ptr(),
+            )
+        }
+    }
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    let client = app.world().resource::<Client>();
+    let screenshots = SteamScreenshots::new(client);
+    app.insert_resource(screenshots)
+        .add_event::<ScreenshotReady>()
+        .add_event::<ScreenshotRequested>()
+        .add_systems(
+            First,
+            flush_screenshots
+                .after(SteamworksSystem::RunCallbacks)
+                .run_if(steam_running),
+        );
+
+    #[cfg(feature = "bevy_render")]
+    app.add_event::<ScreenshotWritten>();
+}
+
+fn flush_screenshots(
+    mut screenshots: ResMut<SteamScreenshots>,
+    mut ready: EventWriter<ScreenshotReady>,
+    mut requested: EventWriter<ScreenshotRequested>,
+    #[cfg(feature = "bevy_render")] mut written: EventWriter<ScreenshotWritten>,
+) {
+    for _ in screenshots.requested.drain() {
+        requested.send(ScreenshotRequested);
+    }
+    #[cfg(feature = "bevy_render")]
+    screenshots.writes.flush_failed(&mut written);
+    for (handle, result) in screenshots.ready.drain() {
+        #[cfg(feature = "bevy_render")]
+        if screenshots.writes.on_ready(handle, result, &mut written) {
+            ready.send(ScreenshotReady { handle, result });
+            continue;
+        }
+        if let Some(tags) = screenshots.pending_tags.pop_front() {
+            if result.is_ok() {
+                for user in tags.users {
+                    screenshots.tag_user(handle, user);
+                }
+                if !tags.location.is_empty() {
+                    screenshots.set_location(handle, &tags.location);
+                }
+            }
+        }
+        ready.send(ScreenshotReady { handle, result });
+    }
+}
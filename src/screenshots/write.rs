@@ -0,0 +1,221 @@
+use std::{
+    fmt,
+    sync::mpsc::{self, Receiver, Sender},
+    thread::{self, JoinHandle},
+};
+
+use bevy_ecs::event::{Event, EventWriter};
+use bevy_log::warn;
+use bevy_render::{render_resource::TextureFormat, texture::Image};
+use bevy_utils::{synccell::SyncCell, HashMap};
+use steamworks::{sys, SteamError};
+
+use super::{ScreenshotHandle, SteamScreenshots};
+use crate::{Client, ShuttingDown};
+
+/// Images with more pixels than this are converted and written on a background
+/// thread, so the frame isn't held up.
+const BACKGROUND_WRITE_PIXELS: usize = 1920 * 1080;
+
+/// An error from writing a screenshot with [`SteamScreenshots::write_image`].
+#[derive(Debug, Clone, Copy)]
+pub enum ScreenshotWriteError {
+    /// The image is not 8-bit RGBA or BGRA.
+    UnsupportedFormat(TextureFormat),
+    /// Steam did not accept the screenshot.
+    WriteFailed,
+    /// Steam accepted the screenshot but failed to save it.
+    Steam(SteamError),
+}
+
+impl fmt::Display for ScreenshotWriteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedFormat(format) => {
+                write!(f, "screenshots cannot be written from {format:?} images")
+            }
+            Self::WriteFailed => f.write_str("Steam did not accept the screenshot"),
+            Self::Steam(err) => write!(f, "failed to save the screenshot: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ScreenshotWriteError {}
+
+/// Sent when a screenshot written with [`SteamScreenshots::write_image`] has been
+/// saved to the user's Steam library, or has failed.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ScreenshotWritten {
+    /// The ID returned by [`SteamScreenshots::write_image`].
+    pub request_id: u64,
+    /// The handle of the saved screenshot, for tagging it.
+    pub result: Result<ScreenshotHandle, ScreenshotWriteError>,
+}
+
+type WriteResult = (u64, Result<ScreenshotHandle, ScreenshotWriteError>);
+
+/// Tracks screenshots from being written until Steam reports them as ready.
+pub(super) struct ScreenshotWrites {
+    client: Client,
+    next_request_id: u64,
+    sender: Sender<WriteResult>,
+    receiver: SyncCell<Receiver<WriteResult>>,
+    /// Screenshots Steam accepted, waiting for their `ScreenshotReady_t`.
+    awaiting: HashMap<ScreenshotHandle, u64>,
+    /// Writes running on background threads.
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl Drop for ScreenshotWrites {
+    fn drop(&mut self) {
+        // Each thread holds a client, so they are waited on to keep the last client
+        // from being released, and Steam shut down, on one of them.
+        for thread in self.threads.drain(..) {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl ScreenshotWrites {
+    pub(super) fn new(client: &Client) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            client: client.clone(),
+            next_request_id: 0,
+            sender,
+            receiver: SyncCell::new(receiver),
+            awaiting: HashMap::default(),
+            threads: Vec::new(),
+        }
+    }
+
+    /// Collects finished writes, sending events for those Steam didn't accept.
+    pub(super) fn flush_failed(&mut self, written: &mut EventWriter<ScreenshotWritten>) {
+        for (request_id, result) in self.receiver.get().try_iter() {
+            match result {
+                Ok(handle) => {
+                    self.awaiting.insert(handle, request_id);
+                }
+                Err(err) => {
+                    written.send(ScreenshotWritten {
+                        request_id,
+                        result: Err(err),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Sends the event for a written screenshot once it is ready. Returns `false`
+    /// if the screenshot wasn't written through [`SteamScreenshots::write_image`].
+    pub(super) fn on_ready(
+        &mut self,
+        handle: ScreenshotHandle,
+        result: Result<(), SteamError>,
+        written: &mut EventWriter<ScreenshotWritten>,
+    ) -> bool {
+        let Some(request_id) = self.awaiting.remove(&handle) else {
+            return false;
+        };
+        written.send(ScreenshotWritten {
+            request_id,
+            result: result.map(|()| handle).map_err(ScreenshotWriteError::Steam),
+        });
+        true
+    }
+}
+
+impl SteamScreenshots {
+    /// Writes an image to the user's Steam library as a screenshot, returning an ID
+    /// for the request.
+    ///
+    /// The image must be 8-bit RGBA or BGRA, as produced by rendering to a texture
+    /// or by `bevy_render`'s `ScreenshotManager`. Large images are converted and
+    /// written on a background thread. Completes with a [`ScreenshotWritten`]
+    /// event carrying the same ID.
+    pub fn write_image(&mut self, image: &Image) -> Result<u64, ShuttingDown> {
+        self.client.ensure_running()?;
+        let request_id = self.writes.next_request_id;
+        self.writes.next_request_id += 1;
+        let format = image.texture_descriptor.format;
+        let bgra = match format {
+            TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => false,
+            TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb => true,
+            format => {
+                let _ = self.writes.sender.send((
+                    request_id,
+                    Err(ScreenshotWriteError::UnsupportedFormat(format)),
+                ));
+                return Ok(request_id);
+            }
+        };
+        let width = image.width();
+        let height = image.height();
+        let pixels = image.data.clone();
+        let sender = self.writes.sender.clone();
+        let client = self.writes.client.clone();
+        let write = move || {
+            let rgb = to_rgb(&pixels, bgra);
+            // Callbacks can't run until the handle has been sent, so its
+            // `ScreenshotReady_t` is never handled before it can be matched to the write.
+            client.with_raw(|_| {
+                let result = write_screenshot(&rgb, width, height);
+                let _ = sender.send((request_id, result));
+            });
+        };
+        if (width as usize) * (height as usize) > BACKGROUND_WRITE_PIXELS {
+            self.writes.threads.retain(|thread| !thread.is_finished());
+            let spawned = thread::Builder::new()
+                .name("steam-screenshot-writer".into())
+                .spawn(write);
+            match spawned {
+                Ok(thread) => self.writes.threads.push(thread),
+                Err(err) => warn!(
+                    "Failed to spawn the Steam screenshot writer thread: {}",
+                    err
+                ),
+            }
+        } else {
+            write();
+        }
+        Ok(request_id)
+    }
+}
+
+/// Drops the alpha channel, which Steam doesn't take, and swaps BGRA to RGB.
+fn to_rgb(pixels: &[u8], bgra: bool) -> Vec<u8> {
+    let mut rgb = Vec::with_capacity(pixels.len() / 4 * 3);
+    for pixel in pixels.chunks_exact(4) {
+        if bgra {
+            rgb.extend_from_slice(&[pixel[2], pixel[1], pixel[0]]);
+        } else {
+            rgb.extend_from_slice(&pixel[..3]);
+        }
+    }
+    rgb
+}
+
+fn write_screenshot(
+    rgb: &[u8],
+    width: u32,
+    height: u32,
+) -> Result<ScreenshotHandle, ScreenshotWriteError> {
+    // steamworks does not wrap ISteamScreenshots, so it is called directly.
+    // SAFETY: The Steam API is initialized for as long as a `Client` exists, and
+    // the pixels outlive the call.
+    let handle = unsafe {
+        sys::SteamAPI_ISteamScreenshots_WriteScreenshot(
+            sys::SteamAPI_SteamScreenshots_v003(),
+            rgb.as_ptr().cast_mut().cast(),
+            rgb.len() as u32,
+            width as i32,
+            height as i32,
+        )
+    };
+    // 0 is INVALID_SCREENSHOT_HANDLE.
+    if handle == 0 {
+        Err(ScreenshotWriteError::WriteFailed)
+    } else {
+        Ok(handle)
+    }
+}
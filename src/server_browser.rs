@@ -0,0 +1,419 @@
+mod favorites;
+
+pub use favorites::*;
+
+use std::{
+    ffi::{c_char, c_int, CStr},
+    net::{Ipv4Addr, SocketAddrV4},
+    time::Duration,
+};
+
+use bevy_app::{App, First};
+use bevy_ecs::{
+    event::{Event, EventWriter},
+    schedule::IntoSystemConfigs,
+    system::{Res, Resource},
+};
+use steamworks::{sys, SteamId};
+
+use crate::{
+    call_result::CallResults, shutdown::steam_running, Client, ShuttingDown, SteamworksSystem,
+};
+
+/// Which list of game servers a [`ServerBrowser`] query searches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ServerListScope {
+    /// Servers registered with the master server.
+    Internet,
+    /// Servers on the local network.
+    Lan,
+    /// Servers the user has marked as favorites.
+    Favorites,
+    /// Servers the user has recently played on.
+    History,
+    /// Servers the user's friends are playing on.
+    Friends,
+}
+
+/// Sent for every server that responds to a query started with
+/// [`ServerBrowser::request`].
+#[derive(Event, Debug, Clone)]
+pub struct ServerFound {
+    /// The ID returned by [`ServerBrowser::request`].
+    pub request_id: u64,
+    /// The address game clients connect to.
+    pub addr: SocketAddrV4,
+    /// The port the server answers queries on.
+    pub query_port: u16,
+    /// The server's Steam ID.
+    pub steam_id: SteamId,
+    /// The server's name.
+    pub name: String,
+    /// The map the server is running.
+    pub map: String,
+    /// How many players are on the server, including bots.
+    pub players: u32,
+    /// The most players the server allows.
+    pub max_players: u32,
+    /// The round trip time to the server.
+    pub ping: Duration,
+    /// The tags the server was registered with.
+    pub tags: Vec<String>,
+    /// Whether the server requires a password.
+    pub has_password: bool,
+}
+
+/// Sent when a query started with [`ServerBrowser::request`] has heard from
+/// every server it is going to. No more [`ServerFound`] events are sent for it.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ServerListComplete {
+    /// The ID returned by [`ServerBrowser::request`].
+    pub request_id: u64,
+    /// How many servers the query found.
+    pub total: usize,
+}
+
+enum ServerListResult {
+    Found(ServerFound),
+    Complete(ServerListComplete),
+}
+
+/// The `ISteamMatchmakingServerListResponse` handed to Steam for a query.
+///
+/// Steam calls back into it from `run_callbacks` through the C++ vtable, so
+/// the layout must match: a vtable pointer followed by our own data.
+#[repr(C)]
+struct ServerListResponse {
+    vtable: &'static ResponseVtable,
+    request_id: u64,
+    results: CallResults<ServerListResult>,
+}
+
+macro_rules! response_vtable {
+    ($abi: literal) => {
+        #[repr(C)]
+        struct ResponseVtable {
+            server_responded:
+                unsafe extern $abi fn(*mut ServerListResponse, sys::HServerListRequest, c_int),
+            server_failed_to_respond:
+                unsafe extern $abi fn(*mut ServerListResponse, sys::HServerListRequest, c_int),
+            refresh_complete: unsafe extern $abi fn(
+                *mut ServerListResponse,
+                sys::HServerListRequest,
+                sys::EMatchMakingServerResponse,
+            ),
+        }
+
+        unsafe extern $abi fn server_responded(
+            this: *mut ServerListResponse,
+            request: sys::HServerListRequest,
+            server: c_int,
+        ) {
+            (*this).on_server_responded(request, server);
+        }
+
+        unsafe extern $abi fn server_failed_to_respond(
+            _: *mut ServerListResponse,
+            _: sys::HServerListRequest,
+            _: c_int,
+        ) {
+        }
+
+        unsafe extern $abi fn refresh_complete(
+            this: *mut ServerListResponse,
+            request: sys::HServerListRequest,
+            _: sys::EMatchMakingServerResponse,
+        ) {
+            (*this).on_refresh_complete(request);
+        }
+    };
+}
+
+// Virtual methods use thiscall on 32-bit Windows, which is the C convention
+// everywhere else.
+#[cfg(all(windows, target_arch = "x86"))]
+response_vtable!("thiscall");
+#[cfg(not(all(windows, target_arch = "x86")))]
+response_vtable!("C");
+
+static RESPONSE_VTABLE: ResponseVtable = ResponseVtable {
+    server_responded,
+    server_failed_to_respond,
+    refresh_complete,
+};
+
+impl ServerListResponse {
+    fn on_server_responded(&self, request: sys::HServerListRequest, server: c_int) {
+        // SAFETY: Steam passes the request and index of a server it has details for.
+        let item = unsafe {
+            sys::SteamAPI_ISteamMatchmakingServers_GetServerDetails(
+                sys::SteamAPI_SteamMatchmakingServers_v002(),
+                request,
+                server,
+            )
+        };
+        // SAFETY: The details stay valid until the request is released, which
+        // can't happen while callbacks are running.
+        let Some(item) = (unsafe { item.as_ref() }) else {
+            return;
+        };
+        let text = |chars: &[c_char]| {
+            // SAFETY: Steam null-terminates every string in the server details.
+            unsafe { CStr::from_ptr(chars.as_ptr()) }
+                .to_string_lossy()
+                .into_owned()
+        };
+        let tags = text(&item.m_szGameTags);
+        self.results.push(ServerListResult::Found(ServerFound {
+            request_id: self.request_id,
+            addr: SocketAddrV4::new(
+                Ipv4Addr::from(item.m_NetAdr.m_unIP),
+                item.m_NetAdr.m_usConnectionPort,
+            ),
+            query_port: item.m_NetAdr.m_usQueryPort,
+            // SAFETY: A `CSteamID` is a single 64 bit integer.
+            steam_id: SteamId::from_raw(unsafe { item.m_steamID.m_steamid.m_unAll64Bits }),
+            name: text(&item.m_szServerName),
+            map: text(&item.m_szMap),
+            players: item.m_nPlayers.max(0) as u32,
+            max_players: item.m_nMaxPlayers.max(0) as u32,
+            ping: Duration::from_millis(item.m_nPing.max(0) as u64),
+            tags: tags
+                .split(',')
+                .filter(|tag| !tag.is_empty())
+                .map(str::to_owned)
+                .collect(),
+            has_password: item.m_bPassword,
+        }));
+    }
+
+    fn on_refresh_complete(&self, request: sys::HServerListRequest) {
+        // SAFETY: Steam passes the request that completed.
+        let total = unsafe {
+            sys::SteamAPI_ISteamMatchmakingServers_GetServerCount(
+                sys::SteamAPI_SteamMatchmakingServers_v002(),
+                request,
+            )
+        };
+        self.results
+            .push(ServerListResult::Complete(ServerListComplete {
+                request_id: self.request_id,
+                total: total.max(0) as usize,
+            }));
+    }
+}
+
+/// A query Steam is running, along with the response it calls back into.
+struct ActiveQuery {
+    handle: sys::HServerListRequest,
+    // Boxed so its address stays stable for as long as Steam holds it.
+    _response: Box<ServerListResponse>,
+}
+
+// SAFETY: The handle is only ever passed back to Steam, which accepts it from
+// any thread.
+unsafe impl Send for ActiveQuery {}
+// SAFETY: See above. Nothing is accessed through a shared `ActiveQuery`.
+unsafe impl Sync for ActiveQuery {}
+
+impl Drop for ActiveQuery {
+    fn drop(&mut self) {
+        // Releasing cancels the query, after which Steam no longer calls the
+        // response, so it can be freed once this returns.
+        // SAFETY: The handle came from a request that hasn't been released yet.
+        unsafe {
+            sys::SteamAPI_ISteamMatchmakingServers_ReleaseRequest(
+                sys::SteamAPI_SteamMatchmakingServers_v002(),
+                self.handle,
+            );
+        }
+    }
+}
+
+/// Lists game servers, for in-game server browsers.
+///
+/// Only one query runs at a time: starting a new one with
+/// [`ServerBrowser::request`] cancels the previous one. Servers stream in as
+/// [`ServerFound`] events, followed by a [`ServerListComplete`].
+#[derive(Resource)]
+pub struct ServerBrowser {
+    active: Option<ActiveQuery>,
+    client: Client,
+    next_request_id: u64,
+    results: CallResults<ServerListResult>,
+}
+
+impl ServerBrowser {
+    /// Starts listing the game servers for this app in `scope`, returning an ID
+    /// to match the [`ServerFound`] and [`ServerListComplete`] events against.
+    ///
+    /// `filters` are key-value pairs such as `("map", "de_dust")` or
+    /// `("gametagsand", "casual")`, as described in Steam's documentation for
+    /// `MatchMakingKeyValuePair_t`. LAN queries ignore them. Keys and values
+    /// longer than 255 bytes are truncated.
+    pub fn request(
+        &mut self,
+        scope: ServerListScope,
+        filters: &[(&str, &str)],
+    ) -> Result<u64, ShuttingDown> {
+        self.client.ensure_running()?;
+        // Release the previous query before starting another.
+        self.active = None;
+        let request_id = self.next_request_id;
+        self.next_request_id += 1;
+
+        let mut response = Box::new(ServerListResponse {
+            vtable: &RESPONSE_VTABLE,
+            request_id,
+            results: self.results.clone(),
+        });
+        let mut pairs: Vec<sys::MatchMakingKeyValuePair_t> = filters
+            .iter()
+            .map(|(key, value)| key_value_pair(key, value))
+            .collect();
+        let mut pointers: Vec<*mut sys::MatchMakingKeyValuePair_t> =
+            pairs.iter_mut().map(|pair| pair as *mut _).collect();
+        let app_id = self.client.utils().app_id().0;
+        let response_ptr = &mut *response as *mut ServerListResponse
+            as *mut sys::ISteamMatchmakingServerListResponse;
+
+        // steamworks does not wrap the server browser, so it is called directly.
+        // SAFETY: The Steam API is initialized for as long as a `Client` exists.
+        // Filters are copied before the request returns, and the response is kept
+        // alive until the request is released.
+        let handle = unsafe {
+            let servers = sys::SteamAPI_SteamMatchmakingServers_v002();
+            let filters = pointers.as_mut_ptr();
+            let count = pointers.len() as u32;
+            match scope {
+                ServerListScope::Internet => {
+                    sys::SteamAPI_ISteamMatchmakingServers_RequestInternetServerList(
+                        servers,
+                        app_id,
+                        filters,
+                        count,
+                        response_ptr,
+                    )
+                }
+                ServerListScope::Lan => {
+                    sys::SteamAPI_ISteamMatchmakingServers_RequestLANServerList(
+                        servers,
+                        app_id,
+                        response_ptr,
+                    )
+                }
+                ServerListScope::Favorites => {
+                    sys::SteamAPI_ISteamMatchmakingServers_RequestFavoritesServerList(
+                        servers,
+                        app_id,
+                        filters,
+                        count,
+                        response_ptr,
+                    )
+                }
+                ServerListScope::History => {
+                    sys::SteamAPI_ISteamMatchmakingServers_RequestHistoryServerList(
+                        servers,
+                        app_id,
+                        filters,
+                        count,
+                        response_ptr,
+                    )
+                }
+                ServerListScope::Friends => {
+                    sys::SteamAPI_ISteamMatchmakingServers_RequestFriendsServerList(
+                        servers,
+                        app_id,
+                        filters,
+                        count,
+                        response_ptr,
+                    )
+                }
+            }
+        };
+        self.active = Some(ActiveQuery {
+            handle,
+            _response: response,
+        });
+        Ok(request_id)
+    }
+
+    /// Cancels the running query, if any. No more events are sent for it.
+    pub fn cancel(&mut self) {
+        self.active = None;
+    }
+
+    /// Checks if a query is still waiting on servers to respond. Returns `false`
+    /// once Steam is shutting down.
+    pub fn is_refreshing(&self) -> bool {
+        let Some(active) = &self.active else {
+            return false;
+        };
+        if self.client.is_shutting_down() {
+            return false;
+        }
+        // SAFETY: The handle came from a request that hasn't been released yet.
+        unsafe {
+            sys::SteamAPI_ISteamMatchmakingServers_IsRefreshing(
+                sys::SteamAPI_SteamMatchmakingServers_v002(),
+                active.handle,
+            )
+        }
+    }
+}
+
+fn key_value_pair(key: &str, value: &str) -> sys::MatchMakingKeyValuePair_t {
+    // SAFETY: The pair is two plain character arrays, for which zeroes are valid.
+    let mut pair: sys::MatchMakingKeyValuePair_t = unsafe { std::mem::zeroed() };
+    copy_truncated(&mut pair.m_szKey, key);
+    copy_truncated(&mut pair.m_szValue, value);
+    pair
+}
+
+/// Copies `text` into `buffer` as a null-terminated string, truncated to fit.
+fn copy_truncated(buffer: &mut [c_char], text: &str) {
+    let bytes = text.as_bytes();
+    let len = bytes.len().min(buffer.len() - 1);
+    for (dst, &src) in buffer.iter_mut().zip(&bytes[..len]) {
+        *dst = src as c_char;
+    }
+    buffer[len] = 0;
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    let client = app.world().resource::<Client>().clone();
+    let favorites = FavoriteServers::new(&client);
+    app.insert_resource(favorites)
+        .insert_resource(ServerBrowser {
+            active: None,
+            client,
+            next_request_id: 0,
+            results: CallResults::new(),
+        })
+        .add_event::<ServerFound>()
+        .add_event::<ServerListComplete>()
+        .add_event::<FavoritesListChanged>()
+        .add_systems(
+            First,
+            (flush_server_list, favorites::track_favorites)
+                .after(SteamworksSystem::RunCallbacks)
+                .run_if(steam_running),
+        );
+}
+
+fn flush_server_list(
+    browser: Res<ServerBrowser>,
+    mut found: EventWriter<ServerFound>,
+    mut complete: EventWriter<ServerListComplete>,
+) {
+    for result in browser.results.drain() {
+        match result {
+            ServerListResult::Found(server) => {
+                found.send(server);
+            }
+            ServerListResult::Complete(done) => {
+                complete.send(done);
+            }
+        }
+    }
+}
@@ -0,0 +1,244 @@
+use std::{
+    ffi::c_void,
+    net::{Ipv4Addr, SocketAddrV4},
+};
+
+use bevy_ecs::{
+    event::{Event, EventWriter},
+    system::{ResMut, Resource},
+};
+use steamworks::{sys, AppId, Callback};
+
+use crate::{call_result::CallResults, shutdown::RegisteredCallback, Client, ShuttingDown};
+
+/// Which of the user's server lists an entry in [`FavoriteServers`] is on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FavoriteKind {
+    /// A server the user marked as a favorite.
+    Favorite,
+    /// A server the user has played on.
+    History,
+}
+
+impl FavoriteKind {
+    fn flags(self) -> u32 {
+        match self {
+            Self::Favorite => sys::k_unFavoriteFlagFavorite,
+            Self::History => sys::k_unFavoriteFlagHistory,
+        }
+    }
+
+    fn from_flags(flags: u32) -> Self {
+        if flags & sys::k_unFavoriteFlagFavorite != 0 {
+            Self::Favorite
+        } else {
+            Self::History
+        }
+    }
+}
+
+/// A server on the user's favorites or history list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FavoriteServer {
+    /// The address game clients connect to.
+    pub addr: SocketAddrV4,
+    /// The port the server answers queries on.
+    pub query_port: u16,
+    /// Which list the server is on.
+    pub kind: FavoriteKind,
+    /// When the user last played on the server, as a Unix timestamp.
+    pub last_played: u32,
+}
+
+/// Sent when a server is added to or removed from the user's favorites or
+/// history, whether through [`FavoriteServers`] or the Steam overlay.
+///
+/// [`FavoriteServers`] has already been refreshed when this is sent.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FavoritesListChanged {
+    /// The address game clients connect to.
+    pub addr: SocketAddrV4,
+    /// The port the server answers queries on.
+    pub query_port: u16,
+    /// Which list changed.
+    pub kind: FavoriteKind,
+    /// Whether the server was added rather than removed.
+    pub added: bool,
+}
+
+/// `FavoritesListChanged_t`, which steamworks does not wrap.
+struct FavoritesChanged(FavoritesListChanged, AppId);
+
+unsafe impl Callback for FavoritesChanged {
+    const ID: i32 = 502;
+    const SIZE: i32 = std::mem::size_of::<sys::FavoritesListChanged_t>() as i32;
+
+    unsafe fn from_raw(raw: *mut c_void) -> Self {
+        let val = &mut *(raw as *mut sys::FavoritesListChanged_t);
+        Self(
+            FavoritesListChanged {
+                addr: SocketAddrV4::new(Ipv4Addr::from(val.m_nIP), val.m_nConnPort as u16),
+                query_port: val.m_nQueryPort as u16,
+                kind: FavoriteKind::from_flags(val.m_nFlags),
+                added: val.m_bAdd,
+            },
+            AppId(val.m_nAppID),
+        )
+    }
+}
+
+/// The user's favorite and recently played game servers for this app.
+///
+/// The lists are kept up to date as servers are added or removed, including from
+/// the Steam overlay, with a [`FavoritesListChanged`] event sent for each change.
+#[derive(Resource)]
+pub struct FavoriteServers {
+    client: Client,
+    servers: Vec<FavoriteServer>,
+    changed: CallResults<FavoritesListChanged>,
+    _changed_callback: RegisteredCallback,
+}
+
+impl FavoriteServers {
+    pub(super) fn new(client: &Client) -> Self {
+        let app_id = client.utils().app_id();
+        let changed = CallResults::new();
+        let changed_in = changed.clone();
+        let changed_callback = RegisteredCallback::new(client.register_callback(
+            move |FavoritesChanged(change, change_app_id)| {
+                if change_app_id == app_id {
+                    changed_in.push(change);
+                }
+            },
+        ));
+        let mut favorites = Self {
+            client: client.clone(),
+            servers: Vec::new(),
+            changed,
+            _changed_callback: changed_callback,
+        };
+        favorites.refresh();
+        favorites
+    }
+
+    /// Iterates over every server on either list.
+    pub fn iter(&self) -> impl Iterator<Item = &FavoriteServer> {
+        self.servers.iter()
+    }
+
+    /// Iterates over the servers on the user's favorites list.
+    pub fn favorites(&self) -> impl Iterator<Item = &FavoriteServer> {
+        self.of_kind(FavoriteKind::Favorite)
+    }
+
+    /// Iterates over the servers on the user's history list.
+    pub fn history(&self) -> impl Iterator<Item = &FavoriteServer> {
+        self.of_kind(FavoriteKind::History)
+    }
+
+    fn of_kind(&self, kind: FavoriteKind) -> impl Iterator<Item = &FavoriteServer> {
+        self.servers
+            .iter()
+            .filter(move |server| server.kind == kind)
+    }
+
+    /// Adds a server to the user's favorites or history, marking it as played
+    /// on now.
+    pub fn add(
+        &self,
+        addr: SocketAddrV4,
+        query_port: u16,
+        kind: FavoriteKind,
+    ) -> Result<(), ShuttingDown> {
+        self.client.ensure_running()?;
+        let now = self.client.utils().get_server_real_time();
+        // steamworks does not wrap favorite servers, so they are called directly.
+        // SAFETY: The Steam API is initialized for as long as a `Client` exists.
+        unsafe {
+            sys::SteamAPI_ISteamMatchmaking_AddFavoriteGame(
+                sys::SteamAPI_SteamMatchmaking_v009(),
+                self.client.utils().app_id().0,
+                u32::from(*addr.ip()),
+                addr.port(),
+                query_port,
+                kind.flags(),
+                now,
+            );
+        }
+        Ok(())
+    }
+
+    /// Removes a server from the user's favorites or history. Returns `false` if
+    /// it wasn't on the list or Steam is shutting down.
+    pub fn remove(&self, addr: SocketAddrV4, query_port: u16, kind: FavoriteKind) -> bool {
+        if self.client.is_shutting_down() {
+            return false;
+        }
+        // steamworks does not wrap favorite servers, so they are called directly.
+        // SAFETY: The Steam API is initialized for as long as a `Client` exists.
+        unsafe {
+            sys::SteamAPI_ISteamMatchmaking_RemoveFavoriteGame(
+                sys::SteamAPI_SteamMatchmaking_v009(),
+                self.client.utils().app_id().0,
+                u32::from(*addr.ip()),
+                addr.port(),
+                query_port,
+                kind.flags(),
+            )
+        }
+    }
+
+    /// Reads both lists from Steam again.
+    ///
+    /// This is done automatically whenever [`FavoritesListChanged`] is sent. Keeps
+    /// the last lists once Steam is shutting down.
+    pub fn refresh(&mut self) {
+        if self.client.is_shutting_down() {
+            return;
+        }
+        let app_id = self.client.utils().app_id().0;
+        self.servers.clear();
+        // steamworks does not wrap favorite servers, so they are called directly.
+        // SAFETY: The Steam API is initialized for as long as a `Client` exists.
+        unsafe {
+            let matchmaking = sys::SteamAPI_SteamMatchmaking_v009();
+            let count = sys::SteamAPI_ISteamMatchmaking_GetFavoriteGameCount(matchmaking);
+            for index in 0..count {
+                let (mut server_app_id, mut ip, mut conn_port, mut query_port) = (0, 0, 0, 0);
+                let (mut flags, mut last_played) = (0, 0);
+                let found = sys::SteamAPI_ISteamMatchmaking_GetFavoriteGame(
+                    matchmaking,
+                    index,
+                    &mut server_app_id,
+                    &mut ip,
+                    &mut conn_port,
+                    &mut query_port,
+                    &mut flags,
+                    &mut last_played,
+                );
+                // The lists are shared between every game, so only keep this app's.
+                if !found || server_app_id != app_id {
+                    continue;
+                }
+                self.servers.push(FavoriteServer {
+                    addr: SocketAddrV4::new(Ipv4Addr::from(ip), conn_port),
+                    query_port,
+                    kind: FavoriteKind::from_flags(flags),
+                    last_played,
+                });
+            }
+        }
+    }
+}
+
+pub(super) fn track_favorites(
+    mut favorites: ResMut<FavoriteServers>,
+    mut changed: EventWriter<FavoritesListChanged>,
+) {
+    let changes = favorites.changed.drain();
+    if changes.is_empty() {
+        return;
+    }
+    favorites.refresh();
+    changed.send_batch(changes);
+}
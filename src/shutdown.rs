@@ -0,0 +1,108 @@
+use std::{
+    ffi::c_void,
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use bevy_app::AppExit;
+use bevy_ecs::{
+    event::EventReader,
+    system::{Res, Resource},
+};
+use steamworks::{sys, Callback, CallbackHandle};
+
+/// The error returned by helpers once Steam is shutting down.
+///
+/// See [`SteamShuttingDown`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShuttingDown;
+
+impl fmt::Display for ShuttingDown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Steam is shutting down")
+    }
+}
+
+impl std::error::Error for ShuttingDown {}
+
+/// Set once [`AppExit`] has been sent or Steam has announced that it is shutting
+/// down.
+///
+/// From the next frame on, Steam callbacks are no longer run, the crate's systems
+/// do nothing, and helpers that would call into Steam fail with [`ShuttingDown`]
+/// instead. Helpers that only read from Steam return `None`, `false`, or nothing,
+/// and refreshing cached values keeps the last ones read. Releasing what is still
+/// held, such as cancelling auth tickets or ending auth sessions, keeps working.
+/// Work done on exit, such as storing stats, still runs in
+/// [`Last`](bevy_app::Last) on the frame [`AppExit`] is sent.
+#[derive(Resource, Clone, Default)]
+pub struct SteamShuttingDown(Arc<AtomicBool>);
+
+impl SteamShuttingDown {
+    /// Checks if Steam is shutting down.
+    pub fn is_set(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+
+    fn set(&self) {
+        self.0.store(true, Ordering::Release);
+    }
+}
+
+/// `SteamShutdown_t`, which steamworks does not wrap.
+struct SteamShutdown;
+
+unsafe impl Callback for SteamShutdown {
+    const ID: i32 = 704;
+    const SIZE: i32 = std::mem::size_of::<sys::SteamShutdown_t>() as i32;
+
+    unsafe fn from_raw(_: *mut c_void) -> Self {
+        Self
+    }
+}
+
+/// A registered Steam callback, disconnected when dropped.
+///
+/// Dropping a steamworks [`CallbackHandle`] leaves its callback registered until
+/// the last client is released, so resources hold this instead to stop their
+/// callbacks as they are torn down.
+pub(crate) struct RegisteredCallback(Option<CallbackHandle>);
+
+impl RegisteredCallback {
+    pub(crate) fn new(handle: CallbackHandle) -> Self {
+        Self(Some(handle))
+    }
+}
+
+impl Drop for RegisteredCallback {
+    fn drop(&mut self) {
+        if let Some(handle) = self.0.take() {
+            handle.disconnect();
+        }
+    }
+}
+
+pub(crate) fn register_shutdown_callback(
+    client: &steamworks::Client,
+    shutting_down: &SteamShuttingDown,
+) -> RegisteredCallback {
+    let shutting_down = shutting_down.clone();
+    RegisteredCallback::new(client.register_callback(move |SteamShutdown| shutting_down.set()))
+}
+
+pub(crate) fn detect_app_exit(
+    shutting_down: Res<SteamShuttingDown>,
+    mut exit: EventReader<AppExit>,
+) {
+    if exit.read().next().is_some() {
+        shutting_down.set();
+    }
+}
+
+/// A run condition for the crate's systems, which stop once Steam is shutting down.
+pub(crate) fn steam_running(shutting_down: Res<SteamShuttingDown>) -> bool {
+    !shutting_down.is_set()
+}
@@ -0,0 +1,207 @@
+#[cfg(feature = "bevy_render")]
+mod achievement_icons;
+mod achievements;
+mod deferred;
+mod leaderboards;
+mod player_count;
+mod user_achievements;
+mod user_stats;
+
+#[cfg(feature = "bevy_render")]
+pub use achievement_icons::AchievementIconLoaded;
+pub use achievements::*;
+pub use deferred::DeferredStatsFlushed;
+pub use leaderboards::*;
+pub use player_count::*;
+pub use user_achievements::*;
+pub use user_stats::*;
+
+use std::time::{Duration, Instant};
+
+use bevy_app::{App, AppExit, First, Last, PostUpdate};
+use bevy_ecs::{
+    event::{EventReader, EventWriter, Events, ManualEventReader},
+    schedule::IntoSystemConfigs,
+    system::{Local, Res, ResMut},
+    world::World,
+};
+use bevy_log::warn;
+
+use crate::{
+    run_steam_callbacks_exclusive, shutdown::steam_running, Client, SteamworksEvent,
+    SteamworksSystem,
+};
+
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+pub(crate) fn plugin(app: &mut App) {
+    let client = app.world().resource::<Client>();
+    client.user_stats().request_current_stats();
+    let achievements = SteamAchievements::new(client);
+    let stats = SteamStats::new(client);
+    let leaderboards = SteamLeaderboards::new(client);
+    let player_count = CurrentPlayerCount::new(client);
+    app.insert_resource(achievements)
+        .insert_resource(stats)
+        .insert_resource(leaderboards)
+        .insert_resource(player_count)
+        .add_event::<AchievementStored>()
+        .add_event::<GlobalAchievementPercentagesReceived>()
+        .add_event::<SteamStatChanged>()
+        .add_event::<UserStatsReady>()
+        .add_event::<UserStatsUnavailable>()
+        .add_event::<DeferredStatsFlushed>()
+        .add_event::<LeaderboardFound>()
+        .add_event::<ScoreUploaded>()
+        .add_event::<LeaderboardEntriesDownloaded>()
+        .add_event::<PlayerCountReceived>()
+        .add_systems(
+            First,
+            (
+                handle_stats_events,
+                achievements::flush_global_percentages,
+                leaderboards::flush_leaderboard_results,
+                player_count::refresh_player_count,
+                user_stats::track_session_time,
+            )
+                .after(SteamworksSystem::RunCallbacks)
+                .run_if(steam_running),
+        )
+        .add_systems(PostUpdate, store_stats.run_if(steam_running))
+        .add_systems(Last, store_stats_on_exit);
+
+    #[cfg(feature = "dev-tools")]
+    app.add_event::<SteamStatsReset>();
+
+    #[cfg(feature = "bevy_render")]
+    app.add_event::<AchievementIconLoaded>().add_systems(
+        PostUpdate,
+        achievement_icons::load_achievement_icons.run_if(steam_running),
+    );
+}
+
+fn handle_stats_events(
+    client: Res<Client>,
+    mut achievements: ResMut<SteamAchievements>,
+    mut stats: ResMut<SteamStats>,
+    mut events: EventReader<SteamworksEvent>,
+    mut stored: EventWriter<AchievementStored>,
+    mut ready: EventWriter<UserStatsReady>,
+    mut unavailable: EventWriter<UserStatsUnavailable>,
+    mut flushed: EventWriter<DeferredStatsFlushed>,
+) {
+    stats.flush_unloaded();
+    for steam_id in stats.expire_user_requests() {
+        unavailable.send(UserStatsUnavailable(steam_id));
+    }
+    for event in events.read() {
+        match event {
+            SteamworksEvent::UserStatsReceived(received) => {
+                if received.result.is_err() {
+                    if stats.on_user_stats_failed(received.steam_id) {
+                        unavailable.send(UserStatsUnavailable(received.steam_id));
+                    }
+                    continue;
+                }
+                if received.steam_id == client.user().steam_id() {
+                    achievements.on_stats_received();
+                    stats.on_stats_received();
+                } else {
+                    stats.on_user_stats_received(received.steam_id);
+                }
+                ready.send(UserStatsReady(received.steam_id));
+            }
+            SteamworksEvent::SteamServersConnected(_) => {
+                let count = achievements.replay_deferred() + stats.replay_deferred();
+                if count > 0 {
+                    flushed.send(DeferredStatsFlushed { count });
+                }
+            }
+            SteamworksEvent::UserStatsStored(_) => stats.storing = false,
+            SteamworksEvent::UserAchievementStored(achievement) => {
+                stored.send(AchievementStored {
+                    name: achievement.achievement_name.clone(),
+                });
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Persists every stat and achievement change made this frame with a single
+/// call to `store_stats`.
+fn store_stats(
+    client: Res<Client>,
+    mut achievements: ResMut<SteamAchievements>,
+    mut stats: ResMut<SteamStats>,
+    mut changed: EventWriter<SteamStatChanged>,
+    #[cfg(feature = "dev-tools")] mut reset: EventWriter<SteamStatsReset>,
+) {
+    #[cfg(feature = "dev-tools")]
+    for result in std::mem::take(&mut stats.resets) {
+        if result.result.is_ok() {
+            achievements.on_stats_reset();
+        }
+        reset.send(result);
+    }
+    if !stats.changed.is_empty() {
+        changed.send_batch(std::mem::take(&mut stats.changed));
+    }
+    if !achievements.dirty && !stats.dirty {
+        return;
+    }
+    achievements.dirty = false;
+    stats.dirty = false;
+    if client.user_stats().store_stats().is_err() {
+        warn!("Failed to store Steam stats and achievements");
+        return;
+    }
+    stats.storing = true;
+}
+
+/// Stores any changes left over when the app exits and, unless disabled, waits
+/// for Steam to confirm them so they aren't lost.
+///
+/// Callbacks are run the same way as in [`First`], through the shared callback
+/// system, so this takes the world exclusively.
+fn store_stats_on_exit(world: &mut World, mut exit: Local<ManualEventReader<AppExit>>) {
+    if exit
+        .read(world.resource::<Events<AppExit>>())
+        .next()
+        .is_none()
+    {
+        return;
+    }
+    let client = world.resource::<Client>().clone();
+    let dirty = world.resource::<SteamAchievements>().dirty || world.resource::<SteamStats>().dirty;
+    let stats = world.resource::<SteamStats>();
+    let (storing, block_on_exit, exit_timeout) =
+        (stats.storing, stats.block_on_exit, stats.exit_timeout);
+    if dirty {
+        if client.user_stats().store_stats().is_err() {
+            warn!("Failed to store Steam stats and achievements before exiting");
+            return;
+        }
+    } else if !storing {
+        return;
+    }
+    if !block_on_exit {
+        return;
+    }
+
+    let mut events = world
+        .resource::<Events<SteamworksEvent>>()
+        .get_reader_current();
+    let deadline = Instant::now() + exit_timeout;
+    while Instant::now() < deadline {
+        run_steam_callbacks_exclusive(world);
+        let stored = events
+            .read(world.resource::<Events<SteamworksEvent>>())
+            .any(|event| matches!(event, SteamworksEvent::UserStatsStored(_)));
+        if stored {
+            return;
+        }
+        std::thread::sleep(EXIT_POLL_INTERVAL);
+    }
+    warn!("Timed out waiting for Steam to store stats and achievements before exiting");
+}
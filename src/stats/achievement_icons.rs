@@ -0,0 +1,101 @@
+use bevy_asset::{Assets, Handle};
+use bevy_ecs::{
+    event::{Event, EventWriter},
+    system::{ResMut, Resource},
+};
+use bevy_render::{
+    render_asset::RenderAssetUsages,
+    render_resource::{Extent3d, TextureDimension, TextureFormat},
+    texture::Image,
+};
+use bevy_utils::{HashMap, HashSet};
+
+use super::SteamAchievements;
+
+/// Sent when an icon requested with [`SteamAchievements::icon`] has been loaded.
+#[derive(Event, Debug, Clone)]
+pub struct AchievementIconLoaded {
+    /// The API name of the achievement.
+    pub name: String,
+    /// The loaded icon.
+    pub handle: Handle<Image>,
+}
+
+#[derive(Default)]
+pub(super) struct AchievementIcons {
+    handles: HashMap<String, Handle<Image>>,
+    requested: HashSet<String>,
+}
+
+impl AchievementIcons {
+    /// Forgets the cached icon of an achievement whose unlock state changed.
+    pub(super) fn invalidate(&mut self, name: &str) {
+        if self.handles.remove(name).is_some() {
+            self.requested.insert(name.to_owned());
+        }
+    }
+
+    /// Forgets every cached icon.
+    #[cfg(feature = "dev-tools")]
+    pub(super) fn invalidate_all(&mut self) {
+        self.requested
+            .extend(self.handles.drain().map(|(name, _)| name));
+    }
+}
+
+impl SteamAchievements {
+    /// Gets the icon of an achievement, matching whether it is currently locked
+    /// or unlocked.
+    ///
+    /// Icons are fetched from Steam in the background, so the first call for an
+    /// achievement returns `None` and an [`AchievementIconLoaded`] event is sent
+    /// once the icon is available. Later calls return the cached handle.
+    pub fn icon(&mut self, name: &str) -> Option<Handle<Image>> {
+        if let Some(handle) = self.icons.handles.get(name) {
+            return Some(handle.clone());
+        }
+        self.icons.requested.insert(name.to_owned());
+        None
+    }
+}
+
+pub(super) fn load_achievement_icons(
+    mut achievements: ResMut<SteamAchievements>,
+    images: Option<ResMut<Assets<Image>>>,
+    mut loaded: EventWriter<AchievementIconLoaded>,
+) {
+    let Some(mut images) = images else {
+        return;
+    };
+    if achievements.icons.requested.is_empty() {
+        return;
+    }
+    let user_stats = achievements.client.user_stats();
+    let icons = &mut achievements.icons;
+    // Icons that Steam hasn't fetched yet stay requested and are retried next frame.
+    icons.requested.retain(|name| {
+        let Some(rgba) = user_stats.achievement(name).get_achievement_icon() else {
+            return true;
+        };
+        // Achievement icons are square RGBA images.
+        let size = ((rgba.len() / 4) as f64).sqrt() as u32;
+        let image = Image::new(
+            Extent3d {
+                width: size,
+                height: size,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            rgba,
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::default(),
+        );
+        let handle = images.add(image);
+        icons.handles.insert(name.clone(), handle.clone());
+        loaded.send(AchievementIconLoaded {
+            name: name.clone(),
+            handle,
+        });
+        false
+    });
+}
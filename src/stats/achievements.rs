@@ -0,0 +1,361 @@
+use std::{
+    ffi::CString,
+    fmt,
+    time::{Duration, Instant},
+};
+
+use bevy_ecs::{
+    event::{Event, EventWriter},
+    system::{ResMut, Resource},
+};
+use bevy_log::warn;
+use bevy_utils::HashMap;
+use steamworks::{sys, SteamError};
+
+use crate::{call_result::CallResults, Client, ShuttingDown};
+
+use super::deferred::defer_write;
+
+/// Sent when Steam confirms an achievement change was stored.
+#[derive(Event, Debug, Clone)]
+pub struct AchievementStored {
+    /// The API name of the achievement.
+    pub name: String,
+}
+
+/// The localized display attributes of an achievement, as returned by
+/// [`SteamAchievements::display_info`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AchievementDisplayInfo {
+    /// The achievement's name in the user's language.
+    pub display_name: String,
+    /// The achievement's description in the user's language.
+    pub description: String,
+    /// Whether the achievement is marked as hidden in the Steamworks partner site.
+    pub hidden: bool,
+    /// Whether the current user has unlocked the achievement.
+    pub unlocked: bool,
+}
+
+impl AchievementDisplayInfo {
+    /// Checks if the achievement's description should be masked, which is the
+    /// case for hidden achievements that are still locked.
+    pub fn is_concealed(&self) -> bool {
+        self.hidden && !self.unlocked
+    }
+}
+
+/// Sent when a [`SteamAchievements::request_global_percentages`] request completes.
+#[derive(Event, Debug, Clone)]
+pub struct GlobalAchievementPercentagesReceived {
+    /// Whether the percentages were received. On failure, such as while offline,
+    /// any previously fetched percentages are kept.
+    pub result: Result<(), SteamError>,
+}
+
+/// An error from [`SteamAchievements::indicate_progress`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AchievementProgressError {
+    /// Steam has not delivered the current user's stats yet.
+    StatsNotReady,
+    /// No achievement with the given API name exists.
+    InvalidAchievement(String),
+    /// The current progress is greater than the maximum.
+    OutOfRange {
+        /// The progress that was indicated.
+        current: u32,
+        /// The maximum progress.
+        max: u32,
+    },
+    /// Steam is shutting down.
+    ShuttingDown,
+}
+
+impl fmt::Display for AchievementProgressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::StatsNotReady => f.write_str("the user's stats have not been received yet"),
+            Self::InvalidAchievement(name) => write!(f, "no achievement named {name:?} exists"),
+            Self::OutOfRange { current, max } => {
+                write!(f, "achievement progress {current} is greater than {max}")
+            }
+            Self::ShuttingDown => write!(f, "{ShuttingDown}"),
+        }
+    }
+}
+
+impl std::error::Error for AchievementProgressError {}
+
+impl From<ShuttingDown> for AchievementProgressError {
+    fn from(_: ShuttingDown) -> Self {
+        Self::ShuttingDown
+    }
+}
+
+/// Unlocks, clears, and queries the current user's achievements.
+///
+/// Writes made before Steam has delivered the user's stats are queued and
+/// applied once [`UserStatsReceived`] arrives. Changes are persisted with a
+/// single call to `store_stats` at the end of the frame in which they were made,
+/// and confirmed with an [`AchievementStored`] event. Changes made while
+/// disconnected from Steam are replayed once it reconnects.
+///
+/// [`UserStatsReceived`]: steamworks::UserStatsReceived
+#[derive(Resource)]
+pub struct SteamAchievements {
+    pub(super) client: Client,
+    ready: bool,
+    queued: Vec<(String, bool)>,
+    pub(super) dirty: bool,
+    deferred: Vec<(String, bool)>,
+    /// The minimum time between two progress notifications for the same achievement.
+    pub progress_interval: Duration,
+    progress: HashMap<String, (u32, Instant)>,
+    global_results: CallResults<Result<(), SteamError>>,
+    global_percentages: HashMap<String, f32>,
+    global_fetched_at: Option<Instant>,
+    #[cfg(feature = "bevy_render")]
+    pub(super) icons: super::achievement_icons::AchievementIcons,
+}
+
+impl SteamAchievements {
+    pub(super) fn new(client: &Client) -> Self {
+        Self {
+            client: client.clone(),
+            ready: false,
+            queued: Vec::new(),
+            dirty: false,
+            deferred: Vec::new(),
+            progress_interval: Duration::from_secs(1),
+            progress: HashMap::default(),
+            global_results: CallResults::new(),
+            global_percentages: HashMap::default(),
+            global_fetched_at: None,
+            #[cfg(feature = "bevy_render")]
+            icons: Default::default(),
+        }
+    }
+
+    /// Checks if Steam has delivered the current user's stats and achievements.
+    pub fn is_ready(&self) -> bool {
+        self.ready
+    }
+
+    /// Unlocks an achievement by its API name.
+    pub fn unlock(&mut self, name: impl Into<String>) -> Result<(), ShuttingDown> {
+        self.set(name.into(), true)
+    }
+
+    /// Clears an achievement by its API name.
+    pub fn clear(&mut self, name: impl Into<String>) -> Result<(), ShuttingDown> {
+        self.set(name.into(), false)
+    }
+
+    /// Checks if an achievement is unlocked.
+    ///
+    /// Returns `None` if the user's stats have not been received yet, the
+    /// achievement does not exist, or Steam is shutting down.
+    pub fn is_unlocked(&self, name: &str) -> Option<bool> {
+        if !self.ready || self.client.is_shutting_down() {
+            return None;
+        }
+        self.client.user_stats().achievement(name).get().ok()
+    }
+
+    /// Shows Steam's progress notification for an achievement, such as
+    /// "Kill 100 enemies: 50/100".
+    ///
+    /// This is safe to call every frame: Steam is only notified when `current`
+    /// changes, and at most once per [`SteamAchievements::progress_interval`] for
+    /// each achievement. Calls that are skipped still return `Ok`.
+    pub fn indicate_progress(
+        &mut self,
+        name: &str,
+        current: u32,
+        max: u32,
+    ) -> Result<(), AchievementProgressError> {
+        self.client.ensure_running()?;
+        if !self.ready {
+            return Err(AchievementProgressError::StatsNotReady);
+        }
+        if current > max {
+            return Err(AchievementProgressError::OutOfRange { current, max });
+        }
+        let invalid = || AchievementProgressError::InvalidAchievement(name.to_owned());
+        if self.client.user_stats().achievement(name).get().is_err() {
+            return Err(invalid());
+        }
+
+        let now = Instant::now();
+        if let Some((last, sent_at)) = self.progress.get(name) {
+            if *last == current || now.duration_since(*sent_at) < self.progress_interval {
+                return Ok(());
+            }
+        }
+        let c_name = CString::new(name).map_err(|_| invalid())?;
+        // steamworks does not wrap IndicateAchievementProgress, so it is called directly.
+        // SAFETY: The Steam API is initialized for as long as a `Client` exists.
+        let indicated = unsafe {
+            sys::SteamAPI_ISteamUserStats_IndicateAchievementProgress(
+                sys::SteamAPI_SteamUserStats_v012(),
+                c_name.as_ptr(),
+                current,
+                max,
+            )
+        };
+        if !indicated {
+            return Err(invalid());
+        }
+        self.progress.insert(name.to_owned(), (current, now));
+        Ok(())
+    }
+
+    /// Requests the percentage of all players that have unlocked each achievement.
+    ///
+    /// Completes with a [`GlobalAchievementPercentagesReceived`] event, after which
+    /// the percentages can be read with [`SteamAchievements::global_percent`].
+    pub fn request_global_percentages(&self) -> Result<(), ShuttingDown> {
+        self.client.ensure_running()?;
+        let results = self.global_results.clone();
+        self.client
+            .user_stats()
+            .request_global_achievement_percentages(move |result| {
+                results.push(result.map(|_| ()));
+            });
+        Ok(())
+    }
+
+    /// Gets the percentage of all players that have unlocked an achievement, from
+    /// 0 to 100.
+    ///
+    /// Returns `None` until [`SteamAchievements::request_global_percentages`] has
+    /// completed successfully, or if the achievement does not exist.
+    pub fn global_percent(&self, name: &str) -> Option<f32> {
+        self.global_percentages.get(name).copied()
+    }
+
+    /// When the global achievement percentages were last fetched, or `None` if
+    /// they have never been fetched.
+    pub fn global_percentages_fetched_at(&self) -> Option<Instant> {
+        self.global_fetched_at
+    }
+
+    /// Gets the localized name and description of an achievement, along with
+    /// whether it is hidden and unlocked.
+    ///
+    /// Returns `None` if the user's stats have not been received yet, the
+    /// achievement does not exist, or Steam is shutting down.
+    pub fn display_info(&self, name: &str) -> Option<AchievementDisplayInfo> {
+        if !self.ready || self.client.is_shutting_down() {
+            return None;
+        }
+        let achievement = self.client.user_stats().achievement(name);
+        let unlocked = achievement.get().ok()?;
+        let attribute = |key| {
+            achievement
+                .get_achievement_display_attribute(key)
+                .map(str::to_owned)
+                .ok()
+        };
+        Some(AchievementDisplayInfo {
+            display_name: attribute("name")?,
+            description: attribute("desc")?,
+            hidden: attribute("hidden").as_deref() == Some("1"),
+            unlocked,
+        })
+    }
+
+    /// Iterates over the API names of every achievement defined for the app.
+    ///
+    /// This is empty until the user's stats have been received, and once Steam is
+    /// shutting down.
+    pub fn iter_all(&self) -> impl Iterator<Item = String> {
+        (self.ready && !self.client.is_shutting_down())
+            .then(|| self.client.user_stats().get_achievement_names())
+            .flatten()
+            .unwrap_or_default()
+            .into_iter()
+    }
+
+    fn set(&mut self, name: String, unlocked: bool) -> Result<(), ShuttingDown> {
+        self.client.ensure_running()?;
+        if !self.ready {
+            self.queued.push((name, unlocked));
+            return Ok(());
+        }
+        self.apply(&name, unlocked);
+        Ok(())
+    }
+
+    fn apply(&mut self, name: &str, unlocked: bool) {
+        let achievement = self.client.user_stats().achievement(name);
+        let result = if unlocked {
+            achievement.set()
+        } else {
+            achievement.clear()
+        };
+        if result.is_err() {
+            warn!("Failed to update Steam achievement {:?}", name);
+            return;
+        }
+        self.dirty = true;
+        if !self.client.user().logged_on() {
+            defer_write(&mut self.deferred, name, unlocked);
+        }
+        #[cfg(feature = "bevy_render")]
+        self.icons.invalidate(name);
+    }
+
+    /// Reapplies the changes made while disconnected so they are stored with the
+    /// next batch, returning how many there were.
+    pub(super) fn replay_deferred(&mut self) -> usize {
+        let deferred = std::mem::take(&mut self.deferred);
+        for (name, unlocked) in &deferred {
+            self.apply(name, *unlocked);
+        }
+        deferred.len()
+    }
+
+    /// Discards local state after the user's stats were reset.
+    #[cfg(feature = "dev-tools")]
+    pub(super) fn on_stats_reset(&mut self) {
+        self.ready = false;
+        self.dirty = false;
+        self.progress.clear();
+        #[cfg(feature = "bevy_render")]
+        self.icons.invalidate_all();
+    }
+
+    pub(super) fn on_stats_received(&mut self) {
+        self.ready = true;
+        for (name, unlocked) in std::mem::take(&mut self.queued) {
+            self.apply(&name, unlocked);
+        }
+    }
+}
+
+pub(super) fn flush_global_percentages(
+    mut achievements: ResMut<SteamAchievements>,
+    mut received: EventWriter<GlobalAchievementPercentagesReceived>,
+) {
+    for result in achievements.global_results.drain() {
+        if result.is_ok() {
+            let user_stats = achievements.client.user_stats();
+            let percentages = user_stats
+                .get_achievement_names()
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|name| {
+                    let percent = user_stats
+                        .achievement(&name)
+                        .get_achievement_achieved_percent()
+                        .ok()?;
+                    Some((name, percent))
+                })
+                .collect();
+            achievements.global_percentages = percentages;
+            achievements.global_fetched_at = Some(Instant::now());
+        }
+        received.send(GlobalAchievementPercentagesReceived { result });
+    }
+}
@@ -0,0 +1,28 @@
+use bevy_ecs::event::Event;
+use bevy_log::warn;
+
+/// The most writes kept per resource while Steam is disconnected.
+const MAX_DEFERRED_WRITES: usize = 256;
+
+/// Sent when stat and achievement writes made while disconnected from Steam have
+/// been replayed after reconnecting.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct DeferredStatsFlushed {
+    /// The number of distinct stats and achievements that were replayed.
+    pub count: usize,
+}
+
+/// Records a write made while disconnected, replacing any earlier write to the
+/// same stat or achievement.
+pub(super) fn defer_write<T>(queue: &mut Vec<(String, T)>, name: &str, value: T) {
+    if let Some(index) = queue.iter().position(|(queued, _)| queued == name) {
+        queue.remove(index);
+    } else if queue.len() >= MAX_DEFERRED_WRITES {
+        let (evicted, _) = queue.remove(0);
+        warn!(
+            "Too many Steam stat writes while disconnected, dropping the write to {:?}",
+            evicted
+        );
+    }
+    queue.push((name.to_owned(), value));
+}
@@ -0,0 +1,360 @@
+use std::{ops::Range, sync::Arc};
+
+use bevy_ecs::{
+    event::{Event, EventWriter},
+    system::{ResMut, Resource},
+};
+use bevy_utils::{HashMap, HashSet};
+use steamworks::{
+    Leaderboard, LeaderboardDataRequest, LeaderboardDisplayType, LeaderboardEntry,
+    LeaderboardScoreUploaded, LeaderboardSortMethod, SteamError, UploadScoreMethod,
+};
+
+use crate::{call_result::CallResults, Client, ShuttingDown};
+
+/// Sent when a [`SteamLeaderboards::find`] or [`SteamLeaderboards::find_or_create`]
+/// request completes.
+#[derive(Event, Debug)]
+pub struct LeaderboardFound {
+    /// The name of the leaderboard.
+    pub name: String,
+    /// The leaderboard handle. [`SteamError::FileNotFound`] if the leaderboard
+    /// does not exist.
+    pub result: Result<Leaderboard, SteamError>,
+}
+
+/// Sent when a [`SteamLeaderboards::upload`] request completes.
+#[derive(Event, Debug)]
+pub struct ScoreUploaded {
+    /// The name of the leaderboard.
+    pub name: String,
+    /// Whether the score changed and the user's new global rank.
+    pub result: Result<LeaderboardScoreUploaded, SteamError>,
+}
+
+/// Sent when a [`SteamLeaderboards::download`] request completes.
+#[derive(Event, Debug)]
+pub struct LeaderboardEntriesDownloaded {
+    /// The name of the leaderboard.
+    pub name: String,
+    /// The ID returned by the [`SteamLeaderboards::download`] call.
+    pub request_id: u64,
+    /// The downloaded entries, including their details. Empty if the leaderboard
+    /// has no entries in the requested range.
+    pub entries: Result<Vec<LeaderboardEntry>, SteamError>,
+}
+
+struct PendingUpload {
+    name: String,
+    score: i32,
+    method: UploadScoreMethod,
+    /// Shared with the upload's callback, which holds on to it for retries.
+    details: Arc<[i32]>,
+    /// Set when the upload used a cached handle, which may have gone stale.
+    retry: bool,
+}
+
+struct PendingDownload {
+    name: String,
+    request_id: u64,
+    request: LeaderboardDataRequest,
+    range: Range<usize>,
+    max_details: usize,
+}
+
+/// A request waiting for its leaderboard to be found.
+enum PendingRequest {
+    Upload(PendingUpload),
+    Download(PendingDownload),
+}
+
+impl PendingRequest {
+    fn name(&self) -> &str {
+        match self {
+            Self::Upload(upload) => &upload.name,
+            Self::Download(download) => &download.name,
+        }
+    }
+}
+
+enum LeaderboardResult {
+    Found(String, Result<Option<Leaderboard>, SteamError>),
+    Uploaded(
+        PendingUpload,
+        Result<Option<LeaderboardScoreUploaded>, SteamError>,
+    ),
+    Downloaded(PendingDownload, Result<Vec<LeaderboardEntry>, SteamError>),
+}
+
+/// Finds leaderboards and caches their handles by name.
+///
+/// Every request completes with a Bevy event instead of a closure. Once a
+/// leaderboard has been found, later requests for it complete on the next frame
+/// without another round trip to Steam.
+#[derive(Resource)]
+pub struct SteamLeaderboards {
+    client: Client,
+    cache: HashMap<String, Leaderboard>,
+    /// Leaderboards with a find in flight.
+    finding: HashSet<String>,
+    results: CallResults<LeaderboardResult>,
+    local: Vec<LeaderboardResult>,
+    awaiting: Vec<PendingRequest>,
+    next_request_id: u64,
+}
+
+impl SteamLeaderboards {
+    pub(super) fn new(client: &Client) -> Self {
+        Self {
+            client: client.clone(),
+            cache: HashMap::default(),
+            finding: HashSet::default(),
+            results: CallResults::new(),
+            local: Vec::new(),
+            awaiting: Vec::new(),
+            next_request_id: 0,
+        }
+    }
+
+    /// Gets the handle of a leaderboard that has already been found.
+    pub fn get(&self, name: &str) -> Option<&Leaderboard> {
+        self.cache.get(name)
+    }
+
+    /// Finds an existing leaderboard by name.
+    ///
+    /// Completes with a [`LeaderboardFound`] event. If the leaderboard is already
+    /// being found, no other request is made and that request's event is sent.
+    pub fn find(&mut self, name: impl Into<String>) -> Result<(), ShuttingDown> {
+        self.client.ensure_running()?;
+        self.request_find(name.into());
+        Ok(())
+    }
+
+    fn request_find(&mut self, name: String) {
+        if self.complete_cached(&name) || self.finding.contains(&name) {
+            return;
+        }
+        let results = self.results.clone();
+        let found = name.clone();
+        self.client
+            .user_stats()
+            .find_leaderboard(&name, move |result| {
+                results.push(LeaderboardResult::Found(found, result));
+            });
+        self.finding.insert(name);
+    }
+
+    /// Finds a leaderboard by name, creating it with the given sort method and
+    /// display type if it doesn't exist.
+    ///
+    /// Completes with a [`LeaderboardFound`] event.
+    pub fn find_or_create(
+        &mut self,
+        name: impl Into<String>,
+        sort: LeaderboardSortMethod,
+        display: LeaderboardDisplayType,
+    ) -> Result<(), ShuttingDown> {
+        self.client.ensure_running()?;
+        let name = name.into();
+        if self.complete_cached(&name) {
+            return Ok(());
+        }
+        let results = self.results.clone();
+        let found = name.clone();
+        self.client
+            .user_stats()
+            .find_or_create_leaderboard(&name, sort, display, move |result| {
+                results.push(LeaderboardResult::Found(found, result));
+            });
+        self.finding.insert(name);
+        Ok(())
+    }
+
+    /// Uploads a score to a leaderboard, finding the leaderboard first if it
+    /// hasn't been found yet.
+    ///
+    /// If a cached handle is rejected by Steam, the leaderboard is found again
+    /// and the upload retried once. Completes with a [`ScoreUploaded`] event.
+    pub fn upload(
+        &mut self,
+        name: impl Into<String>,
+        score: i32,
+        method: UploadScoreMethod,
+        details: &[i32],
+    ) -> Result<(), ShuttingDown> {
+        self.client.ensure_running()?;
+        let mut upload = PendingUpload {
+            name: name.into(),
+            score,
+            method,
+            details: details.into(),
+            retry: true,
+        };
+        match self.cache.get(&upload.name) {
+            Some(leaderboard) => {
+                let leaderboard = leaderboard.clone();
+                self.start_upload(&leaderboard, upload);
+            }
+            None => {
+                upload.retry = false;
+                self.find_then(PendingRequest::Upload(upload));
+            }
+        }
+        Ok(())
+    }
+
+    /// Downloads a range of entries from a leaderboard, finding the leaderboard
+    /// first if it hasn't been found yet.
+    ///
+    /// Use [`LeaderboardDataRequest::Global`] for the top entries,
+    /// [`LeaderboardDataRequest::GlobalAroundUser`] for entries around the current
+    /// user, and [`LeaderboardDataRequest::Friends`] for the user's friends. Up to
+    /// `max_details` detail values are included with each entry.
+    ///
+    /// Returns an ID identifying the request, which completes with a
+    /// [`LeaderboardEntriesDownloaded`] event carrying the same ID.
+    pub fn download(
+        &mut self,
+        name: impl Into<String>,
+        request: LeaderboardDataRequest,
+        range: Range<usize>,
+        max_details: usize,
+    ) -> Result<u64, ShuttingDown> {
+        self.client.ensure_running()?;
+        let request_id = self.next_request_id;
+        self.next_request_id += 1;
+        let download = PendingDownload {
+            name: name.into(),
+            request_id,
+            request,
+            range,
+            max_details,
+        };
+        match self.cache.get(&download.name) {
+            Some(leaderboard) => {
+                let leaderboard = leaderboard.clone();
+                self.start_download(&leaderboard, download);
+            }
+            None => self.find_then(PendingRequest::Download(download)),
+        }
+        Ok(request_id)
+    }
+
+    fn find_then(&mut self, request: PendingRequest) {
+        let name = request.name().to_owned();
+        self.awaiting.push(request);
+        self.request_find(name);
+    }
+
+    fn start(&self, leaderboard: &Leaderboard, request: PendingRequest) {
+        match request {
+            PendingRequest::Upload(upload) => self.start_upload(leaderboard, upload),
+            PendingRequest::Download(download) => self.start_download(leaderboard, download),
+        }
+    }
+
+    fn start_upload(&self, leaderboard: &Leaderboard, upload: PendingUpload) {
+        let results = self.results.clone();
+        let details = upload.details.clone();
+        self.client.user_stats().upload_leaderboard_score(
+            leaderboard,
+            upload.method,
+            upload.score,
+            &details,
+            move |result| {
+                results.push(LeaderboardResult::Uploaded(upload, result));
+            },
+        );
+    }
+
+    fn start_download(&self, leaderboard: &Leaderboard, download: PendingDownload) {
+        let results = self.results.clone();
+        self.client.user_stats().download_leaderboard_entries(
+            leaderboard,
+            download.request,
+            download.range.start,
+            download.range.end,
+            download.max_details,
+            move |result| {
+                results.push(LeaderboardResult::Downloaded(download, result));
+            },
+        );
+    }
+
+    fn complete_cached(&mut self, name: &str) -> bool {
+        let Some(leaderboard) = self.cache.get(name) else {
+            return false;
+        };
+        let found = LeaderboardResult::Found(name.to_owned(), Ok(Some(leaderboard.clone())));
+        self.local.push(found);
+        true
+    }
+}
+
+pub(super) fn flush_leaderboard_results(
+    mut leaderboards: ResMut<SteamLeaderboards>,
+    mut found: EventWriter<LeaderboardFound>,
+    mut uploaded: EventWriter<ScoreUploaded>,
+    mut downloaded: EventWriter<LeaderboardEntriesDownloaded>,
+) {
+    let mut results = std::mem::take(&mut leaderboards.local);
+    results.extend(leaderboards.results.drain());
+    for result in results {
+        match result {
+            LeaderboardResult::Found(name, result) => {
+                let result =
+                    result.and_then(|leaderboard| leaderboard.ok_or(SteamError::FileNotFound));
+                leaderboards.finding.remove(&name);
+                if let Ok(leaderboard) = &result {
+                    leaderboards.cache.insert(name.clone(), leaderboard.clone());
+                }
+                let (ready, awaiting): (Vec<_>, Vec<_>) =
+                    std::mem::take(&mut leaderboards.awaiting)
+                        .into_iter()
+                        .partition(|request| request.name() == name);
+                leaderboards.awaiting = awaiting;
+                for request in ready {
+                    match (&result, request) {
+                        (Ok(leaderboard), request) => leaderboards.start(leaderboard, request),
+                        (Err(err), PendingRequest::Upload(upload)) => {
+                            uploaded.send(ScoreUploaded {
+                                name: upload.name,
+                                result: Err(err.clone()),
+                            });
+                        }
+                        (Err(err), PendingRequest::Download(download)) => {
+                            downloaded.send(LeaderboardEntriesDownloaded {
+                                name: download.name,
+                                request_id: download.request_id,
+                                entries: Err(err.clone()),
+                            });
+                        }
+                    }
+                }
+                found.send(LeaderboardFound { name, result });
+            }
+            LeaderboardResult::Uploaded(mut upload, result) => {
+                let result = result.and_then(|uploaded| uploaded.ok_or(SteamError::Generic));
+                if result.is_err() && upload.retry {
+                    // The cached handle may be stale, so find the leaderboard again.
+                    upload.retry = false;
+                    leaderboards.cache.remove(&upload.name);
+                    leaderboards.find_then(PendingRequest::Upload(upload));
+                    continue;
+                }
+                uploaded.send(ScoreUploaded {
+                    name: upload.name,
+                    result,
+                });
+            }
+            LeaderboardResult::Downloaded(download, entries) => {
+                downloaded.send(LeaderboardEntriesDownloaded {
+                    name: download.name,
+                    request_id: download.request_id,
+                    entries,
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use std::time::{Duration, Instant};
+
+use bevy_ecs::{
+    event::{Event, EventWriter},
+    system::{ResMut, Resource},
+};
+use steamworks::SteamError;
+
+use crate::{call_result::CallResults, Client, ShuttingDown};
+
+/// Sent when a [`CurrentPlayerCount::request`] request completes.
+#[derive(Event, Debug, Clone)]
+pub struct PlayerCountReceived {
+    /// The number of players currently playing the game.
+    pub result: Result<u32, SteamError>,
+}
+
+/// The number of players currently playing the game, as last reported by Steam.
+///
+/// The count is only fetched when [`CurrentPlayerCount::request`] is called, or
+/// periodically if [`CurrentPlayerCount::refresh_interval`] is set.
+#[derive(Resource)]
+pub struct CurrentPlayerCount {
+    client: Client,
+    count: Option<u32>,
+    stale: bool,
+    /// How often the count is refreshed automatically. `None` disables
+    /// automatic refreshes.
+    pub refresh_interval: Option<Duration>,
+    last_request: Option<Instant>,
+    results: CallResults<Result<u32, SteamError>>,
+}
+
+impl CurrentPlayerCount {
+    pub(super) fn new(client: &Client) -> Self {
+        Self {
+            client: client.clone(),
+            count: None,
+            stale: false,
+            refresh_interval: None,
+            last_request: None,
+            results: CallResults::new(),
+        }
+    }
+
+    /// Gets the last known player count, or `None` if it has never been received.
+    pub fn get(&self) -> Option<u32> {
+        self.count
+    }
+
+    /// Checks if the last refresh failed, in which case [`CurrentPlayerCount::get`]
+    /// returns the last known count.
+    pub fn is_stale(&self) -> bool {
+        self.stale
+    }
+
+    /// Requests the number of players currently playing the game.
+    ///
+    /// Completes with a [`PlayerCountReceived`] event and updates this resource.
+    pub fn request(&mut self) -> Result<(), ShuttingDown> {
+        self.client.ensure_running()?;
+        self.send_request();
+        Ok(())
+    }
+
+    fn send_request(&mut self) {
+        self.last_request = Some(Instant::now());
+        let results = self.results.clone();
+        self.client
+            .user_stats()
+            .get_number_of_current_players(move |result| {
+                results.push(result.map(|count| count.max(0) as u32));
+            });
+    }
+}
+
+pub(super) fn refresh_player_count(
+    mut player_count: ResMut<CurrentPlayerCount>,
+    mut received: EventWriter<PlayerCountReceived>,
+) {
+    for result in player_count.results.drain() {
+        match result {
+            Ok(count) => {
+                player_count.count = Some(count);
+                player_count.stale = false;
+            }
+            Err(_) => player_count.stale = true,
+        }
+        received.send(PlayerCountReceived { result });
+    }
+
+    let Some(interval) = player_count.refresh_interval else {
+        return;
+    };
+    let due = player_count
+        .last_request
+        .map_or(true, |last| last.elapsed() >= interval);
+    if due {
+        player_count.send_request();
+    }
+}
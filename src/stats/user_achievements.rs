@@ -0,0 +1,117 @@
+use std::{ffi::CString, time::Instant};
+
+use bevy_ecs::event::Event;
+use steamworks::{sys, SteamId};
+
+use super::{StatsError, SteamStats};
+
+/// The state of another user's stats and achievements, as returned by
+/// [`SteamStats::achievements_for`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserStatsState {
+    /// The user's stats have been requested but have not arrived yet.
+    Pending,
+    /// The user's stats have arrived and can be read.
+    Ready,
+    /// Steam refused to provide the user's stats, such as because of their
+    /// privacy settings, or did not respond within [`SteamStats::user_stats_timeout`].
+    Unavailable,
+}
+
+/// Sent when another user's stats could not be received. See
+/// [`UserStatsState::Unavailable`].
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UserStatsUnavailable(pub SteamId);
+
+impl SteamStats {
+    /// Gets the state of another user's stats and achievements, requesting them
+    /// if they haven't been already.
+    ///
+    /// Once [`UserStatsState::Ready`], achievements can be read with
+    /// [`SteamStats::is_unlocked_for`] and [`SteamStats::unlock_time_for`]. If Steam
+    /// unloads the user's stats, they are requested again on the next call. Stats
+    /// that haven't been received are [`UserStatsState::Unavailable`] once Steam is
+    /// shutting down.
+    pub fn achievements_for(&mut self, steam_id: SteamId) -> UserStatsState {
+        if self.users.contains_key(&steam_id) {
+            UserStatsState::Ready
+        } else if self.unavailable.contains(&steam_id) {
+            UserStatsState::Unavailable
+        } else {
+            if !self.requests.contains_key(&steam_id) && self.request_for(steam_id).is_err() {
+                return UserStatsState::Unavailable;
+            }
+            UserStatsState::Pending
+        }
+    }
+
+    /// Checks if another user has unlocked an achievement.
+    ///
+    /// Fails with [`StatsError::StatsNotReady`] until the user's stats have been
+    /// received with [`SteamStats::achievements_for`].
+    pub fn is_unlocked_for(&self, steam_id: SteamId, name: &str) -> Result<bool, StatsError> {
+        self.user_achievement(steam_id, name)
+            .map(|(unlocked, _)| unlocked)
+    }
+
+    /// Gets when another user unlocked an achievement, as a Unix timestamp.
+    ///
+    /// Returns `None` if the achievement is locked, or if Steam has no record of
+    /// when it was unlocked.
+    pub fn unlock_time_for(
+        &self,
+        steam_id: SteamId,
+        name: &str,
+    ) -> Result<Option<u32>, StatsError> {
+        self.user_achievement(steam_id, name)
+            .map(|(unlocked, time)| (unlocked && time != 0).then_some(time))
+    }
+
+    fn user_achievement(&self, steam_id: SteamId, name: &str) -> Result<(bool, u32), StatsError> {
+        self.client.ensure_running()?;
+        if !self.users.contains_key(&steam_id) {
+            return Err(StatsError::StatsNotReady);
+        }
+        let invalid = || StatsError::InvalidStat(name.to_owned());
+        let c_name = CString::new(name).map_err(|_| invalid())?;
+        let mut unlocked = false;
+        let mut unlock_time = 0;
+        // steamworks does not wrap GetUserAchievementAndUnlockTime, so it is called directly.
+        // SAFETY: The Steam API is initialized for as long as a `Client` exists.
+        let found = unsafe {
+            sys::SteamAPI_ISteamUserStats_GetUserAchievementAndUnlockTime(
+                sys::SteamAPI_SteamUserStats_v012(),
+                steam_id.raw(),
+                c_name.as_ptr(),
+                &mut unlocked,
+                &mut unlock_time,
+            )
+        };
+        if !found {
+            return Err(invalid());
+        }
+        Ok((unlocked, unlock_time))
+    }
+
+    /// Gives up on requests for other users' stats that have gone unanswered for
+    /// longer than [`SteamStats::user_stats_timeout`], returning their users.
+    pub(super) fn expire_user_requests(&mut self) -> Vec<SteamId> {
+        let timeout = self.user_stats_timeout;
+        let now = Instant::now();
+        let mut expired = Vec::new();
+        self.requests.retain(|steam_id, requested| {
+            let keep = now.duration_since(*requested) < timeout;
+            if !keep {
+                expired.push(*steam_id);
+            }
+            keep
+        });
+        self.unavailable.extend(expired.iter().copied());
+        expired
+    }
+
+    /// Marks another user's stats as unavailable after Steam failed to provide them.
+    pub(super) fn on_user_stats_failed(&mut self, steam_id: SteamId) -> bool {
+        self.requests.remove(&steam_id).is_some() && self.unavailable.insert(steam_id)
+    }
+}
@@ -0,0 +1,472 @@
+use std::{
+    ffi::{c_void, CString},
+    fmt,
+    time::{Duration, Instant},
+};
+
+use bevy_ecs::{
+    change_detection::DetectChangesMut,
+    event::Event,
+    system::{Res, ResMut, Resource},
+};
+use bevy_log::warn;
+use bevy_time::Time;
+use bevy_utils::{HashMap, HashSet};
+#[cfg(feature = "dev-tools")]
+use steamworks::SteamError;
+use steamworks::{sys, Callback, SteamId};
+
+use crate::{call_result::CallResults, shutdown::RegisteredCallback, Client, ShuttingDown};
+
+use super::deferred::defer_write;
+
+/// The value of a Steam stat.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StatValue {
+    /// An integer stat.
+    I32(i32),
+    /// A floating point stat.
+    F32(f32),
+}
+
+/// Sent whenever a stat's value changes through [`SteamStats`].
+///
+/// Systems that display stats can read this instead of polling every frame.
+#[derive(Event, Debug, Clone)]
+pub struct SteamStatChanged {
+    /// The API name of the stat.
+    pub name: String,
+    /// The new value of the stat.
+    pub value: StatValue,
+}
+
+/// Sent when Steam has delivered a user's stats, either the current user's or
+/// those requested with [`SteamStats::request_for`].
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UserStatsReady(pub SteamId);
+
+/// Sent when a [`SteamStats::reset_all`] request completes.
+#[cfg(feature = "dev-tools")]
+#[derive(Event, Debug, Clone)]
+pub struct SteamStatsReset {
+    /// Whether achievements were reset as well as stats.
+    pub achievements_too: bool,
+    /// The result of the reset.
+    pub result: Result<(), SteamError>,
+}
+
+/// `UserStatsUnloaded_t`, which steamworks does not wrap.
+struct UserStatsUnloaded(SteamId);
+
+unsafe impl Callback for UserStatsUnloaded {
+    const ID: i32 = 1108;
+    const SIZE: i32 = std::mem::size_of::<sys::UserStatsUnloaded_t>() as i32;
+
+    unsafe fn from_raw(raw: *mut c_void) -> Self {
+        let val = &mut *(raw as *mut sys::UserStatsUnloaded_t);
+        Self(SteamId::from_raw(val.m_steamIDUser.m_steamid.m_unAll64Bits))
+    }
+}
+
+/// An error from reading or writing a stat via [`SteamStats`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StatsError {
+    /// Steam has not delivered the current user's stats yet.
+    StatsNotReady,
+    /// The stat does not exist or has a different type.
+    InvalidStat(String),
+    /// Steam is shutting down.
+    ShuttingDown,
+}
+
+impl fmt::Display for StatsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::StatsNotReady => f.write_str("Steam stats have not been received yet"),
+            Self::InvalidStat(name) => write!(f, "{name:?} is not a valid stat of this type"),
+            Self::ShuttingDown => write!(f, "{ShuttingDown}"),
+        }
+    }
+}
+
+impl std::error::Error for StatsError {}
+
+impl From<ShuttingDown> for StatsError {
+    fn from(_: ShuttingDown) -> Self {
+        Self::ShuttingDown
+    }
+}
+
+/// Reads and writes the current user's stats.
+///
+/// Steam's stats are requested when the plugin is built. Until they arrive, every
+/// accessor returns [`StatsError::StatsNotReady`]. Writes are persisted together with
+/// any achievement changes in a single call to `store_stats` at the end of the frame,
+/// and every change is announced with a [`SteamStatChanged`] event. Writes made
+/// while disconnected from Steam are replayed once it reconnects, followed by a
+/// [`DeferredStatsFlushed`] event.
+///
+/// [`DeferredStatsFlushed`]: crate::DeferredStatsFlushed
+#[derive(Resource)]
+pub struct SteamStats {
+    client: Client,
+    ready: bool,
+    cache: HashMap<String, StatValue>,
+    pub(super) changed: Vec<SteamStatChanged>,
+    pub(super) dirty: bool,
+    /// Set while a `store_stats` call is waiting for `UserStatsStored`.
+    pub(super) storing: bool,
+    deferred: Vec<(String, StatValue)>,
+    /// Whether to block on [`AppExit`] until Steam confirms that pending stat and
+    /// achievement changes were stored, for at most [`SteamStats::exit_timeout`].
+    ///
+    /// [`AppExit`]: bevy_app::AppExit
+    pub block_on_exit: bool,
+    /// How long to wait for Steam to confirm pending changes when exiting.
+    pub exit_timeout: Duration,
+    pub(super) users: HashMap<SteamId, HashMap<String, StatValue>>,
+    /// When each pending request for another user's stats was made.
+    pub(super) requests: HashMap<SteamId, Instant>,
+    /// Users whose stats Steam did not provide.
+    pub(super) unavailable: HashSet<SteamId>,
+    /// How long to wait for another user's stats before treating them as
+    /// unavailable. Defaults to 10 seconds.
+    pub user_stats_timeout: Duration,
+    /// Seconds elapsed since the plugin was built.
+    elapsed: f64,
+    /// When each average rate stat was last flushed, in seconds since the plugin was built.
+    avg_rate_flushed: HashMap<String, f64>,
+    #[cfg(feature = "dev-tools")]
+    pub(super) resets: Vec<SteamStatsReset>,
+    unloaded: CallResults<SteamId>,
+    _unloaded_callback: RegisteredCallback,
+}
+
+impl SteamStats {
+    pub(super) fn new(client: &Client) -> Self {
+        let unloaded = CallResults::new();
+        let unloaded_in = unloaded.clone();
+        let unloaded_callback = RegisteredCallback::new(client.register_callback(
+            move |UserStatsUnloaded(steam_id)| {
+                unloaded_in.push(steam_id);
+            },
+        ));
+        Self {
+            client: client.clone(),
+            ready: false,
+            cache: HashMap::default(),
+            changed: Vec::new(),
+            dirty: false,
+            storing: false,
+            deferred: Vec::new(),
+            block_on_exit: true,
+            exit_timeout: Duration::from_secs(2),
+            users: HashMap::default(),
+            requests: HashMap::default(),
+            unavailable: HashSet::default(),
+            user_stats_timeout: Duration::from_secs(10),
+            elapsed: 0.0,
+            avg_rate_flushed: HashMap::default(),
+            #[cfg(feature = "dev-tools")]
+            resets: Vec::new(),
+            unloaded,
+            _unloaded_callback: unloaded_callback,
+        }
+    }
+
+    /// Checks if Steam has delivered the current user's stats.
+    pub fn is_ready(&self) -> bool {
+        self.ready
+    }
+
+    /// Gets the value of an integer stat.
+    pub fn get_i32(&self, name: &str) -> Result<i32, StatsError> {
+        self.check_ready()?;
+        match self.cache.get(name) {
+            Some(StatValue::I32(value)) => Ok(*value),
+            Some(StatValue::F32(_)) => Err(StatsError::InvalidStat(name.to_owned())),
+            None => self
+                .client
+                .user_stats()
+                .get_stat_i32(name)
+                .map_err(|_| StatsError::InvalidStat(name.to_owned())),
+        }
+    }
+
+    /// Gets the value of a floating point stat.
+    pub fn get_f32(&self, name: &str) -> Result<f32, StatsError> {
+        self.check_ready()?;
+        match self.cache.get(name) {
+            Some(StatValue::F32(value)) => Ok(*value),
+            Some(StatValue::I32(_)) => Err(StatsError::InvalidStat(name.to_owned())),
+            None => self
+                .client
+                .user_stats()
+                .get_stat_f32(name)
+                .map_err(|_| StatsError::InvalidStat(name.to_owned())),
+        }
+    }
+
+    /// Sets the value of an integer stat.
+    pub fn set_i32(&mut self, name: &str, value: i32) -> Result<(), StatsError> {
+        self.check_ready()?;
+        if self.get_i32(name)? == value {
+            return Ok(());
+        }
+        self.client
+            .user_stats()
+            .set_stat_i32(name, value)
+            .map_err(|_| StatsError::InvalidStat(name.to_owned()))?;
+        self.update(name, StatValue::I32(value));
+        Ok(())
+    }
+
+    /// Sets the value of a floating point stat.
+    pub fn set_f32(&mut self, name: &str, value: f32) -> Result<(), StatsError> {
+        self.check_ready()?;
+        if self.get_f32(name)? == value {
+            return Ok(());
+        }
+        self.client
+            .user_stats()
+            .set_stat_f32(name, value)
+            .map_err(|_| StatsError::InvalidStat(name.to_owned()))?;
+        self.update(name, StatValue::F32(value));
+        Ok(())
+    }
+
+    /// Adds `delta` to an integer stat, returning the new value.
+    pub fn add_i32(&mut self, name: &str, delta: i32) -> Result<i32, StatsError> {
+        let value = self.get_i32(name)?.saturating_add(delta);
+        self.set_i32(name, value)?;
+        Ok(value)
+    }
+
+    /// Updates an average rate stat, such as "points per hour".
+    ///
+    /// `count_this_session` is how much the stat's numerator grew over a session
+    /// lasting `session_length`. Steam computes the new rate, which can then be
+    /// read with [`SteamStats::get_f32`]. The update is stored with every other
+    /// change at the end of the frame.
+    pub fn update_avg_rate(
+        &mut self,
+        name: &str,
+        count_this_session: f32,
+        session_length: f64,
+    ) -> Result<(), StatsError> {
+        self.check_ready()?;
+        let invalid = || StatsError::InvalidStat(name.to_owned());
+        let c_name = CString::new(name).map_err(|_| invalid())?;
+        // steamworks does not wrap UpdateAvgRateStat, so it is called directly.
+        // SAFETY: The Steam API is initialized for as long as a `Client` exists.
+        let updated = unsafe {
+            sys::SteamAPI_ISteamUserStats_UpdateAvgRateStat(
+                sys::SteamAPI_SteamUserStats_v012(),
+                c_name.as_ptr(),
+                count_this_session,
+                session_length,
+            )
+        };
+        if !updated {
+            return Err(invalid());
+        }
+        let value = self
+            .client
+            .user_stats()
+            .get_stat_f32(name)
+            .map_err(|_| invalid())?;
+        self.update(name, StatValue::F32(value));
+        Ok(())
+    }
+
+    /// Updates an average rate stat with the time elapsed since it was last
+    /// flushed, or since the plugin was built if it never was.
+    ///
+    /// The session length is measured in seconds using Bevy's [`Time`], so the
+    /// stat's window in the Steamworks partner site should be set in seconds too.
+    pub fn flush_avg_rate(
+        &mut self,
+        name: &str,
+        count_this_session: f32,
+    ) -> Result<(), StatsError> {
+        let last_flushed = self.avg_rate_flushed.get(name).copied().unwrap_or(0.0);
+        let session_length = self.elapsed - last_flushed;
+        self.update_avg_rate(name, count_this_session, session_length)?;
+        self.avg_rate_flushed.insert(name.to_owned(), self.elapsed);
+        Ok(())
+    }
+
+    /// Requests the stats of another user.
+    ///
+    /// Once they arrive, a [`UserStatsReady`] event is sent and they can be read
+    /// with [`SteamStats::get_i32_for`] and [`SteamStats::get_f32_for`].
+    pub fn request_for(&mut self, steam_id: SteamId) -> Result<(), ShuttingDown> {
+        self.client.ensure_running()?;
+        self.users.remove(&steam_id);
+        self.unavailable.remove(&steam_id);
+        self.requests.insert(steam_id, Instant::now());
+        self.client.user_stats().request_user_stats(steam_id.raw());
+        Ok(())
+    }
+
+    /// Gets the value of another user's integer stat.
+    ///
+    /// Fails with [`StatsError::StatsNotReady`] until the user's stats have been
+    /// received, or once Steam has unloaded them to make room for others.
+    pub fn get_i32_for(&mut self, steam_id: SteamId, name: &str) -> Result<i32, StatsError> {
+        match self.get_for(steam_id, name, false)? {
+            StatValue::I32(value) => Ok(value),
+            StatValue::F32(_) => Err(StatsError::InvalidStat(name.to_owned())),
+        }
+    }
+
+    /// Gets the value of another user's floating point stat.
+    ///
+    /// Fails with [`StatsError::StatsNotReady`] until the user's stats have been
+    /// received, or once Steam has unloaded them to make room for others.
+    pub fn get_f32_for(&mut self, steam_id: SteamId, name: &str) -> Result<f32, StatsError> {
+        match self.get_for(steam_id, name, true)? {
+            StatValue::F32(value) => Ok(value),
+            StatValue::I32(_) => Err(StatsError::InvalidStat(name.to_owned())),
+        }
+    }
+
+    fn get_for(
+        &mut self,
+        steam_id: SteamId,
+        name: &str,
+        float: bool,
+    ) -> Result<StatValue, StatsError> {
+        self.client.ensure_running()?;
+        let stats = self
+            .users
+            .get_mut(&steam_id)
+            .ok_or(StatsError::StatsNotReady)?;
+        if let Some(value) = stats.get(name) {
+            return Ok(*value);
+        }
+        let c_name = CString::new(name).map_err(|_| StatsError::InvalidStat(name.to_owned()))?;
+        // steamworks does not wrap GetUserStat, so it is called directly.
+        // SAFETY: The Steam API is initialized for as long as a `Client` exists.
+        let value = unsafe {
+            let user_stats = sys::SteamAPI_SteamUserStats_v012();
+            if float {
+                let mut value = 0.0;
+                sys::SteamAPI_ISteamUserStats_GetUserStatFloat(
+                    user_stats,
+                    steam_id.raw(),
+                    c_name.as_ptr(),
+                    &mut value,
+                )
+                .then_some(StatValue::F32(value))
+            } else {
+                let mut value = 0;
+                sys::SteamAPI_ISteamUserStats_GetUserStatInt32(
+                    user_stats,
+                    steam_id.raw(),
+                    c_name.as_ptr(),
+                    &mut value,
+                )
+                .then_some(StatValue::I32(value))
+            }
+        };
+        let value = value.ok_or_else(|| StatsError::InvalidStat(name.to_owned()))?;
+        stats.insert(name.to_owned(), value);
+        Ok(value)
+    }
+
+    /// Resets all of the current user's stats, and optionally their achievements,
+    /// on Steam's servers.
+    ///
+    /// **This is a development tool.** It is meant for resetting test accounts and
+    /// should not be reachable from a shipped game. Stats are requested again
+    /// afterwards, so reads fail with [`StatsError::StatsNotReady`] until the reset
+    /// values arrive. Completes with a [`SteamStatsReset`] event.
+    #[cfg(feature = "dev-tools")]
+    pub fn reset_all(&mut self, achievements_too: bool) -> Result<(), ShuttingDown> {
+        self.client.ensure_running()?;
+        let user_stats = self.client.user_stats();
+        let result = user_stats
+            .reset_all_stats(achievements_too)
+            .map_err(|_| SteamError::Generic);
+        if result.is_ok() {
+            self.ready = false;
+            self.cache.clear();
+            self.changed.clear();
+            self.dirty = false;
+            user_stats.request_current_stats();
+        }
+        self.resets.push(SteamStatsReset {
+            achievements_too,
+            result,
+        });
+        Ok(())
+    }
+
+    fn check_ready(&self) -> Result<(), StatsError> {
+        self.client.ensure_running()?;
+        if self.ready {
+            Ok(())
+        } else {
+            Err(StatsError::StatsNotReady)
+        }
+    }
+
+    fn update(&mut self, name: &str, value: StatValue) {
+        if !self.client.user().logged_on() {
+            defer_write(&mut self.deferred, name, value);
+        }
+        self.cache.insert(name.to_owned(), value);
+        self.changed.push(SteamStatChanged {
+            name: name.to_owned(),
+            value,
+        });
+        self.dirty = true;
+    }
+
+    pub(super) fn on_stats_received(&mut self) {
+        self.ready = true;
+        // Steam's values are authoritative once received.
+        self.cache.clear();
+    }
+
+    /// Reapplies the writes made while disconnected so they are stored with the
+    /// next batch, returning how many there were.
+    pub(super) fn replay_deferred(&mut self) -> usize {
+        let deferred = std::mem::take(&mut self.deferred);
+        let user_stats = self.client.user_stats();
+        for (name, value) in &deferred {
+            let result = match *value {
+                StatValue::I32(value) => user_stats.set_stat_i32(name, value),
+                StatValue::F32(value) => user_stats.set_stat_f32(name, value),
+            };
+            if result.is_err() {
+                warn!("Failed to replay the write to Steam stat {:?}", name);
+            }
+        }
+        if !deferred.is_empty() {
+            self.dirty = true;
+        }
+        deferred.len()
+    }
+
+    pub(super) fn on_user_stats_received(&mut self, steam_id: SteamId) {
+        self.requests.remove(&steam_id);
+        self.unavailable.remove(&steam_id);
+        self.users.insert(steam_id, HashMap::default());
+    }
+
+    /// Forgets the stats of users that Steam has unloaded.
+    pub(super) fn flush_unloaded(&mut self) {
+        for steam_id in self.unloaded.drain() {
+            self.users.remove(&steam_id);
+        }
+    }
+}
+
+pub(super) fn track_session_time(time: Option<Res<Time>>, mut stats: ResMut<SteamStats>) {
+    if let Some(time) = time {
+        // Advancing the clock isn't a change anyone needs to react to.
+        stats.bypass_change_detection().elapsed += time.delta_seconds_f64();
+    }
+}
@@ -0,0 +1,134 @@
+//! Utilities for testing systems that handle Steam events.
+//!
+//! Enabled with the `test-utils` feature.
+//!
+//! Tests that need a real Steam client can build their app with [`steam_app`],
+//! which skips them unless the `STEAM_TESTS` environment variable is set to `1`.
+
+use std::time::Duration;
+
+use bevy_app::App;
+use bevy_time::TimePlugin;
+
+use crate::{SteamCallbacks, SteamworksEvent, SteamworksPlugin};
+
+/// The app ID of Spacewar, Valve's example game, which any Steam account can run.
+pub const SPACEWAR_APP_ID: u32 = 480;
+
+/// How long [`pump_until`] waits between frames, giving Steam time to respond.
+const FRAME_INTERVAL: Duration = Duration::from_millis(16);
+
+/// Checks if tests that need a running Steam client are enabled, by setting the
+/// `STEAM_TESTS` environment variable to `1`.
+pub fn steam_tests_enabled() -> bool {
+    std::env::var("STEAM_TESTS").is_ok_and(|value| value == "1")
+}
+
+/// Builds a headless [`App`] with [`SteamworksPlugin`] initialized as Spacewar,
+/// or returns `None` if [`steam_tests_enabled`] is `false` so the test can be
+/// skipped.
+///
+/// Steam only allows one client per process, so tests using this should not run
+/// at the same time as each other.
+///
+/// ```rust no_run
+/// use bevy_steamworks::test;
+///
+/// let Some(mut app) = test::steam_app() else {
+///     return;
+/// };
+/// assert!(test::pump_until(&mut app, 300, |app| {
+///     app.world().contains_resource::<bevy_steamworks::Client>()
+/// }));
+/// ```
+///
+/// # Panics
+///
+/// Panics if Steam tests are enabled but Steam could not be initialized, such
+/// as when Steam is not running.
+pub fn steam_app() -> Option<App> {
+    if !steam_tests_enabled() {
+        return None;
+    }
+    let plugin = SteamworksPlugin::init_app(SPACEWAR_APP_ID)
+        .expect("STEAM_TESTS is set, but Steam could not be initialized");
+    let mut app = App::new();
+    app.add_plugins((TimePlugin, plugin));
+    Some(app)
+}
+
+/// Updates `app` until `done` returns `true`, for at most `max_frames` frames.
+///
+/// Frames are spaced a little apart to give Steam time to respond. Returns
+/// whether `done` returned `true`.
+pub fn pump_until(
+    app: &mut App,
+    max_frames: usize,
+    mut done: impl FnMut(&mut App) -> bool,
+) -> bool {
+    for _ in 0..max_frames {
+        app.update();
+        if done(app) {
+            return true;
+        }
+        std::thread::sleep(FRAME_INTERVAL);
+    }
+    false
+}
+
+/// Queues `event` as if Steam had sent it.
+///
+/// The event is pushed into the same buffer callbacks are, so it is sent on the
+/// next update in [`SteamworksSystem::RunCallbacks`](crate::SteamworksSystem::RunCallbacks),
+/// after any events Steam sends before then, and is subject to the same limits and
+/// coalescing.
+///
+/// The payloads of most variants have public fields and can be constructed
+/// directly. Those of [`SteamworksEvent::AuthSessionTicketResponse`] and
+/// [`SteamworksEvent::TicketForWebApiResponse`] hold an
+/// [`AuthTicket`](crate::AuthTicket), which can only come from a real ticket
+/// request.
+///
+/// ```rust no_run
+/// use bevy::prelude::*;
+/// use bevy_steamworks::*;
+///
+/// let mut app = App::new();
+/// app.add_plugins(SteamworksPlugin::init_app(480).unwrap());
+/// test::inject_event(
+///     &mut app,
+///     SteamworksEvent::GameOverlayActivated(GameOverlayActivated { active: true }),
+/// );
+/// app.update();
+/// ```
+///
+/// # Panics
+///
+/// Panics if [`SteamworksPlugin`](crate::SteamworksPlugin) has not been added to `app`,
+/// or was added with [`SteamworksPlugin::minimal`](crate::SteamworksPlugin::minimal).
+pub fn inject_event(app: &mut App, event: SteamworksEvent) {
+    inject_events(app, [event]);
+}
+
+/// Queues several events in order, as with [`inject_event`].
+///
+/// # Panics
+///
+/// Panics if [`SteamworksPlugin`](crate::SteamworksPlugin) has not been added to `app`,
+/// or was added with [`SteamworksPlugin::minimal`](crate::SteamworksPlugin::minimal).
+pub fn inject_events(app: &mut App, events: impl IntoIterator<Item = SteamworksEvent>) {
+    let callbacks = app
+        .world_mut()
+        .get_resource_mut::<SteamCallbacks>()
+        .expect("The SteamworksPlugin has not been added to the app");
+    let pending = callbacks
+        .pending
+        .as_ref()
+        .expect("SteamworksPlugin::minimal does not send events");
+    // SAFETY: Having the `App` mutably borrowed means no system, and so no
+    // callback, can be running. This cannot alias.
+    let pending = unsafe { &mut *pending.get() };
+    for event in events {
+        pending.push(event);
+    }
+}
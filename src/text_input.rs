@@ -0,0 +1,239 @@
+use std::fmt;
+
+use bevy_app::{App, First};
+use bevy_ecs::{
+    event::{Event, EventWriter},
+    query::With,
+    schedule::IntoSystemConfigs,
+    system::{Commands, ResMut, Resource},
+    world::World,
+};
+use bevy_math::Rect;
+use bevy_window::{PrimaryWindow, Window};
+use steamworks::{
+    sys, FloatingGamepadTextInputMode, GamepadTextInputDismissed, GamepadTextInputLineMode,
+    GamepadTextInputMode,
+};
+
+use crate::{
+    call_result::CallResults, shutdown::steam_running, Client, ShuttingDown, SteamworksSystem,
+};
+
+/// Sent when the floating keyboard opened by
+/// [`SteamTextInput::show_floating_keyboard`] is dismissed.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct FloatingKeyboardDismissed;
+
+/// Sent when the dialog opened by [`SteamTextInput::request_gamepad_text`] closes.
+#[derive(Event, Debug, Clone)]
+pub struct GamepadTextSubmitted {
+    /// The ID returned by [`SteamTextInput::request_gamepad_text`].
+    pub request_id: u64,
+    /// The text the user entered, or `None` if they cancelled.
+    pub text: Option<String>,
+}
+
+/// An error from [`SteamTextInput::request_gamepad_text`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GamepadTextInputError {
+    /// Another text input dialog is still open.
+    AlreadyOpen,
+    /// Steam could not show the dialog, such as when it is not running in Big
+    /// Picture mode.
+    Unavailable,
+    /// The description or existing text contains a nul character, which Steam
+    /// can't display.
+    InteriorNul,
+    /// Steam is shutting down.
+    ShuttingDown,
+}
+
+impl fmt::Display for GamepadTextInputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AlreadyOpen => f.write_str("a gamepad text input dialog is already open"),
+            Self::Unavailable => f.write_str("the gamepad text input dialog is unavailable"),
+            Self::InteriorNul => f.write_str("the gamepad text input contains a nul character"),
+            Self::ShuttingDown => write!(f, "{ShuttingDown}"),
+        }
+    }
+}
+
+impl std::error::Error for GamepadTextInputError {}
+
+impl From<ShuttingDown> for GamepadTextInputError {
+    fn from(_: ShuttingDown) -> Self {
+        Self::ShuttingDown
+    }
+}
+
+/// Shows Steam's on-screen keyboards, such as the one on the Steam Deck.
+#[derive(Resource)]
+pub struct SteamTextInput {
+    client: Client,
+    dismissed: CallResults<()>,
+    submitted: CallResults<(u64, Option<String>)>,
+    pending: Option<u64>,
+    next_request_id: u64,
+}
+
+impl SteamTextInput {
+    fn new(client: &Client) -> Self {
+        Self {
+            client: client.clone(),
+            dismissed: CallResults::new(),
+            submitted: CallResults::new(),
+            pending: None,
+            next_request_id: 0,
+        }
+    }
+
+    /// Shows the floating keyboard without covering `rect`, the area of the text
+    /// field being edited in logical window coordinates.
+    ///
+    /// `scale_factor` is the window's scale factor, used to convert `rect` into
+    /// the physical pixels Steam expects. Returns `false` if the keyboard could
+    /// not be shown, such as when Steam is not running in Big Picture mode or is
+    /// shutting down. Otherwise a [`FloatingKeyboardDismissed`] event is sent once
+    /// it closes.
+    pub fn show_floating_keyboard(
+        &self,
+        mode: FloatingGamepadTextInputMode,
+        rect: Rect,
+        scale_factor: f32,
+    ) -> bool {
+        if self.client.is_shutting_down() {
+            return false;
+        }
+        let min = (rect.min * scale_factor).round();
+        let size = (rect.size() * scale_factor).round();
+        let dismissed = self.dismissed.clone();
+        self.client.utils().show_floating_gamepad_text_input(
+            mode,
+            min.x as i32,
+            min.y as i32,
+            size.x as i32,
+            size.y as i32,
+            move || dismissed.push(()),
+        )
+    }
+
+    /// Shows the modal text input dialog, for entering a single value such as a
+    /// player name in Big Picture mode.
+    ///
+    /// Only one dialog can be open at a time. Returns an ID identifying the
+    /// request, which completes with a [`GamepadTextSubmitted`] event carrying the
+    /// same ID. Fails with [`GamepadTextInputError::InteriorNul`] if
+    /// `description` or `existing_text` contains a nul character.
+    pub fn request_gamepad_text(
+        &mut self,
+        mode: GamepadTextInputMode,
+        line_mode: GamepadTextInputLineMode,
+        description: &str,
+        max_chars: u32,
+        existing_text: Option<&str>,
+    ) -> Result<u64, GamepadTextInputError> {
+        self.client.ensure_running()?;
+        if self.pending.is_some() {
+            return Err(GamepadTextInputError::AlreadyOpen);
+        }
+        // steamworks converts both strings to C strings and panics on a nul.
+        if description.contains('\0') || existing_text.is_some_and(|text| text.contains('\0')) {
+            return Err(GamepadTextInputError::InteriorNul);
+        }
+        let request_id = self.next_request_id;
+        let submitted = self.submitted.clone();
+        let shown = self.client.utils().show_gamepad_text_input(
+            mode,
+            line_mode,
+            description,
+            max_chars,
+            existing_text,
+            move |dismissed| submitted.push((request_id, entered_text(&dismissed))),
+        );
+        if !shown {
+            return Err(GamepadTextInputError::Unavailable);
+        }
+        self.next_request_id += 1;
+        self.pending = Some(request_id);
+        Ok(request_id)
+    }
+}
+
+/// Reads the text entered into the gamepad text input dialog, which can only be
+/// done while handling the callback for it being dismissed.
+///
+/// steamworks never releases the callback, so it reads the text directly rather
+/// than through a [`Client`], which would otherwise be held forever and keep Steam
+/// from shutting down.
+fn entered_text(dismissed: &GamepadTextInputDismissed) -> Option<String> {
+    let len = dismissed.submitted_text_len?;
+    let mut text = vec![0u8; len as usize];
+    // SAFETY: The Steam API is initialized while callbacks are being run, and `text`
+    // outlives the call.
+    let read = unsafe {
+        sys::SteamAPI_ISteamUtils_GetEnteredGamepadTextInput(
+            sys::SteamAPI_SteamUtils_v010(),
+            text.as_mut_ptr().cast(),
+            len,
+        )
+    };
+    if !read {
+        return None;
+    }
+    // The length Steam reports includes the nul terminator.
+    if let Some(end) = text.iter().position(|&byte| byte == 0) {
+        text.truncate(end);
+    }
+    Some(String::from_utf8_lossy(&text).into_owned())
+}
+
+/// Extends [`Commands`] with Steam's on-screen keyboards.
+pub trait SteamTextInputCommands {
+    /// Shows the floating keyboard without covering `rect`, in the primary
+    /// window's logical coordinates. See [`SteamTextInput::show_floating_keyboard`].
+    fn steam_show_floating_keyboard(&mut self, mode: FloatingGamepadTextInputMode, rect: Rect);
+}
+
+impl SteamTextInputCommands for Commands<'_, '_> {
+    fn steam_show_floating_keyboard(&mut self, mode: FloatingGamepadTextInputMode, rect: Rect) {
+        self.add(move |world: &mut World| {
+            let scale_factor = world
+                .query_filtered::<&Window, With<PrimaryWindow>>()
+                .get_single(world)
+                .map_or(1.0, Window::scale_factor);
+            if let Some(text_input) = world.get_resource::<SteamTextInput>() {
+                text_input.show_floating_keyboard(mode, rect, scale_factor);
+            }
+        });
+    }
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    let text_input = SteamTextInput::new(app.world().resource::<Client>());
+    app.insert_resource(text_input)
+        .add_event::<FloatingKeyboardDismissed>()
+        .add_event::<GamepadTextSubmitted>()
+        .add_systems(
+            First,
+            flush_text_input
+                .after(SteamworksSystem::RunCallbacks)
+                .run_if(steam_running),
+        );
+}
+
+fn flush_text_input(
+    mut text_input: ResMut<SteamTextInput>,
+    mut dismissed: EventWriter<FloatingKeyboardDismissed>,
+    mut submitted: EventWriter<GamepadTextSubmitted>,
+) {
+    for () in text_input.dismissed.drain() {
+        dismissed.send(FloatingKeyboardDismissed);
+    }
+    for (request_id, text) in text_input.submitted.drain() {
+        if text_input.pending == Some(request_id) {
+            text_input.pending = None;
+        }
+        submitted.send(GamepadTextSubmitted { request_id, text });
+    }
+}
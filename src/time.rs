@@ -0,0 +1,83 @@
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use bevy_app::{App, First};
+use bevy_ecs::{
+    schedule::IntoSystemConfigs,
+    system::{ResMut, Resource},
+};
+
+use crate::{shutdown::steam_running, Client, SteamworksSystem};
+
+/// Steam's server clock, for timestamps the user can't tamper with by changing
+/// their system clock, such as daily challenge seeds and limited-time events.
+///
+/// Steam reports the time in whole seconds and the value is cached between
+/// refreshes, so it is only accurate to within about a second. It should not be
+/// used to synchronize anything that needs sub-second precision.
+#[derive(Resource)]
+pub struct SteamTime {
+    client: Client,
+    /// How often the server time is read from Steam again. Defaults to 60 seconds.
+    pub refresh_interval: Duration,
+    server_time: u32,
+    /// The local time when `server_time` was read, as seconds since the Unix epoch.
+    local_time: i64,
+    refreshed_at: Instant,
+}
+
+impl SteamTime {
+    fn new(client: &Client) -> Self {
+        let mut time = Self {
+            client: client.clone(),
+            refresh_interval: Duration::from_secs(60),
+            server_time: 0,
+            local_time: 0,
+            refreshed_at: Instant::now(),
+        };
+        time.refresh();
+        time
+    }
+
+    /// Gets the current Steam server time, as seconds since the Unix epoch.
+    pub fn server_now_unix(&self) -> u64 {
+        u64::from(self.server_time) + self.refreshed_at.elapsed().as_secs()
+    }
+
+    /// Gets how many seconds the Steam server clock is ahead of the local clock.
+    ///
+    /// Adding this to a local Unix timestamp estimates the server time at that
+    /// moment.
+    pub fn estimated_offset(&self) -> i64 {
+        i64::from(self.server_time) - self.local_time
+    }
+
+    /// Reads the server time from Steam again. Keeps the last reading once Steam
+    /// is shutting down.
+    pub fn refresh(&mut self) {
+        if self.client.is_shutting_down() {
+            return;
+        }
+        self.server_time = self.client.utils().get_server_real_time();
+        self.local_time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since_epoch| since_epoch.as_secs() as i64);
+        self.refreshed_at = Instant::now();
+    }
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    let client = app.world().resource::<Client>();
+    let time = SteamTime::new(client);
+    app.insert_resource(time).add_systems(
+        First,
+        refresh_server_time
+            .after(SteamworksSystem::RunCallbacks)
+            .run_if(steam_running),
+    );
+}
+
+fn refresh_server_time(mut time: ResMut<SteamTime>) {
+    if time.refreshed_at.elapsed() >= time.refresh_interval {
+        time.refresh();
+    }
+}
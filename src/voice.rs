@@ -0,0 +1,195 @@
+#[cfg(feature = "bevy_audio")]
+mod playback;
+mod push_to_talk;
+mod resample;
+
+#[cfg(feature = "bevy_audio")]
+pub use playback::*;
+pub use push_to_talk::*;
+pub use resample::*;
+
+#[cfg(feature = "bevy_audio")]
+use bevy_app::PostUpdate;
+use bevy_app::{App, First, Plugin, Update};
+#[cfg(feature = "bevy_audio")]
+use bevy_audio::AddAudioSource;
+use bevy_ecs::{
+    event::{Event, EventWriter},
+    schedule::IntoSystemConfigs,
+    system::{ResMut, Resource},
+};
+use bevy_log::warn;
+use steamworks::sys::{self, EVoiceResult};
+
+use crate::{sends_steam_events, shutdown::steam_running, Client, ShuttingDown, SteamworksSystem};
+
+/// The size of the buffer compressed voice is read into, as recommended by Valve.
+const VOICE_BUFFER_SIZE: usize = 8 * 1024;
+
+/// Compressed voice recorded from the user's microphone.
+///
+/// Voice is read every frame, so each event only holds a frame's worth of audio
+/// and is small enough to send to peers as is. The bytes can only be decoded by
+/// Steam's voice decompression on the receiving end.
+#[derive(Event, Debug, Clone)]
+pub struct VoiceData {
+    /// The compressed voice.
+    pub bytes: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RecordingState {
+    Stopped,
+    Recording,
+    /// Recording was stopped, but Steam keeps recording briefly afterwards so the
+    /// end of what was said isn't cut off.
+    Stopping,
+}
+
+/// Records voice from the user's microphone, inserted by [`SteamVoicePlugin`].
+///
+/// While recording, compressed voice is sent as [`VoiceData`] events.
+#[derive(Resource)]
+pub struct SteamVoice {
+    client: Client,
+    state: RecordingState,
+    buffer: Vec<u8>,
+}
+
+impl SteamVoice {
+    /// Starts recording voice.
+    pub fn start_recording(&mut self) -> Result<(), ShuttingDown> {
+        self.client.ensure_running()?;
+        // steamworks does not wrap voice recording, so it is called directly.
+        // SAFETY: The Steam API is initialized for as long as a `Client` exists.
+        unsafe {
+            sys::SteamAPI_ISteamUser_StartVoiceRecording(sys::SteamAPI_SteamUser_v023());
+        }
+        self.state = RecordingState::Recording;
+        Ok(())
+    }
+
+    /// Stops recording voice.
+    ///
+    /// People often release push-to-talk keys early, so Steam keeps recording for
+    /// a short time afterwards, and [`VoiceData`] events keep being sent until it
+    /// is done.
+    pub fn stop_recording(&mut self) -> Result<(), ShuttingDown> {
+        self.client.ensure_running()?;
+        if self.state != RecordingState::Recording {
+            return Ok(());
+        }
+        // steamworks does not wrap voice recording, so it is called directly.
+        // SAFETY: The Steam API is initialized for as long as a `Client` exists.
+        unsafe {
+            sys::SteamAPI_ISteamUser_StopVoiceRecording(sys::SteamAPI_SteamUser_v023());
+        }
+        self.state = RecordingState::Stopping;
+        Ok(())
+    }
+
+    /// Gets the sample rate Steam decompresses voice at with the least processing.
+    ///
+    /// Use a [`VoiceResampler`] to convert decompressed voice to the rate of an
+    /// audio device that differs from this.
+    pub fn optimal_voice_sample_rate(&self) -> Result<u32, ShuttingDown> {
+        self.client.ensure_running()?;
+        Ok(optimal_voice_sample_rate())
+    }
+
+    /// Checks if voice is being recorded, including the short time Steam keeps
+    /// recording after [`SteamVoice::stop_recording`].
+    pub fn is_recording(&self) -> bool {
+        self.state != RecordingState::Stopped
+    }
+}
+
+/// An opt-in [`Plugin`] for recording voice chat through Steam, and with the
+/// `bevy_audio` feature, playing it back through [`VoicePlayback`].
+///
+/// Recording can be started and stopped by hand through [`SteamVoice`], or tied
+/// to an input by setting [`SteamPushToTalk::binding`].
+///
+/// Must be added after [`SteamworksPlugin`](crate::SteamworksPlugin).
+pub struct SteamVoicePlugin;
+
+impl Plugin for SteamVoicePlugin {
+    fn build(&self, app: &mut App) {
+        let client = app.world().resource::<Client>().clone();
+        #[cfg(feature = "bevy_audio")]
+        let playback = VoicePlayback::new(&client);
+        app.insert_resource(SteamVoice {
+            client,
+            state: RecordingState::Stopped,
+            buffer: vec![0; VOICE_BUFFER_SIZE],
+        })
+        .init_resource::<SteamPushToTalk>()
+        .add_event::<VoiceData>()
+        .add_event::<PushToTalk>()
+        .add_systems(
+            First,
+            read_voice
+                .after(SteamworksSystem::RunCallbacks)
+                .run_if(steam_running),
+        )
+        .add_systems(
+            Update,
+            push_to_talk::drive_push_to_talk.run_if(steam_running),
+        );
+        if sends_steam_events(app) {
+            app.add_systems(
+                Update,
+                push_to_talk::track_overlay.before(push_to_talk::drive_push_to_talk),
+            );
+        }
+
+        #[cfg(feature = "bevy_audio")]
+        app.insert_resource(playback)
+            .add_audio_source::<VoiceStream>()
+            .add_systems(PostUpdate, playback::spawn_voice_streams);
+    }
+}
+
+fn read_voice(mut voice: ResMut<SteamVoice>, mut data: EventWriter<VoiceData>) {
+    if voice.state == RecordingState::Stopped {
+        return;
+    }
+    let mut written = 0;
+    // steamworks does not wrap voice recording, so it is called directly.
+    // SAFETY: The Steam API is initialized for as long as a `Client` exists, and
+    // the buffer outlives the call.
+    let result = unsafe {
+        sys::SteamAPI_ISteamUser_GetVoice(
+            sys::SteamAPI_SteamUser_v023(),
+            true,
+            voice.buffer.as_mut_ptr().cast(),
+            voice.buffer.len() as u32,
+            &mut written,
+            false,
+            std::ptr::null_mut(),
+            0,
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    match result {
+        EVoiceResult::k_EVoiceResultOK => data.send(VoiceData {
+            bytes: voice.buffer[..written as usize].to_vec(),
+        }),
+        // Nothing was said since the last frame.
+        EVoiceResult::k_EVoiceResultNoData => {}
+        EVoiceResult::k_EVoiceResultNotRecording => {
+            // Steam has finished the tail after `stop_recording`.
+            if voice.state == RecordingState::Stopping {
+                voice.state = RecordingState::Stopped;
+            }
+        }
+        result => warn!("Failed to read Steam voice: {:?}", result),
+    }
+}
+
+fn optimal_voice_sample_rate() -> u32 {
+    // steamworks does not wrap voice, so it is called directly.
+    // SAFETY: The Steam API is initialized for as long as a `Client` exists.
+    unsafe { sys::SteamAPI_ISteamUser_GetVoiceOptimalSampleRate(sys::SteamAPI_SteamUser_v023()) }
+}
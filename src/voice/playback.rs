@@ -0,0 +1,289 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use bevy_asset::{Asset, Assets};
+use bevy_audio::{AudioSourceBundle, Decodable, PlaybackSettings, Source};
+use bevy_ecs::{
+    entity::Entity,
+    system::{Commands, ResMut, Resource},
+};
+use bevy_log::warn;
+use bevy_reflect::TypePath;
+use bevy_utils::HashMap;
+use steamworks::{
+    sys::{self, EVoiceResult},
+    SteamId,
+};
+
+use crate::{Client, ShuttingDown};
+
+/// How many samples the playback thread takes from a speaker's buffer at once.
+const SAMPLES_PER_REFILL: usize = 256;
+/// How long after their last packet a speaker is still considered speaking.
+const SPEAKING_GRACE: Duration = Duration::from_millis(250);
+
+/// The decompressed voice of a speaker, shared with the audio thread.
+struct VoiceBuffer {
+    samples: VecDeque<i16>,
+    /// Whether playback is waiting for the jitter buffer to fill.
+    buffering: bool,
+    jitter_samples: usize,
+    volume: f32,
+}
+
+/// A streaming audio source playing one speaker's voice.
+#[derive(Asset, TypePath)]
+pub struct VoiceStream {
+    buffer: Arc<Mutex<VoiceBuffer>>,
+    sample_rate: u32,
+}
+
+impl Decodable for VoiceStream {
+    type DecoderItem = i16;
+    type Decoder = VoiceDecoder;
+
+    fn decoder(&self) -> Self::Decoder {
+        VoiceDecoder {
+            buffer: self.buffer.clone(),
+            sample_rate: self.sample_rate,
+            local: VecDeque::with_capacity(SAMPLES_PER_REFILL),
+            volume: 1.0,
+        }
+    }
+}
+
+/// Plays a [`VoiceStream`], producing silence while the speaker is quiet.
+pub struct VoiceDecoder {
+    buffer: Arc<Mutex<VoiceBuffer>>,
+    sample_rate: u32,
+    /// Samples taken from the shared buffer, so it is only locked once per refill.
+    local: VecDeque<i16>,
+    volume: f32,
+}
+
+impl VoiceDecoder {
+    fn refill(&mut self) {
+        let Ok(mut buffer) = self.buffer.lock() else {
+            return;
+        };
+        self.volume = buffer.volume;
+        if buffer.buffering {
+            if buffer.samples.len() < buffer.jitter_samples {
+                return;
+            }
+            buffer.buffering = false;
+        }
+        if buffer.samples.is_empty() {
+            // The speaker went quiet, so buffer again before resuming.
+            buffer.buffering = true;
+            return;
+        }
+        let count = buffer.samples.len().min(SAMPLES_PER_REFILL);
+        self.local.extend(buffer.samples.drain(..count));
+    }
+}
+
+impl Iterator for VoiceDecoder {
+    type Item = i16;
+
+    fn next(&mut self) -> Option<i16> {
+        if self.local.is_empty() {
+            self.refill();
+        }
+        // The stream never ends, so silence is played while there is no voice.
+        let sample = self.local.pop_front().unwrap_or(0);
+        Some((f32::from(sample) * self.volume) as i16)
+    }
+}
+
+impl Source for VoiceDecoder {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+struct Speaker {
+    buffer: Arc<Mutex<VoiceBuffer>>,
+    entity: Option<Entity>,
+    last_voice: Instant,
+}
+
+/// Plays voice received from other players through `bevy_audio`, inserted by
+/// [`SteamVoicePlugin`](crate::SteamVoicePlugin) with the `bevy_audio` feature.
+///
+/// Voice recorded by a peer's [`SteamVoice`](crate::SteamVoice) is passed to
+/// [`VoicePlayback::push`] as it arrives. Each speaker gets their own audio
+/// entity playing a [`VoiceStream`], which buffers for
+/// [`VoicePlayback::jitter_buffer`] before playing so uneven packet arrival
+/// doesn't cause gaps.
+#[derive(Resource)]
+pub struct VoicePlayback {
+    /// How much voice to buffer before playing a speaker. Defaults to 60 ms.
+    pub jitter_buffer: Duration,
+    client: Client,
+    sample_rate: u32,
+    speakers: HashMap<SteamId, Speaker>,
+    decompressed: Vec<u8>,
+    removed: Vec<Entity>,
+}
+
+impl VoicePlayback {
+    pub(super) fn new(client: &Client) -> Self {
+        let sample_rate = super::optimal_voice_sample_rate();
+        Self {
+            jitter_buffer: Duration::from_millis(60),
+            client: client.clone(),
+            sample_rate,
+            speakers: HashMap::default(),
+            // One second of 16-bit mono audio, which is more than a packet holds.
+            decompressed: vec![0; sample_rate as usize * 2],
+            removed: Vec::new(),
+        }
+    }
+
+    /// Gets the sample rate voice is decompressed to. `bevy_audio` converts it to the
+    /// rate of the audio device during playback.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Decompresses a packet of voice from `speaker` and queues it for playback.
+    pub fn push(&mut self, speaker: SteamId, bytes: &[u8]) -> Result<(), ShuttingDown> {
+        self.client.ensure_running()?;
+        let Some(written) = self.decompress(bytes) else {
+            return Ok(());
+        };
+        let samples = self.decompressed[..written]
+            .chunks_exact(2)
+            .map(|sample| i16::from_le_bytes([sample[0], sample[1]]));
+        let jitter_samples =
+            (self.jitter_buffer.as_secs_f64() * f64::from(self.sample_rate)) as usize;
+        let speaker = self.speakers.entry(speaker).or_insert_with(|| Speaker {
+            buffer: Arc::new(Mutex::new(VoiceBuffer {
+                samples: VecDeque::new(),
+                buffering: true,
+                jitter_samples,
+                volume: 1.0,
+            })),
+            entity: None,
+            last_voice: Instant::now(),
+        });
+        speaker.last_voice = Instant::now();
+        if let Ok(mut buffer) = speaker.buffer.lock() {
+            buffer.jitter_samples = jitter_samples;
+            buffer.samples.extend(samples);
+        }
+        Ok(())
+    }
+
+    /// Decompresses voice into 16-bit mono PCM, returning the number of bytes written.
+    fn decompress(&mut self, bytes: &[u8]) -> Option<usize> {
+        let mut written = 0;
+        // steamworks does not wrap voice playback, so it is called directly.
+        // SAFETY: The Steam API is initialized for as long as a `Client` exists, and
+        // both buffers outlive the call.
+        let result = unsafe {
+            sys::SteamAPI_ISteamUser_DecompressVoice(
+                sys::SteamAPI_SteamUser_v023(),
+                bytes.as_ptr().cast(),
+                bytes.len() as u32,
+                self.decompressed.as_mut_ptr().cast(),
+                self.decompressed.len() as u32,
+                &mut written,
+                self.sample_rate,
+            )
+        };
+        if result != EVoiceResult::k_EVoiceResultOK {
+            warn!("Failed to decompress Steam voice: {:?}", result);
+            return None;
+        }
+        Some(written as usize)
+    }
+
+    /// Gets the playback volume of a speaker, from 0 to 1.
+    pub fn volume(&self, speaker: SteamId) -> f32 {
+        self.speakers
+            .get(&speaker)
+            .and_then(|speaker| speaker.buffer.lock().ok().map(|buffer| buffer.volume))
+            .unwrap_or(1.0)
+    }
+
+    /// Sets the playback volume of a speaker, from 0 to 1. Does nothing if the
+    /// speaker hasn't been heard from yet.
+    pub fn set_volume(&mut self, speaker: SteamId, volume: f32) {
+        let Some(speaker) = self.speakers.get(&speaker) else {
+            return;
+        };
+        if let Ok(mut buffer) = speaker.buffer.lock() {
+            buffer.volume = volume.clamp(0.0, 1.0);
+        }
+    }
+
+    /// Iterates over the speakers whose voice was received or is still playing,
+    /// for drawing speaker icons.
+    pub fn currently_speaking(&self) -> impl Iterator<Item = SteamId> + '_ {
+        self.speakers
+            .iter()
+            .filter(|(_, speaker)| {
+                speaker.last_voice.elapsed() < SPEAKING_GRACE
+                    || speaker
+                        .buffer
+                        .lock()
+                        .map_or(false, |buffer| !buffer.samples.is_empty())
+            })
+            .map(|(steam_id, _)| *steam_id)
+    }
+
+    /// Stops playing a speaker's voice, such as when they leave.
+    pub fn remove(&mut self, speaker: SteamId) {
+        if let Some(entity) = self
+            .speakers
+            .remove(&speaker)
+            .and_then(|speaker| speaker.entity)
+        {
+            self.removed.push(entity);
+        }
+    }
+}
+
+pub(super) fn spawn_voice_streams(
+    mut commands: Commands,
+    mut playback: ResMut<VoicePlayback>,
+    mut streams: ResMut<Assets<VoiceStream>>,
+) {
+    for entity in std::mem::take(&mut playback.removed) {
+        if let Some(mut entity) = commands.get_entity(entity) {
+            entity.despawn();
+        }
+    }
+    let sample_rate = playback.sample_rate;
+    for speaker in playback.speakers.values_mut() {
+        if speaker.entity.is_some() {
+            continue;
+        }
+        let source = streams.add(VoiceStream {
+            buffer: speaker.buffer.clone(),
+            sample_rate,
+        });
+        let entity = commands.spawn(AudioSourceBundle {
+            source,
+            settings: PlaybackSettings::ONCE,
+        });
+        speaker.entity = Some(entity.id());
+    }
+}
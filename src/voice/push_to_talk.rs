@@ -0,0 +1,118 @@
+use bevy_ecs::{
+    event::{Event, EventReader, EventWriter},
+    system::{Res, ResMut, Resource},
+};
+use bevy_input::{
+    gamepad::{GamepadButton, GamepadButtonType, Gamepads},
+    keyboard::KeyCode,
+    ButtonInput,
+};
+use bevy_window::WindowFocused;
+
+use super::SteamVoice;
+use crate::{SteamDigitalActions, SteamworksEvent};
+
+/// Sent when push-to-talk starts or stops recording voice.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PushToTalk {
+    /// Whether voice is now being recorded.
+    pub active: bool,
+}
+
+/// The input that is held to talk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PushToTalkBinding {
+    /// A keyboard key.
+    Key(KeyCode),
+    /// A button on any connected gamepad.
+    GamepadButton(GamepadButtonType),
+    /// A Steam Input digital action registered with [`SteamDigitalActions`].
+    SteamAction(String),
+}
+
+/// Drives [`SteamVoice`] recording from a held input, inserted by
+/// [`SteamVoicePlugin`](crate::SteamVoicePlugin).
+///
+/// Recording follows whether the input is held rather than its presses and
+/// releases, so a release is never missed. It is also stopped while the window
+/// is unfocused or the Steam overlay is open, since the input's release may
+/// never reach the game.
+#[derive(Resource)]
+pub struct SteamPushToTalk {
+    /// The input that is held to talk. `None` disables push-to-talk.
+    pub binding: Option<PushToTalkBinding>,
+    active: bool,
+    focused: bool,
+    overlay_open: bool,
+}
+
+impl Default for SteamPushToTalk {
+    fn default() -> Self {
+        Self {
+            binding: None,
+            active: false,
+            focused: true,
+            overlay_open: false,
+        }
+    }
+}
+
+impl SteamPushToTalk {
+    /// Checks if push-to-talk is currently recording.
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+}
+
+pub(super) fn track_overlay(
+    mut push_to_talk: ResMut<SteamPushToTalk>,
+    mut events: EventReader<SteamworksEvent>,
+) {
+    for event in events.read() {
+        if let SteamworksEvent::GameOverlayActivated(overlay) = event {
+            push_to_talk.overlay_open = overlay.active;
+        }
+    }
+}
+
+pub(super) fn drive_push_to_talk(
+    mut push_to_talk: ResMut<SteamPushToTalk>,
+    mut voice: ResMut<SteamVoice>,
+    keys: Option<Res<ButtonInput<KeyCode>>>,
+    gamepad_buttons: Option<Res<ButtonInput<GamepadButton>>>,
+    gamepads: Option<Res<Gamepads>>,
+    actions: Option<Res<SteamDigitalActions>>,
+    mut focus: EventReader<WindowFocused>,
+    mut changed: EventWriter<PushToTalk>,
+) {
+    for event in focus.read() {
+        push_to_talk.focused = event.focused;
+    }
+    let held = match &push_to_talk.binding {
+        None => false,
+        Some(PushToTalkBinding::Key(key)) => keys.map_or(false, |keys| keys.pressed(*key)),
+        Some(PushToTalkBinding::GamepadButton(button)) => match (gamepad_buttons, gamepads) {
+            (Some(buttons), Some(gamepads)) => gamepads
+                .iter()
+                .any(|gamepad| buttons.pressed(GamepadButton::new(gamepad, *button))),
+            _ => false,
+        },
+        Some(PushToTalkBinding::SteamAction(name)) => {
+            actions.map_or(false, |actions| actions.pressed(name))
+        }
+    };
+    let active = held && push_to_talk.focused && !push_to_talk.overlay_open;
+    if active == push_to_talk.active {
+        return;
+    }
+    let recording = if active {
+        voice.start_recording()
+    } else {
+        voice.stop_recording()
+    };
+    if recording.is_err() {
+        return;
+    }
+    push_to_talk.active = active;
+    changed.send(PushToTalk { active });
+}
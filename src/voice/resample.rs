@@ -0,0 +1,152 @@
+/// Converts mono 16-bit PCM from one sample rate to another with linear
+/// interpolation, such as from Steam's optimal voice sample rate to the rate of
+/// an audio device.
+///
+/// The resampler keeps its position between calls to
+/// [`VoiceResampler::process`], so a stream can be fed one packet at a time
+/// without clicks at packet boundaries. Pitch is preserved:
+///
+/// ```rust
+/// # use bevy_steamworks::VoiceResampler;
+/// // One second of a 440 Hz tone at 24 kHz.
+/// let input: Vec<i16> = (0..24_000)
+///     .map(|i| {
+///         let t = i as f64 / 24_000.0;
+///         ((t * 440.0 * std::f64::consts::TAU).sin() * 10_000.0) as i16
+///     })
+///     .collect();
+///
+/// let mut resampler = VoiceResampler::new(24_000, 44_100);
+/// let mut output = Vec::new();
+/// for packet in input.chunks(480) {
+///     resampler.process(packet, &mut output);
+/// }
+///
+/// // Still one second long, and still 440 Hz.
+/// assert!((output.len() as i64 - 44_100).abs() <= 2);
+/// let rising_edges = output.windows(2).filter(|w| w[0] < 0 && w[1] >= 0).count();
+/// assert!((rising_edges as i64 - 440).abs() <= 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct VoiceResampler {
+    step: f64,
+    /// The position of the next output sample, where 0 is the last sample of the
+    /// previous input and 1 is the first sample of the next.
+    position: f64,
+    previous: i16,
+}
+
+impl VoiceResampler {
+    /// Creates a resampler from `from_rate` to `to_rate`, both in Hz.
+    pub fn new(from_rate: u32, to_rate: u32) -> Self {
+        Self {
+            step: f64::from(from_rate) / f64::from(to_rate.max(1)),
+            position: 1.0,
+            previous: 0,
+        }
+    }
+
+    /// Resamples `input`, appending the result to `output`.
+    pub fn process(&mut self, input: &[i16], output: &mut Vec<i16>) {
+        let Some(&last) = input.last() else {
+            return;
+        };
+        let sample = |index: usize| {
+            if index == 0 {
+                self.previous
+            } else {
+                input[index - 1]
+            }
+        };
+        output.reserve((input.len() as f64 / self.step) as usize + 1);
+        while self.position < input.len() as f64 {
+            let index = self.position as usize;
+            let fraction = self.position - index as f64;
+            let a = f64::from(sample(index));
+            let b = f64::from(sample(index + 1));
+            output.push((a + (b - a) * fraction).round() as i16);
+            self.position += self.step;
+        }
+        self.position -= input.len() as f64;
+        self.previous = last;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One second of a sine wave.
+    fn sine(frequency: f64, rate: u32) -> Vec<i16> {
+        (0..rate)
+            .map(|i| {
+                let t = f64::from(i) / f64::from(rate);
+                ((t * frequency * std::f64::consts::TAU).sin() * 10_000.0) as i16
+            })
+            .collect()
+    }
+
+    fn resample(input: &[i16], from_rate: u32, to_rate: u32, packet_len: usize) -> Vec<i16> {
+        let mut resampler = VoiceResampler::new(from_rate, to_rate);
+        let mut output = Vec::new();
+        for packet in input.chunks(packet_len) {
+            resampler.process(packet, &mut output);
+        }
+        output
+    }
+
+    fn rising_edges(samples: &[i16]) -> usize {
+        samples.windows(2).filter(|w| w[0] < 0 && w[1] >= 0).count()
+    }
+
+    #[test]
+    fn keeps_length_and_pitch() {
+        for (from_rate, to_rate) in [(24_000, 44_100), (24_000, 48_000), (48_000, 11_025)] {
+            let input = sine(440.0, from_rate);
+            let output = resample(&input, from_rate, to_rate, 480);
+            assert!(
+                (output.len() as i64 - i64::from(to_rate)).abs() <= 2,
+                "{from_rate} -> {to_rate} Hz gave {} samples",
+                output.len()
+            );
+            assert!(
+                (rising_edges(&output) as i64 - rising_edges(&input) as i64).abs() <= 1,
+                "{from_rate} -> {to_rate} Hz changed the pitch"
+            );
+        }
+    }
+
+    #[test]
+    fn keeps_amplitude() {
+        let output = resample(&sine(440.0, 24_000), 24_000, 48_000, 480);
+        let peak = output.iter().map(|s| s.unsigned_abs()).max().unwrap();
+        assert!((9_900..=10_000).contains(&peak), "peak was {peak}");
+    }
+
+    #[test]
+    fn same_rate_is_unchanged() {
+        let input = sine(440.0, 24_000);
+        // The last sample is held back until the next packet arrives.
+        assert_eq!(
+            resample(&input, 24_000, 24_000, 480),
+            input[..input.len() - 1]
+        );
+    }
+
+    #[test]
+    fn packet_boundaries_do_not_matter() {
+        let input = sine(440.0, 24_000);
+        let whole = resample(&input, 24_000, 44_100, input.len());
+        for packet_len in [1, 7, 480, 1000] {
+            assert_eq!(resample(&input, 24_000, 44_100, packet_len), whole);
+        }
+    }
+
+    #[test]
+    fn empty_input_produces_nothing() {
+        let mut resampler = VoiceResampler::new(24_000, 48_000);
+        let mut output = Vec::new();
+        resampler.process(&[], &mut output);
+        assert!(output.is_empty());
+    }
+}
@@ -0,0 +1,20 @@
+use std::time::Duration;
+
+use bevy_ecs::event::Event;
+
+/// Sent when running Steam callbacks took longer than the threshold set with
+/// [`SteamworksPlugin::with_callback_watchdog`](crate::SteamworksPlugin::with_callback_watchdog).
+#[derive(Event, Debug, Clone, Copy)]
+pub struct SteamCallbackOverrun {
+    /// How long running the callbacks took.
+    pub duration: Duration,
+    /// How many Steam events were forwarded that frame.
+    pub events: usize,
+}
+
+/// When to report slow callback runs.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CallbackWatchdog {
+    pub(crate) threshold: Duration,
+    pub(crate) log: bool,
+}
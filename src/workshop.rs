@@ -0,0 +1,168 @@
+#[cfg(feature = "bevy_asset")]
+mod asset_source;
+#[cfg(feature = "bevy_asset")]
+mod hot_reload;
+mod subscriptions;
+mod ugc;
+
+#[cfg(feature = "bevy_asset")]
+pub use asset_source::WORKSHOP_ASSET_SOURCE;
+#[cfg(feature = "bevy_asset")]
+pub use hot_reload::*;
+pub use subscriptions::*;
+pub use ugc::*;
+
+use bevy_app::{App, First};
+use bevy_ecs::{
+    event::{Event, EventWriter},
+    schedule::IntoSystemConfigs,
+    system::{ResMut, Resource},
+};
+use steamworks::{
+    ClientManager, PublishedFileId, SteamError, UpdateHandle, UpdateStatus, UpdateWatchHandle,
+};
+
+use crate::{
+    call_result::CallResults, shutdown::steam_running, Client, ShuttingDown, SteamworksSystem,
+};
+
+/// Progress of an in-flight Steam Workshop item upload.
+///
+/// Sent once per frame for every upload tracked by [`WorkshopPublisher`].
+#[derive(Event, Debug)]
+pub struct WorkshopUploadProgress {
+    /// The item being uploaded.
+    pub file_id: PublishedFileId,
+    /// The stage the upload is currently in.
+    pub status: UpdateStatus,
+    /// The number of bytes processed so far.
+    pub bytes_processed: u64,
+    /// The total number of bytes to process in the current stage.
+    pub bytes_total: u64,
+}
+
+/// Sent when Steam finishes processing an item update submitted through
+/// [`WorkshopPublisher::submit`].
+#[derive(Event, Debug)]
+pub struct WorkshopUploadComplete {
+    /// The item that was uploaded.
+    pub file_id: PublishedFileId,
+    /// On success, whether the user still needs to accept the Workshop legal agreement.
+    pub result: Result<bool, SteamError>,
+}
+
+struct TrackedUpload {
+    id: u64,
+    file_id: PublishedFileId,
+    handle: UpdateWatchHandle<ClientManager>,
+}
+
+/// Submits Steam Workshop item updates and tracks their progress.
+///
+/// Every upload submitted through this resource is polled each frame and reported
+/// via [`WorkshopUploadProgress`] until Steam responds, at which point a
+/// [`WorkshopUploadComplete`] event is sent and the upload is no longer tracked.
+#[derive(Resource)]
+pub struct WorkshopPublisher {
+    client: Client,
+    uploads: Vec<TrackedUpload>,
+    next_id: u64,
+    submitted: CallResults<(u64, Result<(PublishedFileId, bool), SteamError>)>,
+}
+
+impl WorkshopPublisher {
+    /// Submits an update for `file_id` created via [`UGC::start_item_update`] and
+    /// starts tracking its progress.
+    ///
+    /// [`UGC::start_item_update`]: steamworks::UGC::start_item_update
+    pub fn submit(
+        &mut self,
+        file_id: PublishedFileId,
+        update: UpdateHandle<ClientManager>,
+        change_note: Option<&str>,
+    ) -> Result<(), ShuttingDown> {
+        self.client.ensure_running()?;
+        let id = self.next_id;
+        self.next_id += 1;
+        let submitted = self.submitted.clone();
+        let handle = update.submit(change_note, move |result| {
+            submitted.push((id, result));
+        });
+        self.uploads.push(TrackedUpload {
+            id,
+            file_id,
+            handle,
+        });
+        Ok(())
+    }
+
+    /// Checks if any uploads are currently in flight.
+    pub fn is_uploading(&self) -> bool {
+        !self.uploads.is_empty()
+    }
+
+    /// Iterates over the items with uploads currently in flight.
+    pub fn uploading(&self) -> impl Iterator<Item = PublishedFileId> + '_ {
+        self.uploads.iter().map(|upload| upload.file_id)
+    }
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    let client = app.world().resource::<Client>();
+    let publisher = WorkshopPublisher {
+        client: client.clone(),
+        uploads: Vec::new(),
+        next_id: 0,
+        submitted: CallResults::new(),
+    };
+    let ugc = SteamUgc::new(client);
+    app.insert_resource(publisher)
+        .add_event::<WorkshopUploadProgress>()
+        .add_event::<WorkshopUploadComplete>()
+        .init_resource::<SubscribedWorkshopItems>()
+        .add_event::<WorkshopItemStateChanged>()
+        .insert_resource(ugc)
+        .add_event::<WorkshopItemSubscribed>()
+        .add_event::<WorkshopItemUnsubscribed>()
+        .add_event::<WorkshopItemDeleted>()
+        .add_systems(
+            First,
+            (
+                poll_uploads,
+                subscriptions::track_item_states,
+                ugc::flush_ugc_results,
+            )
+                .after(SteamworksSystem::RunCallbacks)
+                .run_if(steam_running),
+        );
+
+    #[cfg(feature = "bevy_asset")]
+    asset_source::register(app);
+}
+
+fn poll_uploads(
+    mut publisher: ResMut<WorkshopPublisher>,
+    mut progress: EventWriter<WorkshopUploadProgress>,
+    mut complete: EventWriter<WorkshopUploadComplete>,
+) {
+    for (id, result) in publisher.submitted.drain() {
+        let Some(index) = publisher.uploads.iter().position(|upload| upload.id == id) else {
+            continue;
+        };
+        let upload = publisher.uploads.swap_remove(index);
+        complete.send(WorkshopUploadComplete {
+            file_id: upload.file_id,
+            result: result.map(|(_, needs_agreement)| needs_agreement),
+        });
+    }
+
+    for upload in publisher.uploads.iter() {
+        let (status, bytes_processed, bytes_total) = upload.handle.progress();
+        progress.send(WorkshopUploadProgress {
+            file_id: upload.file_id,
+            status,
+            bytes_processed,
+            bytes_total,
+        });
+    }
+}
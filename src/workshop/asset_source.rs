@@ -0,0 +1,217 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, MutexGuard, PoisonError,
+    },
+};
+
+use bevy_app::App;
+use bevy_asset::{
+    io::{AssetReader, AssetReaderError, AssetSource, PathStream, Reader},
+    AssetApp, AssetPath,
+};
+use bevy_ecs::system::Resource;
+use futures_lite::StreamExt;
+use steamworks::PublishedFileId;
+
+use crate::Client;
+
+/// The [`AssetSourceId`] Workshop content is registered under.
+///
+/// Paths take the form `steamugc://<published file id>/<path within the item>`.
+///
+/// [`AssetSourceId`]: bevy_asset::io::AssetSourceId
+pub const WORKSHOP_ASSET_SOURCE: &str = "steamugc";
+
+/// The assets read through [`WORKSHOP_ASSET_SOURCE`], shared with the reader so
+/// [`WorkshopHotReloadPlugin`](crate::WorkshopHotReloadPlugin) can track them in
+/// [`WorkshopAssets`](crate::WorkshopAssets).
+#[derive(Resource, Clone, Default)]
+pub(super) struct WorkshopAssetLoads(Arc<RecordedLoads>);
+
+#[derive(Default)]
+struct RecordedLoads {
+    /// Set once something takes the loads, so they don't pile up otherwise.
+    enabled: AtomicBool,
+    loads: Mutex<Vec<(PublishedFileId, AssetPath<'static>)>>,
+}
+
+impl WorkshopAssetLoads {
+    /// Starts recording the assets read from Workshop items.
+    pub(super) fn enable(&self) {
+        self.0.enabled.store(true, Ordering::Relaxed);
+    }
+
+    fn record(&self, id: PublishedFileId, path: &Path) {
+        if !self.0.enabled.load(Ordering::Relaxed) {
+            return;
+        }
+        let path = AssetPath::from_path(path)
+            .with_source(WORKSHOP_ASSET_SOURCE)
+            .into_owned();
+        self.lock().push((id, path));
+    }
+
+    /// Takes the assets read since the last call, along with the items they were
+    /// read from.
+    pub(super) fn take(&self) -> Vec<(PublishedFileId, AssetPath<'static>)> {
+        std::mem::take(&mut *self.lock())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<(PublishedFileId, AssetPath<'static>)>> {
+        self.0.loads.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// An [`AssetReader`] that reads the installed content of Workshop items.
+///
+/// The first segment of every path is parsed as a [`PublishedFileId`] and the
+/// remainder is resolved relative to that item's install folder. Legacy
+/// single-file items are read by either an empty remainder or the file's name.
+struct WorkshopAssetReader {
+    client: Client,
+    loads: WorkshopAssetLoads,
+}
+
+impl WorkshopAssetReader {
+    async fn resolve(&self, path: &Path) -> Result<(PublishedFileId, PathBuf), AssetReaderError> {
+        let mut components = path.components();
+        let id = components
+            .next()
+            .and_then(|component| component.as_os_str().to_str())
+            .and_then(|segment| segment.parse::<u64>().ok())
+            .map(PublishedFileId)
+            .ok_or_else(|| {
+                io_error(
+                    io::ErrorKind::InvalidInput,
+                    format!("{path:?} does not start with a Workshop item ID"),
+                )
+            })?;
+        let relative = components.as_path();
+        self.client
+            .ensure_running()
+            .map_err(|err| io_error(io::ErrorKind::Other, err.to_string()))?;
+        let info = self.client.ugc().item_install_info(id).ok_or_else(|| {
+            io_error(
+                io::ErrorKind::NotFound,
+                format!("Workshop item {} is not installed", id.0),
+            )
+        })?;
+
+        let install_path = PathBuf::from(info.folder);
+        let is_file = async_fs::metadata(&install_path)
+            .await
+            .is_ok_and(|metadata| metadata.is_file());
+        if is_file {
+            let is_item_file = relative.as_os_str().is_empty()
+                || Some(relative.as_os_str()) == install_path.file_name();
+            return if is_item_file {
+                Ok((id, install_path))
+            } else {
+                Err(AssetReaderError::NotFound(path.to_owned()))
+            };
+        }
+        Ok((id, install_path.join(relative)))
+    }
+}
+
+impl AssetReader for WorkshopAssetReader {
+    async fn read<'a>(&'a self, path: &'a Path) -> Result<Box<Reader<'a>>, AssetReaderError> {
+        let (id, full_path) = self.resolve(path).await?;
+        let file = open(path, full_path).await?;
+        self.loads.record(id, path);
+        Ok(Box::new(file))
+    }
+
+    async fn read_meta<'a>(&'a self, path: &'a Path) -> Result<Box<Reader<'a>>, AssetReaderError> {
+        let (_, full_path) = self.resolve(path).await?;
+        let mut full_path = full_path.into_os_string();
+        full_path.push(".meta");
+        let file = open(path, full_path.into()).await?;
+        Ok(Box::new(file))
+    }
+
+    async fn read_directory<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> Result<Box<PathStream>, AssetReaderError> {
+        let (_, full_path) = self.resolve(path).await?;
+        let entries = match async_fs::read_dir(&full_path).await {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                return Err(AssetReaderError::NotFound(path.to_owned()));
+            }
+            Err(err) => return Err(err.into()),
+        };
+        let path = path.to_owned();
+        let paths = entries
+            .filter_map(|entry| entry.ok())
+            .map(move |entry| path.join(entry.file_name()));
+        Ok(Box::new(paths))
+    }
+
+    async fn is_directory<'a>(&'a self, path: &'a Path) -> Result<bool, AssetReaderError> {
+        let (_, full_path) = self.resolve(path).await?;
+        Ok(async_fs::metadata(full_path)
+            .await
+            .is_ok_and(|metadata| metadata.is_dir()))
+    }
+}
+
+async fn open(path: &Path, full_path: PathBuf) -> Result<async_fs::File, AssetReaderError> {
+    match async_fs::File::open(&full_path).await {
+        Ok(file) => Ok(file),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            Err(AssetReaderError::NotFound(path.to_owned()))
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn io_error(kind: io::ErrorKind, message: String) -> AssetReaderError {
+    AssetReaderError::Io(Arc::new(io::Error::new(kind, message)))
+}
+
+pub(super) fn register(app: &mut App) {
+    let client = app.world().resource::<Client>().clone();
+    let loads = WorkshopAssetLoads::default();
+    app.insert_resource(loads.clone()).register_asset_source(
+        WORKSHOP_ASSET_SOURCE,
+        AssetSource::build().with_reader(move || {
+            Box::new(WorkshopAssetReader {
+                client: client.clone(),
+                loads: loads.clone(),
+            })
+        }),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_are_only_recorded_once_enabled() {
+        let loads = WorkshopAssetLoads::default();
+        loads.record(PublishedFileId(1), Path::new("1/ignored.png"));
+        assert!(loads.take().is_empty());
+
+        loads.enable();
+        loads.record(PublishedFileId(1), Path::new("1/textures/a.png"));
+        loads.record(PublishedFileId(2), Path::new("2/b.ron"));
+        let recorded = loads.take();
+        assert_eq!(
+            recorded,
+            vec![
+                (
+                    PublishedFileId(1),
+                    AssetPath::from("steamugc://1/textures/a.png")
+                ),
+                (PublishedFileId(2), AssetPath::from("steamugc://2/b.ron")),
+            ]
+        );
+        assert!(loads.take().is_empty());
+    }
+}
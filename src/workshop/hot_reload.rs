@@ -0,0 +1,172 @@
+use std::path::PathBuf;
+
+use bevy_app::{App, First, Plugin};
+use bevy_asset::{AssetServer, UntypedHandle};
+use bevy_ecs::{
+    event::{Event, EventReader, EventWriter},
+    schedule::IntoSystemConfigs,
+    system::{Res, ResMut, Resource},
+};
+use bevy_utils::HashMap;
+use steamworks::PublishedFileId;
+
+use super::asset_source::WorkshopAssetLoads;
+use crate::{
+    sends_steam_events, shutdown::steam_running, Client, SteamworksEvent, SteamworksSystem,
+};
+
+/// Sent when a Workshop item finishes downloading or updating while the game is running.
+#[derive(Event, Debug, Clone)]
+pub struct WorkshopContentChanged {
+    /// The item that changed.
+    pub id: PublishedFileId,
+    /// The folder the item is installed into.
+    pub install_path: PathBuf,
+}
+
+/// Tracks which loaded assets came from which Workshop item.
+///
+/// [`WorkshopHotReloadPlugin`] fills this in as assets are read through
+/// [`WORKSHOP_ASSET_SOURCE`](crate::WORKSHOP_ASSET_SOURCE). Assets loaded from an
+/// item's install folder any other way can be registered with
+/// [`WorkshopAssets::track`].
+///
+/// Tracked handles are strong, so they keep their assets loaded. They are
+/// reloaded whenever their item is updated, and can be removed with
+/// [`WorkshopAssets::untrack`] to unload an item's content once it is unsubscribed.
+#[derive(Resource, Default)]
+pub struct WorkshopAssets {
+    handles: HashMap<PublishedFileId, Vec<UntypedHandle>>,
+}
+
+impl WorkshopAssets {
+    /// Records that `handle` was loaded from the content of the item `id`.
+    ///
+    /// Tracking the same handle again does nothing.
+    pub fn track(&mut self, id: PublishedFileId, handle: impl Into<UntypedHandle>) {
+        let handle = handle.into();
+        let handles = self.handles.entry(id).or_default();
+        if !handles.contains(&handle) {
+            handles.push(handle);
+        }
+    }
+
+    /// Gets the handles loaded from the content of the item `id`.
+    pub fn handles(&self, id: PublishedFileId) -> &[UntypedHandle] {
+        self.handles.get(&id).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Stops tracking the item `id`, returning its handles.
+    ///
+    /// Dropping the returned handles will unload the assets if nothing else holds them.
+    pub fn untrack(&mut self, id: PublishedFileId) -> Vec<UntypedHandle> {
+        self.handles.remove(&id).unwrap_or_default()
+    }
+
+    /// Iterates over every item with tracked assets.
+    pub fn items(&self) -> impl Iterator<Item = PublishedFileId> + '_ {
+        self.handles.keys().copied()
+    }
+}
+
+/// An opt-in [`Plugin`] that reacts to Workshop items being installed or updated
+/// mid-session.
+///
+/// Whenever a [`DownloadItemResult`] for this app succeeds, a [`WorkshopContentChanged`]
+/// event is sent with the item's install folder. Assets loaded from Workshop items
+/// are tracked in [`WorkshopAssets`], and if `reload_assets` is set, every handle
+/// tracked for the changed item is reloaded via [`AssetServer::reload`].
+///
+/// Must be added after [`SteamworksPlugin`](crate::SteamworksPlugin).
+///
+/// [`DownloadItemResult`]: steamworks::DownloadItemResult
+pub struct WorkshopHotReloadPlugin {
+    /// Whether to reload tracked assets automatically. If `false`, only
+    /// [`WorkshopContentChanged`] is sent.
+    pub reload_assets: bool,
+}
+
+impl Default for WorkshopHotReloadPlugin {
+    fn default() -> Self {
+        Self {
+            reload_assets: true,
+        }
+    }
+}
+
+impl Plugin for WorkshopHotReloadPlugin {
+    fn build(&self, app: &mut App) {
+        if let Some(loads) = app.world().get_resource::<WorkshopAssetLoads>() {
+            loads.enable();
+        }
+        app.init_resource::<WorkshopAssets>()
+            .add_event::<WorkshopContentChanged>();
+        if !sends_steam_events(app) {
+            return;
+        }
+        app.add_systems(
+            First,
+            (detect_content_changes, track_workshop_assets)
+                .chain()
+                .after(SteamworksSystem::RunCallbacks)
+                .run_if(steam_running),
+        );
+        if self.reload_assets {
+            app.add_systems(
+                First,
+                reload_changed_content
+                    .after(track_workshop_assets)
+                    .run_if(steam_running),
+            );
+        }
+    }
+}
+
+fn detect_content_changes(
+    client: Res<Client>,
+    mut events: EventReader<SteamworksEvent>,
+    mut changed: EventWriter<WorkshopContentChanged>,
+) {
+    let app_id = client.utils().app_id();
+    for event in events.read() {
+        let SteamworksEvent::DownloadItemResult(result) = event else {
+            continue;
+        };
+        if result.app_id != app_id || result.error.is_some() {
+            continue;
+        }
+        let Some(info) = client.ugc().item_install_info(result.published_file_id) else {
+            continue;
+        };
+        changed.send(WorkshopContentChanged {
+            id: result.published_file_id,
+            install_path: PathBuf::from(info.folder),
+        });
+    }
+}
+
+fn track_workshop_assets(
+    loads: Option<Res<WorkshopAssetLoads>>,
+    asset_server: Res<AssetServer>,
+    mut assets: ResMut<WorkshopAssets>,
+) {
+    for (id, path) in loads.iter().flat_map(|loads| loads.take()) {
+        if let Some(handle) = asset_server.get_handle_untyped(path) {
+            assets.track(id, handle);
+        }
+    }
+}
+
+fn reload_changed_content(
+    assets: Res<WorkshopAssets>,
+    asset_server: Res<AssetServer>,
+    mut changed: EventReader<WorkshopContentChanged>,
+) {
+    for event in changed.read() {
+        for handle in assets.handles(event.id) {
+            if let Some(path) = handle.path() {
+                asset_server.reload(path.clone());
+            }
+        }
+    }
+}
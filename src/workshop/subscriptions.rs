@@ -0,0 +1,160 @@
+use std::time::{Duration, Instant};
+
+use bevy_ecs::{
+    event::{Event, EventWriter},
+    system::{Res, ResMut, Resource},
+};
+use bevy_utils::HashMap;
+use steamworks::{ItemState, PublishedFileId};
+
+use crate::Client;
+
+/// Sent when the [`ItemState`] of a subscribed Workshop item changes.
+///
+/// Items that are unsubscribed are reported one last time with their state
+/// as reported by Steam after unsubscribing.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct WorkshopItemStateChanged {
+    /// The item whose state changed.
+    pub id: PublishedFileId,
+    /// The previously observed state. Empty if the item was newly subscribed.
+    pub old_state: ItemState,
+    /// The current state of the item.
+    pub new_state: ItemState,
+}
+
+/// The Workshop items the current user is subscribed to, along with their last
+/// observed [`ItemState`].
+///
+/// Polling the state of every known item is cheap, but enumerating the full
+/// subscription list is not, so the two are refreshed at separately configurable
+/// intervals.
+#[derive(Resource)]
+pub struct SubscribedWorkshopItems {
+    items: HashMap<PublishedFileId, ItemState>,
+    /// How often the state of every known item is checked.
+    pub state_poll_interval: Duration,
+    /// How often the full list of subscribed items is enumerated.
+    pub rescan_interval: Duration,
+    last_state_poll: Option<Instant>,
+    last_rescan: Option<Instant>,
+    scanned: bool,
+}
+
+impl Default for SubscribedWorkshopItems {
+    fn default() -> Self {
+        Self {
+            items: HashMap::default(),
+            state_poll_interval: Duration::from_secs(1),
+            rescan_interval: Duration::from_secs(10),
+            last_state_poll: None,
+            last_rescan: None,
+            scanned: false,
+        }
+    }
+}
+
+impl SubscribedWorkshopItems {
+    /// Gets the last observed state of a subscribed item.
+    pub fn state(&self, id: PublishedFileId) -> Option<ItemState> {
+        self.items.get(&id).copied()
+    }
+
+    /// Checks if the user is subscribed to the given item.
+    pub fn contains(&self, id: PublishedFileId) -> bool {
+        self.items.contains_key(&id)
+    }
+
+    /// Iterates over every subscribed item and its last observed state.
+    pub fn iter(&self) -> impl Iterator<Item = (PublishedFileId, ItemState)> + '_ {
+        self.items.iter().map(|(id, state)| (*id, *state))
+    }
+
+    /// The number of subscribed items.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Checks if the user isn't subscribed to any items.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub(super) fn insert(&mut self, id: PublishedFileId, state: ItemState) -> Option<ItemState> {
+        self.items.insert(id, state)
+    }
+
+    pub(super) fn remove(&mut self, id: PublishedFileId) -> Option<ItemState> {
+        self.items.remove(&id)
+    }
+
+    /// Forces both the subscription list and item states to be refreshed next frame.
+    pub fn refresh(&mut self) {
+        self.last_state_poll = None;
+        self.last_rescan = None;
+    }
+}
+
+fn elapsed(last: Option<Instant>, now: Instant, interval: Duration) -> bool {
+    last.map_or(true, |last| now.duration_since(last) >= interval)
+}
+
+pub(super) fn track_item_states(
+    client: Res<Client>,
+    mut subscribed: ResMut<SubscribedWorkshopItems>,
+    mut changes: EventWriter<WorkshopItemStateChanged>,
+) {
+    let now = Instant::now();
+    let rescan = elapsed(subscribed.last_rescan, now, subscribed.rescan_interval);
+    let poll = rescan
+        || elapsed(
+            subscribed.last_state_poll,
+            now,
+            subscribed.state_poll_interval,
+        );
+    if !poll {
+        return;
+    }
+
+    let ugc = client.ugc();
+    // The first scan only establishes a baseline.
+    let initial = !subscribed.scanned;
+    subscribed.scanned = true;
+    subscribed.last_state_poll = Some(now);
+    if rescan {
+        subscribed.last_rescan = Some(now);
+        let current = ugc.subscribed_items();
+        let removed: Vec<PublishedFileId> = subscribed
+            .items
+            .keys()
+            .filter(|id| !current.contains(*id))
+            .copied()
+            .collect();
+        for id in removed {
+            if let Some(old_state) = subscribed.items.remove(&id) {
+                changes.send(WorkshopItemStateChanged {
+                    id,
+                    old_state,
+                    new_state: ugc.item_state(id),
+                });
+            }
+        }
+        for id in current {
+            subscribed.items.entry(id).or_insert_with(ItemState::empty);
+        }
+    }
+
+    for (id, old_state) in subscribed.items.iter_mut() {
+        let new_state = ugc.item_state(*id);
+        if new_state != *old_state {
+            if !initial {
+                changes.send(WorkshopItemStateChanged {
+                    id: *id,
+                    old_state: *old_state,
+                    new_state,
+                });
+            }
+            *old_state = new_state;
+        }
+    }
+}
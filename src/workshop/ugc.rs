@@ -0,0 +1,213 @@
+use std::fmt;
+
+use bevy_ecs::{
+    event::{Event, EventWriter},
+    system::{Res, ResMut, Resource},
+};
+use steamworks::{CreateQueryError, ItemState, PublishedFileId, SteamError, SteamId};
+
+use crate::{call_result::CallResults, Client, ShuttingDown};
+
+use super::{SubscribedWorkshopItems, WorkshopItemStateChanged};
+
+/// Sent when a [`SteamUgc::subscribe`] request completes.
+#[derive(Event, Debug)]
+pub struct WorkshopItemSubscribed {
+    /// The item that was subscribed to.
+    pub id: PublishedFileId,
+    /// The result of the request.
+    pub result: Result<(), SteamError>,
+}
+
+/// Sent when a [`SteamUgc::unsubscribe`] request completes.
+#[derive(Event, Debug)]
+pub struct WorkshopItemUnsubscribed {
+    /// The item that was unsubscribed from.
+    pub id: PublishedFileId,
+    /// The result of the request.
+    pub result: Result<(), SteamError>,
+}
+
+/// Sent when a [`SteamUgc::delete_item`] request completes.
+#[derive(Event, Debug)]
+pub struct WorkshopItemDeleted {
+    /// The item that was deleted.
+    pub id: PublishedFileId,
+    /// The result of the request.
+    pub result: Result<(), DeleteItemError>,
+}
+
+/// An error from [`SteamUgc::delete_item`].
+#[derive(Debug)]
+pub enum DeleteItemError {
+    /// The current user does not own the item, so it was not deleted.
+    NotOwner,
+    /// The query used to look up the item's owner could not be created.
+    Query(CreateQueryError),
+    /// Steam failed to look up or delete the item.
+    Steam(SteamError),
+}
+
+impl fmt::Display for DeleteItemError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotOwner => f.write_str("the current user does not own the workshop item"),
+            Self::Query(err) => write!(f, "failed to query the workshop item: {err}"),
+            Self::Steam(err) => write!(f, "failed to delete the workshop item: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for DeleteItemError {}
+
+enum UgcResult {
+    Subscribed(PublishedFileId, Result<(), SteamError>),
+    Unsubscribed(PublishedFileId, Result<(), SteamError>),
+    /// The owner of an item about to be deleted was looked up.
+    OwnerFetched(PublishedFileId, Option<SteamId>),
+    Deleted(PublishedFileId, Result<(), DeleteItemError>),
+}
+
+/// Manages Steam Workshop items from in-game UI.
+///
+/// Every request completes with a Bevy event rather than a closure:
+/// [`WorkshopItemSubscribed`], [`WorkshopItemUnsubscribed`], and [`WorkshopItemDeleted`].
+#[derive(Resource)]
+pub struct SteamUgc {
+    client: Client,
+    results: CallResults<UgcResult>,
+    local: Vec<UgcResult>,
+}
+
+impl SteamUgc {
+    pub(super) fn new(client: &Client) -> Self {
+        Self {
+            client: client.clone(),
+            results: CallResults::new(),
+            local: Vec::new(),
+        }
+    }
+
+    /// Subscribes the current user to an item.
+    ///
+    /// Completes with a [`WorkshopItemSubscribed`] event.
+    pub fn subscribe(&self, id: PublishedFileId) -> Result<(), ShuttingDown> {
+        self.client.ensure_running()?;
+        let results = self.results.clone();
+        self.client.ugc().subscribe_item(id, move |result| {
+            results.push(UgcResult::Subscribed(id, result));
+        });
+        Ok(())
+    }
+
+    /// Unsubscribes the current user from an item.
+    ///
+    /// Completes with a [`WorkshopItemUnsubscribed`] event. On success, the item is
+    /// removed from [`SubscribedWorkshopItems`] immediately.
+    pub fn unsubscribe(&self, id: PublishedFileId) -> Result<(), ShuttingDown> {
+        self.client.ensure_running()?;
+        let results = self.results.clone();
+        self.client.ugc().unsubscribe_item(id, move |result| {
+            results.push(UgcResult::Unsubscribed(id, result));
+        });
+        Ok(())
+    }
+
+    /// Permanently deletes an item owned by the current user.
+    ///
+    /// The item's owner is looked up first and the deletion is refused with
+    /// [`DeleteItemError::NotOwner`] if it isn't the current user. Completes with a
+    /// [`WorkshopItemDeleted`] event.
+    pub fn delete_item(&mut self, id: PublishedFileId) -> Result<(), ShuttingDown> {
+        self.client.ensure_running()?;
+        let query = match self.client.ugc().query_item(id) {
+            Ok(query) => query,
+            Err(err) => {
+                self.local
+                    .push(UgcResult::Deleted(id, Err(DeleteItemError::Query(err))));
+                return Ok(());
+            }
+        };
+        let results = self.results.clone();
+        // Steam can't take new call results while running this closure, so the
+        // deletion itself is requested once the owner reaches `flush_ugc_results`.
+        query.fetch(move |fetched| {
+            results.push(match fetched {
+                Ok(fetched) => UgcResult::OwnerFetched(id, fetched.get(0).map(|item| item.owner)),
+                Err(err) => UgcResult::Deleted(id, Err(DeleteItemError::Steam(err))),
+            });
+        });
+        Ok(())
+    }
+
+    /// Requests the deletion of an item once its owner is known.
+    fn delete_owned_item(
+        &self,
+        id: PublishedFileId,
+        owner: Option<SteamId>,
+    ) -> Result<(), DeleteItemError> {
+        if owner != Some(self.client.user().steam_id()) {
+            return Err(DeleteItemError::NotOwner);
+        }
+        let results = self.results.clone();
+        self.client.ugc().delete_item(id, move |result| {
+            results.push(UgcResult::Deleted(
+                id,
+                result.map_err(DeleteItemError::Steam),
+            ));
+        });
+        Ok(())
+    }
+}
+
+pub(super) fn flush_ugc_results(
+    client: Res<Client>,
+    mut ugc: ResMut<SteamUgc>,
+    mut subscribed_items: ResMut<SubscribedWorkshopItems>,
+    mut subscribed: EventWriter<WorkshopItemSubscribed>,
+    mut unsubscribed: EventWriter<WorkshopItemUnsubscribed>,
+    mut deleted: EventWriter<WorkshopItemDeleted>,
+    mut changes: EventWriter<WorkshopItemStateChanged>,
+) {
+    let mut results = std::mem::take(&mut ugc.local);
+    results.extend(ugc.results.drain());
+    for result in results {
+        match result {
+            UgcResult::Subscribed(id, result) => {
+                if result.is_ok() {
+                    let new_state = client.ugc().item_state(id);
+                    let old_state = subscribed_items.insert(id, new_state);
+                    changes.send(WorkshopItemStateChanged {
+                        id,
+                        old_state: old_state.unwrap_or_else(ItemState::empty),
+                        new_state,
+                    });
+                }
+                subscribed.send(WorkshopItemSubscribed { id, result });
+            }
+            UgcResult::Unsubscribed(id, result) => {
+                if result.is_ok() {
+                    if let Some(old_state) = subscribed_items.remove(id) {
+                        changes.send(WorkshopItemStateChanged {
+                            id,
+                            old_state,
+                            new_state: client.ugc().item_state(id),
+                        });
+                    }
+                }
+                unsubscribed.send(WorkshopItemUnsubscribed { id, result });
+            }
+            UgcResult::OwnerFetched(id, owner) => {
+                if let Err(err) = ugc.delete_owned_item(id, owner) {
+                    deleted.send(WorkshopItemDeleted {
+                        id,
+                        result: Err(err),
+                    });
+                }
+            }
+            UgcResult::Deleted(id, result) => {
+                deleted.send(WorkshopItemDeleted { id, result });
+            }
+        }
+    }
+}
@@ -0,0 +1,257 @@
+//! Tests against a real Steam client, run as Spacewar.
+//!
+//! Skipped unless `STEAM_TESTS=1` is set and Steam is running:
+//!
+//! ```sh
+//! STEAM_TESTS=1 cargo test --features test-utils --test steam
+//! ```
+
+use std::{
+    io::Read,
+    sync::{Mutex, MutexGuard, PoisonError},
+    time::Duration,
+};
+
+use bevy::{prelude::*, time::TimePlugin};
+use bevy_steamworks::*;
+
+/// Steam only allows one client per process, so each test holds this for as long
+/// as its app exists.
+static STEAM: Mutex<()> = Mutex::new(());
+
+/// Builds an app with [`test::steam_app`] once no other test is using Steam.
+///
+/// The guard must outlive the app, so bind it first.
+fn steam_app() -> Option<(MutexGuard<'static, ()>, App)> {
+    let guard = STEAM.lock().unwrap_or_else(PoisonError::into_inner);
+    let Some(app) = test::steam_app() else {
+        eprintln!("Skipping Steam tests, set STEAM_TESTS=1 with Steam running to run them");
+        return None;
+    };
+    Some((guard, app))
+}
+
+#[derive(Resource, Default)]
+struct Received {
+    /// Set by a reader in `First`, so only if events are flushed before it.
+    stats_in_first: bool,
+}
+
+fn read_in_first(mut events: EventReader<SteamworksEvent>, mut received: ResMut<Received>) {
+    for event in events.read() {
+        if let SteamworksEvent::UserStatsReceived(stats) = event {
+            assert!(stats.result.is_ok(), "{:?}", stats.result);
+            received.stats_in_first = true;
+        }
+    }
+}
+
+#[test]
+fn steam_client() {
+    let Some((_steam, mut app)) = steam_app() else {
+        return;
+    };
+    app.init_resource::<Received>()
+        .add_systems(First, read_in_first.after(SteamworksSystem::RunCallbacks));
+
+    let client = app.world().resource::<Client>().clone();
+    for friend in client.friends().get_friends(FriendFlags::IMMEDIATE) {
+        assert_ne!(friend.id().raw(), 0);
+    }
+
+    client.user_stats().request_current_stats();
+    let received = test::pump_until(&mut app, 600, |app| {
+        app.world().resource::<Received>().stats_in_first
+    });
+    assert!(received, "UserStatsReceived was not sent in First");
+}
+
+#[test]
+fn async_cloud_writes_finish_on_exit() {
+    let Some((_steam, mut app)) = steam_app() else {
+        return;
+    };
+    app.update();
+
+    let payload: Vec<u8> = (0..64 * 1024).map(|i| i as u8).collect();
+    let names: Vec<String> = (0..8)
+        .map(|i| format!("bevy_steamworks_test_{i}.sav"))
+        .collect();
+    let saves = app.world().resource::<SteamCloudSaves>();
+    for name in &names {
+        saves.write_async(name.clone(), payload.clone()).unwrap();
+    }
+    // Exit on the same frame, before the writer thread can have caught up.
+    app.world_mut().send_event(AppExit::Success);
+    app.update();
+    assert!(app.world().resource::<SteamShuttingDown>().is_set());
+
+    // Helpers refuse to run once shutting down, so check through the client.
+    let storage = app.world().resource::<Client>().remote_storage();
+    for name in &names {
+        assert!(
+            storage.file(name).exists(),
+            "{name} was not written before exiting"
+        );
+        let mut written = Vec::new();
+        storage.file(name).read().read_to_end(&mut written).unwrap();
+        assert!(written.ends_with(&payload), "{name} was cut short");
+        storage.file(name).delete();
+    }
+}
+
+#[test]
+fn apps_can_be_rebuilt() {
+    for i in 0..20 {
+        // Initializing Steam again fails unless the last app released every client.
+        let Some((_steam, mut app)) = steam_app() else {
+            return;
+        };
+        app.update();
+        // Leave a write in flight, so the writer thread still holds a client when
+        // the app is dropped.
+        app.world()
+            .resource::<SteamCloudSaves>()
+            .write_async(format!("bevy_steamworks_rebuild_{i}.sav"), vec![0; 1024])
+            .unwrap();
+        app.update();
+        drop(app);
+    }
+
+    let Some((_steam, app)) = steam_app() else {
+        return;
+    };
+    let storage = app.world().resource::<Client>().remote_storage();
+    for i in 0..20 {
+        let file = storage.file(&format!("bevy_steamworks_rebuild_{i}.sav"));
+        assert!(file.exists(), "write {i} was lost when its app was dropped");
+        file.delete();
+    }
+}
+
+#[test]
+fn minimal_apps_run_opt_in_plugins() {
+    let _steam = STEAM.lock().unwrap_or_else(PoisonError::into_inner);
+    if !test::steam_tests_enabled() {
+        return;
+    }
+    let plugin = SteamworksPlugin::init_app(test::SPACEWAR_APP_ID)
+        .unwrap()
+        .minimal();
+    let mut app = App::new();
+    app.add_plugins((
+        TimePlugin,
+        WindowPlugin {
+            primary_window: None,
+            ..default()
+        },
+        bevy::input::InputPlugin,
+        plugin,
+    ))
+    .add_plugins((SteamInputPlugin, SteamVoicePlugin));
+    #[cfg(feature = "dev-tools")]
+    app.add_plugins(SteamworksDevToolsPlugin::default());
+
+    // Opt-in plugins must not read events that minimal apps never register.
+    for _ in 0..3 {
+        app.update();
+    }
+    assert!(!app.world().contains_resource::<Events<SteamworksEvent>>());
+    assert!(app.world().contains_resource::<Client>());
+}
+
+#[test]
+fn helpers_refuse_to_call_steam_once_shutting_down() {
+    let Some((_steam, mut app)) = steam_app() else {
+        return;
+    };
+    app.update();
+    let steam_id = app.world().resource::<Client>().user().steam_id();
+
+    app.world_mut().send_event(AppExit::Success);
+    app.update();
+    assert!(app.world().resource::<SteamShuttingDown>().is_set());
+
+    let world = app.world_mut();
+    let saves = world.resource::<SteamCloudSaves>();
+    assert!(matches!(
+        saves.write("bevy_steamworks_exit.sav", b"late"),
+        Err(CloudSaveError::ShuttingDown)
+    ));
+    assert_eq!(
+        saves.write_async("bevy_steamworks_exit.sav", b"late".to_vec()),
+        Err(ShuttingDown)
+    );
+    assert_eq!(saves.set_cloud_enabled_for_app(true), Err(ShuttingDown));
+    assert!(!saves.exists("bevy_steamworks_exit.sav"));
+    assert!(!saves.delete("bevy_steamworks_exit.sav"));
+    assert!(saves.list(None).is_empty());
+    assert_eq!(saves.metadata("bevy_steamworks_exit.sav"), None);
+
+    let mut achievements = world.resource_mut::<SteamAchievements>();
+    assert_eq!(achievements.unlock("ACH_WIN_ONE_GAME"), Err(ShuttingDown));
+    assert_eq!(achievements.is_unlocked("ACH_WIN_ONE_GAME"), None);
+    assert_eq!(achievements.iter_all().count(), 0);
+
+    let mut leaderboards = world.resource_mut::<SteamLeaderboards>();
+    assert_eq!(leaderboards.find("Feet Traveled"), Err(ShuttingDown));
+
+    let friends = world.resource::<SteamFriends>();
+    assert_eq!(friends.display_name(steam_id), Err(ShuttingDown));
+    assert_eq!(friends.nickname(steam_id), None);
+
+    let mut auth = world.resource_mut::<SteamAuth>();
+    assert_eq!(
+        auth.request_webapi_ticket("bevy_steamworks"),
+        Err(ShuttingDown)
+    );
+
+    let ugc = world.resource::<SteamUgc>();
+    assert_eq!(ugc.subscribe(PublishedFileId(1)), Err(ShuttingDown));
+
+    let mut screenshots = world.resource_mut::<SteamScreenshots>();
+    assert_eq!(screenshots.trigger(), Err(ShuttingDown));
+    assert_eq!(
+        screenshots.trigger_tagged(&[steam_id], "test"),
+        Err(ShuttingDown)
+    );
+    assert!(!screenshots.is_hooked());
+
+    let mut lobby = world.resource_mut::<CurrentLobby>();
+    assert_eq!(lobby.leave(), Err(ShuttingDown));
+
+    let mut rich_presence = world.resource_mut::<RichPresence>();
+    assert!(!rich_presence.set("status", "Exiting"));
+    assert_eq!(rich_presence.clear_all(), Err(ShuttingDown));
+}
+
+#[test]
+fn stats_are_stored_on_exit() {
+    let Some((_steam, mut app)) = steam_app() else {
+        return;
+    };
+    let ready = test::pump_until(&mut app, 600, |app| {
+        app.world().resource::<SteamStats>().is_ready()
+    });
+    assert!(ready, "the current user's stats were not received");
+
+    let games = app
+        .world_mut()
+        .resource_mut::<SteamStats>()
+        .add_i32("NumGames", 1)
+        .unwrap();
+    // Exit before `store_stats` runs, so only the exit system stores the change.
+    app.world_mut().send_event(AppExit::Success);
+    let mut events = app
+        .world()
+        .resource::<Events<SteamworksEvent>>()
+        .get_reader_current();
+    app.update();
+
+    let stored = events
+        .read(app.world().resource::<Events<SteamworksEvent>>())
+        .any(|event| matches!(event, SteamworksEvent::UserStatsStored(_)));
+    assert!(stored, "UserStatsStored was not forwarded while exiting");
+    let client = app.world().resource::<Client>();
+    assert_eq!(client.user_stats().get_stat_i32("NumGames"), Ok(games));
+}